@@ -0,0 +1,609 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::http::StatusCode;
+use tokio::{
+    sync::{Mutex as AsyncMutex, watch},
+    task::AbortHandle,
+};
+use tracing::{info, warn};
+
+use crate::{
+    config::{AppConfig, ConfigErrors, env_vars, validate_source},
+    data_processor::DataProcessorReader,
+    dto::ReloadResponse,
+    exchange::SourceUrl,
+    indicator::IndicatorRegistry,
+    models::{SharedMap, SourceStatus},
+    rpc_manager::RpcManager,
+    source::{PriceSource, SourceError, build_source},
+    utils::now_millis,
+};
+
+/// Error of an admin request.
+#[derive(Debug)]
+pub enum AdminError {
+    /// The URL does not describe a supported source, and why
+    InvalidSource(String),
+    /// These symbols are already tracked
+    AlreadyTracked(Vec<String>),
+    /// The symbol is not tracked
+    NotTracked(String),
+    /// The symbol's source also feeds other symbols (batched or combined), so it
+    /// cannot be stopped on its own
+    Shared(String),
+    /// Seeding the new source failed
+    Source(SourceError),
+}
+
+impl AdminError {
+    /// HTTP status the error is reported with.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidSource(_) => StatusCode::BAD_REQUEST,
+            Self::AlreadyTracked(_) | Self::Shared(_) => StatusCode::CONFLICT,
+            Self::NotTracked(_) => StatusCode::NOT_FOUND,
+            Self::Source(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSource(reason) => write!(f, "invalid source: {reason}"),
+            Self::AlreadyTracked(symbols) => write!(f, "already tracked: {symbols:?}"),
+            Self::NotTracked(symbol) => write!(f, "symbol {symbol} is not tracked"),
+            Self::Shared(symbol) => {
+                write!(f, "symbol {symbol} shares its source with other symbols")
+            }
+            Self::Source(e) => write!(f, "seeding failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+/// Adds and removes tracked symbols while the service is running.
+///
+/// Every symbol gets its own source, seeded and spawned like the configured ones,
+/// and its reader and status are published to the maps shared with the HTTP
+/// handlers. Only symbols whose source feeds nothing else can be removed: the
+/// source's task is aborted and the symbol disappears from `/stats` and `/health`.
+///
+/// The admin also owns the current configuration: [`SymbolAdmin::reload`] applies
+/// a new one by diffing it against the current one.
+pub struct SymbolAdmin {
+    /// Current configuration, followed by every running source
+    config: watch::Sender<Arc<AppConfig>>,
+    /// Readers served by `/stats`
+    readers: Arc<SharedMap<DataProcessorReader>>,
+    /// Statuses served by `/health`
+    statuses: Arc<SharedMap<Arc<SourceStatus>>>,
+    /// Symbol -> task of the source feeding only that symbol
+    tasks: Mutex<HashMap<String, AbortHandle>>,
+    /// Serializes reloads
+    reloading: AsyncMutex<()>,
+    /// Outcome of the latest reload
+    last_reload: Mutex<Option<ReloadResponse>>,
+    /// Environment variables reloads read, the process environment over `.env`
+    /// unless replaced with [`SymbolAdmin::with_env`]
+    env: fn() -> HashMap<String, String>,
+    /// Indicators reloaded settings resolve against, set with
+    /// [`SymbolAdmin::with_registry`]
+    registry: IndicatorRegistry,
+}
+
+impl SymbolAdmin {
+    /// Creates the admin over the shared maps; `tasks` are the removable symbols
+    /// started at boot and `config` publishes the configuration they follow.
+    pub fn new(
+        config: watch::Sender<Arc<AppConfig>>,
+        readers: Arc<SharedMap<DataProcessorReader>>,
+        statuses: Arc<SharedMap<Arc<SourceStatus>>>,
+        tasks: HashMap<String, AbortHandle>,
+    ) -> Self {
+        Self {
+            config,
+            readers,
+            statuses,
+            tasks: Mutex::new(tasks),
+            reloading: AsyncMutex::new(()),
+            last_reload: Mutex::new(None),
+            env: env_vars,
+            registry: IndicatorRegistry::default(),
+        }
+    }
+
+    /// Makes reloads resolve `INDICATORS` against `registry`, the one the
+    /// configuration at startup was loaded with.
+    pub fn with_registry(mut self, registry: IndicatorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Makes reloads read their environment variables from `env`, e.g. a fixed map
+    /// in tests.
+    pub fn with_env(mut self, env: fn() -> HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// The current configuration.
+    pub fn config(&self) -> Arc<AppConfig> {
+        self.config.borrow().clone()
+    }
+
+    /// Bearer token required by the admin endpoints, `None` while they are disabled.
+    pub fn token(&self) -> Option<String> {
+        self.config.borrow().admin_token.clone()
+    }
+
+    /// Starts tracking the source at `url` (same format as an entry of `URLS`).
+    ///
+    /// Returns the symbols it tracks.
+    pub async fn add(&self, url: &str) -> Result<Vec<String>, AdminError> {
+        self.add_url(&SourceUrl::parse(url)).await
+    }
+
+    /// Starts tracking the source at `source_url`.
+    async fn add_url(&self, source_url: &SourceUrl) -> Result<Vec<String>, AdminError> {
+        let config = self.config();
+        validate_source(source_url, &config.providers)
+            .map_err(|e| AdminError::InvalidSource(e.to_string()))?;
+        let source = build_source(&config, source_url)
+            .ok_or_else(|| AdminError::InvalidSource(format!("URL {}", source_url.url)))?;
+        self.add_source(source).await
+    }
+
+    /// Seeds `source`, spawns a manager for it and publishes its symbols.
+    ///
+    /// Symbols already tracked are rejected: combining sources into a consensus is
+    /// only done at startup.
+    pub async fn add_source(
+        &self,
+        source: Box<dyn PriceSource>,
+    ) -> Result<Vec<String>, AdminError> {
+        self.ensure_untracked(source.symbols())?;
+
+        let config = self.config();
+        let name = source.name().to_string();
+        let (handles, task) = RpcManager::spawn_cancellable(
+            source,
+            &config.stats,
+            config.retry_policy,
+            config.breaker_policy,
+            Some(self.config.subscribe()),
+        )
+        .await
+        .map_err(AdminError::Source)?;
+
+        let symbols: Vec<String> = handles.iter().map(|(s, _, _)| s.clone()).collect();
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        // Seeding takes a while; another request may have added a symbol meanwhile
+        if let Err(e) = self.ensure_untracked(&symbols) {
+            task.abort();
+            return Err(e);
+        }
+        self.readers.update(|readers| {
+            for (symbol, reader, _) in &handles {
+                readers.insert(symbol.clone(), reader.clone());
+            }
+        });
+        self.statuses.update(|statuses| {
+            for (symbol, _, status) in &handles {
+                statuses.insert(symbol.clone(), status.clone());
+            }
+        });
+        match symbols.as_slice() {
+            [symbol] => {
+                tasks.insert(symbol.clone(), task);
+            }
+            // A discovering source may report several symbols, which then share it
+            _ => drop(task),
+        }
+
+        info!("Added {:?} from {}", symbols, name);
+        Ok(symbols)
+    }
+
+    /// Stops tracking `symbol`.
+    pub fn remove(&self, symbol: &str) -> Result<(), AdminError> {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(task) = tasks.remove(symbol) else {
+            return Err(match self.readers.get(symbol) {
+                Some(_) => AdminError::Shared(symbol.to_string()),
+                None => AdminError::NotTracked(symbol.to_string()),
+            });
+        };
+
+        task.abort();
+        self.readers.update(|readers| readers.remove(symbol));
+        self.statuses.update(|statuses| statuses.remove(symbol));
+        info!("Removed {}", symbol);
+        Ok(())
+    }
+
+    /// Applies `new` in place of the current configuration.
+    ///
+    /// Sources missing from `new` are removed and new ones are added. Running
+    /// sources pick up new intervals, timeouts, retry, breaker and consensus
+    /// settings, and `/ready` and `/health` the new required symbols and unhealthy
+    /// fraction. Symbols whose statistics settings (SMA window, windows,
+    /// indicators, ...) changed switch to them in place: unchanged statistics keep
+    /// their state and the report lists how each change is rebuilt from it (see
+    /// `StatsConfig::changes_from`). Changes that cannot be applied live (shared
+    /// sources, batched polling, providers, the watch interval, IP and port) are
+    /// reported in `errors`.
+    pub async fn reload(&self, new: AppConfig) -> ReloadResponse {
+        let _reloading = self.reloading.lock().await;
+        let old = self.config();
+        let new = Arc::new(new);
+        let mut report = ReloadResponse::new(now_millis());
+
+        // Sources removed from the configuration
+        for url in old.urls.iter().filter(|url| !new.urls.contains(url)) {
+            match url.symbol() {
+                Some(symbol) => match self.remove(&symbol) {
+                    Ok(()) => report.removed.push(symbol),
+                    Err(e) => report.errors.push(e.to_string()),
+                },
+                None => report
+                    .errors
+                    .push(format!("cannot remove {}: no symbol", url.url)),
+            }
+        }
+
+        // Running sources follow the new intervals, timeouts and policies; the HTTP
+        // handlers read the current configuration on every request
+        let reconfigured = old.interval != new.interval
+            || old.symbol_intervals != new.symbol_intervals
+            || old.timeout != new.timeout
+            || old.ws_idle_timeout != new.ws_idle_timeout
+            || old.retry_policy != new.retry_policy
+            || old.breaker_policy != new.breaker_policy
+            || old.consensus_policy != new.consensus_policy
+            || old.stats != new.stats;
+        self.config.send_if_modified(|current| {
+            *current = new.clone();
+            reconfigured
+        });
+        report.reconfigured = reconfigured;
+
+        // Writers of the remaining symbols switch their statistics on the update
+        for symbol in self.readers.load().keys() {
+            let stats = new.stats.for_symbol(symbol);
+            let changes = stats.changes_from(&old.stats.for_symbol(symbol));
+            if !changes.is_empty() {
+                report.statistics.insert(symbol.clone(), changes);
+            }
+        }
+
+        // Sources added to the configuration
+        for url in new.urls.iter().filter(|url| !old.urls.contains(url)) {
+            match self.add_url(url).await {
+                Ok(symbols) => report.added.extend(symbols),
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+
+        if old.batch_poll != new.batch_poll {
+            report
+                .errors
+                .push("BATCH_POLL changes need a restart".to_string());
+        }
+        if old.providers != new.providers {
+            report
+                .errors
+                .push("PROVIDER_* changes only apply to sources added afterwards".to_string());
+        }
+        if old.config_watch != new.config_watch {
+            report
+                .errors
+                .push("CONFIG_WATCH_INTERVAL changes need a restart".to_string());
+        }
+        if old.ip != new.ip || old.port != new.port {
+            report
+                .errors
+                .push("IP and PORT changes need a restart".to_string());
+        }
+        self.record_reload(report.finish())
+    }
+
+    /// Reloads the configuration from the environment, `.env` and the config file
+    /// read at startup.
+    pub async fn reload_from_env(&self) -> ReloadResponse {
+        let config_file = self.config().config_file.clone();
+        match AppConfig::from_parts(&(self.env)(), config_file, &self.registry) {
+            Ok(config) => self.reload(config).await,
+            Err(ConfigErrors(errors)) => {
+                let errors = errors.iter().map(ToString::to_string).collect();
+                self.record_reload(ReloadResponse::failed(now_millis(), errors))
+            }
+        }
+    }
+
+    /// Outcome of the latest reload, if any.
+    pub fn last_reload(&self) -> Option<ReloadResponse> {
+        self.last_reload
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Logs and stores the outcome of a reload.
+    fn record_reload(&self, report: ReloadResponse) -> ReloadResponse {
+        if report.errors.is_empty() {
+            info!(
+                "Reloaded configuration: added {:?}, removed {:?}, statistics {:?}",
+                report.added, report.removed, report.statistics
+            );
+        } else {
+            warn!(
+                "Reloaded configuration ({}): added {:?}, removed {:?}, statistics {:?}, errors {:?}",
+                report.status, report.added, report.removed, report.statistics, report.errors
+            );
+        }
+        *self.last_reload.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+        report
+    }
+
+    /// Fails with `AlreadyTracked` if one of `symbols` is tracked.
+    fn ensure_untracked(&self, symbols: &[String]) -> Result<(), AdminError> {
+        let readers = self.readers.load();
+        let tracked: Vec<String> = symbols
+            .iter()
+            .filter(|s| readers.contains_key(*s))
+            .cloned()
+            .collect();
+        if tracked.is_empty() {
+            Ok(())
+        } else {
+            Err(AdminError::AlreadyTracked(tracked))
+        }
+    }
+}
+
+#[cfg(test)]
+mod admin_tests {
+    use super::*;
+    use crate::{
+        consensus::{ConsensusMethod, ConsensusPolicy},
+        data_processor::{EmaSpec, StatsConfig},
+        indicator::RateOfChange,
+        source::{ChannelSource, PriceUpdate},
+    };
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    fn admin_with(config: AppConfig) -> SymbolAdmin {
+        SymbolAdmin::new(
+            watch::channel(Arc::new(config)).0,
+            Arc::new(SharedMap::new(HashMap::new())),
+            Arc::new(SharedMap::new(HashMap::new())),
+            HashMap::new(),
+        )
+    }
+
+    fn admin() -> SymbolAdmin {
+        admin_with(AppConfig::for_tests())
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_symbol() {
+        let admin = admin();
+        let (source, sender) = ChannelSource::new("push".into(), vec!["SOLUSDT".into()], 4);
+        sender
+            .send(PriceUpdate::new("SOLUSDT", 150.0, None))
+            .await
+            .unwrap();
+
+        let added = admin.add_source(Box::new(source)).await.unwrap();
+        assert_eq!(added, vec!["SOLUSDT".to_string()]);
+        assert_eq!(admin.readers.get("SOLUSDT").unwrap().read().data.max, 150.0);
+        assert!(admin.statuses.get("SOLUSDT").is_some());
+
+        // A second source for the same symbol is rejected before seeding
+        let (again, _again_tx) = ChannelSource::new("again".into(), vec!["SOLUSDT".into()], 4);
+        let err = admin.add_source(Box::new(again)).await.unwrap_err();
+        assert!(matches!(err, AdminError::AlreadyTracked(_)));
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+
+        admin.remove("SOLUSDT").unwrap();
+        assert!(admin.readers.get("SOLUSDT").is_none());
+        assert!(admin.statuses.get("SOLUSDT").is_none());
+        // The aborted manager drops the source
+        timeout(Duration::from_secs(2), async {
+            while !sender.is_closed() {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let err = admin.remove("SOLUSDT").unwrap_err();
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_shared_source_and_invalid_url_are_rejected() {
+        let admin = admin();
+        let symbols = vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+        let (source, sender) = ChannelSource::new("push".into(), symbols, 4);
+        for (symbol, price) in [("BTCUSDT", 100.0), ("ETHUSDT", 10.0)] {
+            sender
+                .send(PriceUpdate::new(symbol, price, None))
+                .await
+                .unwrap();
+        }
+        admin.add_source(Box::new(source)).await.unwrap();
+
+        let err = admin.remove("BTCUSDT").unwrap_err();
+        assert!(matches!(err, AdminError::Shared(_)));
+        assert!(admin.readers.get("BTCUSDT").is_some());
+
+        let err = admin
+            .add("https://example.com/no-symbol")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_reload_removes_sources_and_pushes_intervals() {
+        let sol = SourceUrl::parse("https://api.binance.com/api/v3/ticker/price?symbol=SOLUSDT");
+        let admin = admin_with(AppConfig {
+            urls: vec![sol],
+            ..AppConfig::for_tests()
+        });
+        let (source, sender) = ChannelSource::new("push".into(), vec!["SOLUSDT".into()], 4);
+        sender
+            .send(PriceUpdate::new("SOLUSDT", 150.0, None))
+            .await
+            .unwrap();
+        admin.add_source(Box::new(source)).await.unwrap();
+        let mut updates = admin.config.subscribe();
+
+        let report = admin
+            .reload(AppConfig {
+                interval: Duration::from_millis(50),
+                port: 9000,
+                ..AppConfig::for_tests()
+            })
+            .await;
+        assert_eq!(report.removed, vec!["SOLUSDT".to_string()]);
+        assert!(report.reconfigured);
+        assert_eq!(report.status, "partial");
+        assert_eq!(report.errors.len(), 1);
+        assert!(admin.readers.get("SOLUSDT").is_none());
+        assert!(updates.has_changed().unwrap());
+        assert_eq!(
+            updates.borrow_and_update().interval,
+            Duration::from_millis(50)
+        );
+        assert_eq!(admin.last_reload().unwrap().removed, report.removed);
+
+        // Nothing changed: sources are not woken up
+        let report = admin
+            .reload(AppConfig {
+                interval: Duration::from_millis(50),
+                port: 9000,
+                ..AppConfig::for_tests()
+            })
+            .await;
+        assert!(!report.reconfigured);
+        assert_eq!(report.status, "ok");
+        assert!(!updates.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reload_pushes_policies_and_reports_startup_settings() {
+        let admin = admin();
+        let mut updates = admin.config.subscribe();
+
+        let report = admin
+            .reload(AppConfig {
+                consensus_policy: ConsensusPolicy {
+                    method: ConsensusMethod::TrimmedMean,
+                    ..ConsensusPolicy::default()
+                },
+                ready_symbols: vec!["BTCUSDT".into()],
+                batch_poll: true,
+                ..AppConfig::for_tests()
+            })
+            .await;
+        assert!(report.reconfigured);
+        assert_eq!(report.errors, vec!["BATCH_POLL changes need a restart"]);
+        assert!(updates.has_changed().unwrap());
+        let current = updates.borrow_and_update().clone();
+        assert_eq!(
+            current.consensus_policy.method,
+            ConsensusMethod::TrimmedMean
+        );
+        assert_eq!(current.ready_symbols, vec!["BTCUSDT".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reload_resolves_indicators_against_the_registry() {
+        const BTC: &str = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
+        let mut registry = IndicatorRegistry::default();
+        registry.register("momentum", RateOfChange::factory);
+        let admin = admin_with(AppConfig {
+            urls: vec![SourceUrl::parse(BTC)],
+            ..AppConfig::for_tests()
+        })
+        .with_env(|| {
+            let vars = [
+                ("URLS", BTC),
+                ("INTERVAL", "100"),
+                ("SMA_N", "2"),
+                ("INDICATORS", "momentum(3)"),
+            ];
+            vars.into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        });
+
+        // The built-in indicators do not include the custom one
+        let report = admin.reload_from_env().await;
+        assert_eq!(report.status, "failed");
+        assert!(admin.config().stats.indicators.is_empty());
+
+        let admin = admin.with_registry(registry);
+        let report = admin.reload_from_env().await;
+        assert_ne!(report.status, "failed", "{:?}", report.errors);
+        let indicators = &admin.config().stats.indicators;
+        assert_eq!(indicators[0].to_string(), "momentum(3)");
+    }
+
+    #[tokio::test]
+    async fn test_reload_switches_statistics_of_running_symbols() {
+        let admin = admin();
+        let (source, sender) = ChannelSource::new("push".into(), vec!["SOLUSDT".into()], 4);
+        for price in [150.0, 151.0, 152.0] {
+            sender
+                .send(PriceUpdate::new("SOLUSDT", price, None))
+                .await
+                .unwrap();
+        }
+        admin.add_source(Box::new(source)).await.unwrap();
+        let reader = admin.readers.get("SOLUSDT").unwrap();
+        timeout(Duration::from_secs(2), async {
+            while reader.read().data.data_point < 3 {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let report = admin
+            .reload(AppConfig {
+                stats: StatsConfig::new(2).with_emas(vec![EmaSpec::Span(2)]),
+                ..AppConfig::for_tests()
+            })
+            .await;
+        assert!(report.reconfigured);
+        assert_eq!(report.status, "ok");
+        assert_eq!(
+            report.statistics["SOLUSDT"],
+            vec!["ema_2 added: seeded from the SMA window"]
+        );
+
+        // The same reader picks up the new statistics without losing the history
+        timeout(Duration::from_secs(2), async {
+            while reader.latest().ema_specs().is_empty() {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let snap = reader.read();
+        assert_eq!(snap.data.data_point, 3);
+        assert_eq!((snap.data.min, snap.data.max), (150.0, 152.0));
+        assert_eq!(snap.emas.len(), 1);
+        assert!(admin.readers.get("SOLUSDT").is_some());
+    }
+}
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use reqwest::{Client, Error};
+use tokio::time::{Interval, MissedTickBehavior, interval};
+
+use crate::{
+    config::AppConfig,
+    dto::{BinancePrice, GetPrice},
+    source::{PriceSource, PriceUpdate, SourceFuture},
+    utils::retune_ticker,
+};
+
+/// Builds a Binance multi-symbol URL, e.g.
+/// `https://api.binance.com/api/v3/ticker/price?symbols=["BTCUSDT","ETHUSDT"]`.
+///
+/// # Example
+/// ```
+/// use aboss_task::batch_manager::batch_url;
+/// let url = batch_url("https://api.binance.com/api/v3/ticker/price", ["BTCUSDT", "ETHUSDT"]);
+/// assert_eq!(
+///     url,
+///     r#"https://api.binance.com/api/v3/ticker/price?symbols=["BTCUSDT","ETHUSDT"]"#
+/// );
+/// ```
+pub fn batch_url<I, S>(base: &str, symbols: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let quoted: Vec<String> = symbols
+        .into_iter()
+        .map(|s| format!("\"{}\"", s.as_ref()))
+        .collect();
+    format!("{}?symbols=[{}]", base, quoted.join(","))
+}
+
+/// Polls the prices of many symbols with a single request per interval.
+///
+/// Uses Binance's multi-symbol `ticker/price?symbols=[...]` endpoint and returns one
+/// `PriceUpdate` per returned `BinancePrice`, so request weight no longer grows with
+/// one request per symbol. `RpcManager` fans the updates out by `symbol` and flags
+/// requested symbols missing from a response.
+pub struct BatchPoller {
+    /// Full multi-symbol URL.
+    url: String,
+
+    /// Requested symbols.
+    symbols: Vec<String>,
+
+    /// Reqwest client used for HTTP requests.
+    client_manager: Client,
+
+    /// Paces the requests; the first tick completes immediately.
+    ticker: Interval,
+}
+
+impl BatchPoller {
+    /// Constructs a new `BatchPoller`.
+    ///
+    /// # Parameters
+    /// - `interval_duration`: Duration between HTTP requests.
+    /// - `base`: Endpoint without query, e.g. `https://api.binance.com/api/v3/ticker/price`.
+    /// - `symbols`: Symbols to request.
+    /// - `client_manager`: Reqwest client to perform requests.
+    pub fn new(
+        interval_duration: Duration,
+        base: &str,
+        symbols: Vec<String>,
+        client_manager: Client,
+    ) -> Self {
+        let mut ticker = interval(interval_duration);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            url: batch_url(base, &symbols),
+            symbols,
+            client_manager,
+            ticker,
+        }
+    }
+
+    /// Sends a single multi-symbol request.
+    pub async fn send_reqwest(
+        client_manager: &Client,
+        url: &str,
+    ) -> Result<Vec<BinancePrice>, Error> {
+        let res = client_manager.get(url).send().await?;
+        res.json::<Vec<BinancePrice>>().await
+    }
+}
+
+impl PriceSource for BatchPoller {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    fn expects_all_symbols(&self) -> bool {
+        true
+    }
+
+    fn next_prices(&mut self) -> SourceFuture<'_> {
+        Box::pin(async move {
+            self.ticker.tick().await;
+            let prices = Self::send_reqwest(&self.client_manager, &self.url).await?;
+            Ok(prices
+                .into_iter()
+                .map(|p| {
+                    let (price, decimal) = (p.get_price(), p.get_decimal());
+                    PriceUpdate::new(p.symbol, price, None).with_decimal(decimal)
+                })
+                .collect())
+        })
+    }
+
+    fn reconfigure(&mut self, config: &AppConfig) {
+        retune_ticker(&mut self.ticker, config.interval);
+        self.client_manager = config.client.clone();
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use crate::{
+        circuit_breaker::BreakerPolicy,
+        data_processor::{DataProcessor, DataProcessorReader},
+        models::SourceStatus,
+        retry::RetryPolicy,
+        rpc_manager::RpcManager,
+        source::PriceSink,
+    };
+    use std::{collections::HashMap, sync::Arc};
+
+    type Outputs = HashMap<String, (DataProcessorReader, Arc<SourceStatus>)>;
+
+    fn price(symbol: &str, price: f64) -> PriceUpdate {
+        PriceUpdate::new(symbol, price, None)
+    }
+
+    /// `RpcManager` driving a `BatchPoller` that is never polled
+    fn manager(symbols: &[&str]) -> (RpcManager, Outputs) {
+        let mut outputs = HashMap::new();
+        let mut readers = HashMap::new();
+        for symbol in symbols {
+            let (reader, writer) = DataProcessor::split(2, 1.0);
+            let status = Arc::new(SourceStatus::default());
+            let sink: Box<dyn PriceSink> = Box::new(writer);
+            outputs.insert(symbol.to_string(), (sink, status.clone()));
+            readers.insert(symbol.to_string(), (reader, status));
+        }
+        let source = BatchPoller::new(
+            Duration::from_secs(1),
+            "http://localhost/api/v3/ticker/price",
+            symbols.iter().map(|s| s.to_string()).collect(),
+            Client::new(),
+        );
+        let manager = RpcManager::new(
+            Box::new(source),
+            outputs,
+            RetryPolicy::default(),
+            BreakerPolicy::default(),
+        );
+        (manager, readers)
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_routes_by_symbol() {
+        let (manager, readers) = manager(&["BTCUSDT", "ETHUSDT"]);
+        manager.fan_out(vec![price("ETHUSDT", 4400.0), price("BTCUSDT", 117000.0)]);
+
+        let btc = readers["BTCUSDT"].0.read();
+        let eth = readers["ETHUSDT"].0.read();
+        assert_eq!(btc.version, 1);
+        assert_eq!(btc.data.max, 117000.0);
+        assert_eq!(eth.version, 1);
+        assert_eq!(eth.data.max, 4400.0);
+        assert!(readers.values().all(|(_, s)| s.failure_streak() == 0));
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_ignores_unrequested_and_flags_missing() {
+        let (manager, readers) = manager(&["BTCUSDT", "ETHUSDT"]);
+        manager.fan_out(vec![price("BTCUSDT", 2.0), price("SOLUSDT", 188.0)]);
+        manager.fan_out(vec![price("BTCUSDT", 3.0)]);
+
+        assert_eq!(readers["BTCUSDT"].0.read().version, 2);
+        assert_eq!(readers["ETHUSDT"].0.read().version, 0);
+
+        let eth_status = &readers["ETHUSDT"].1;
+        assert_eq!(eth_status.failure_streak(), 2);
+        assert_eq!(
+            eth_status.last_error().as_deref(),
+            Some("missing from source response")
+        );
+        assert_eq!(readers["BTCUSDT"].1.failure_streak(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_poller_reports_requested_symbols() {
+        let poller = BatchPoller::new(
+            Duration::from_secs(1),
+            "http://localhost/api/v3/ticker/price",
+            vec!["BTCUSDT".into(), "ETHUSDT".into()],
+            Client::new(),
+        );
+        assert!(poller.expects_all_symbols());
+        assert_eq!(poller.symbols(), ["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(
+            poller.name(),
+            r#"http://localhost/api/v3/ticker/price?symbols=["BTCUSDT","ETHUSDT"]"#
+        );
+    }
+
+    #[test]
+    fn test_batched_response_fixture() {
+        let json =
+            r#"[{"symbol":"BTCUSDT","price":"117454.33"},{"symbol":"ETHUSDT","price":"4418.20"}]"#;
+        let prices: Vec<BinancePrice> = serde_json::from_str(json).unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[1].symbol, "ETHUSDT");
+        assert_eq!(prices[1].get_price(), 4418.20);
+    }
+}
@@ -0,0 +1,284 @@
+use std::{collections::VecDeque, mem, sync::Mutex};
+
+use crate::window::WindowSpec;
+
+/// Default candle resolutions kept per symbol when `CANDLES` is not set.
+pub const DEFAULT_CANDLES: &str = "1m,5m,15m,1h";
+/// Default number of candles retained per symbol and resolution.
+pub const DEFAULT_CANDLE_LIMIT: usize = 1000;
+
+/// One OHLCV candle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    /// Start of the candle in Unix milliseconds, a multiple of the resolution
+    pub open_time: u64,
+    /// First price of the candle
+    pub open: f64,
+    /// Highest price of the candle
+    pub high: f64,
+    /// Lowest price of the candle
+    pub low: f64,
+    /// Latest price of the candle
+    pub close: f64,
+    /// Traded base volume reported with the prices
+    pub volume: f64,
+    /// Traded quote volume, `sum(price * quantity)`
+    pub quote_volume: f64,
+    /// Number of prices aggregated
+    pub trades: u64,
+}
+
+impl Candle {
+    /// Candle opened at `open_time` by `price`.
+    fn open(open_time: u64, price: f64, quantity: f64) -> Self {
+        Self {
+            open_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: quantity,
+            quote_volume: price * quantity,
+            trades: 1,
+        }
+    }
+
+    /// Candle without prices carrying `close` forward, like exchanges do for quiet
+    /// intervals.
+    fn flat(open_time: u64, close: f64) -> Self {
+        Self {
+            open_time,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0.0,
+            quote_volume: 0.0,
+            trades: 0,
+        }
+    }
+}
+
+/// Candles of one resolution, oldest first, bounded to `limit` entries.
+pub(crate) struct CandleSeries {
+    /// Resolution of the candles
+    interval: WindowSpec,
+    /// Resolution in milliseconds
+    span: u64,
+    /// Maximum number of candles retained
+    limit: usize,
+    /// Retained candles, the last one is still open
+    candles: VecDeque<Candle>,
+}
+
+impl CandleSeries {
+    /// Creates the series with a first candle opened by `price` at `at`.
+    pub(crate) fn new(interval: WindowSpec, limit: usize, at: u64, price: f64) -> Self {
+        assert!(limit > 0, "candle limit must be > 0");
+        let span = interval.millis();
+        let mut candles = VecDeque::with_capacity(limit);
+        candles.push_back(Candle::open(at - at % span, price, 0.0));
+        Self {
+            interval,
+            span,
+            limit,
+            candles,
+        }
+    }
+
+    /// Adds `price` traded at `at`, opening new candles as needed.
+    ///
+    /// Intervals without prices since the last candle get flat candles; `at` older
+    /// than the open candle counts towards the open candle.
+    pub(crate) fn push(&mut self, at: u64, price: f64, quantity: f64) {
+        let bucket = at - at % self.span;
+        let last = self.candles.back_mut().expect("series is never empty");
+        if bucket <= last.open_time {
+            last.high = last.high.max(price);
+            last.low = last.low.min(price);
+            last.close = price;
+            last.volume += quantity;
+            last.quote_volume += price * quantity;
+            last.trades += 1;
+            return;
+        }
+
+        // Only the last `limit - 1` quiet candles can still be retained
+        let (last_open, close) = (last.open_time, last.close);
+        let gap = (bucket - last_open) / self.span - 1;
+        let skip = gap.saturating_sub(self.limit as u64 - 1);
+        for i in skip..gap {
+            self.append(Candle::flat(last_open + (i + 1) * self.span, close));
+        }
+        self.append(Candle::open(bucket, price, quantity));
+    }
+
+    /// The last `limit` candles, oldest first.
+    pub(crate) fn latest(&self, limit: usize) -> Vec<Candle> {
+        let skip = self.candles.len().saturating_sub(limit);
+        self.candles.iter().skip(skip).copied().collect()
+    }
+
+    /// Retains at most `limit` candles from now on, dropping the oldest ones.
+    fn set_limit(&mut self, limit: usize) {
+        assert!(limit > 0, "candle limit must be > 0");
+        self.limit = limit;
+        let excess = self.candles.len().saturating_sub(limit);
+        self.candles.drain(..excess);
+    }
+
+    fn append(&mut self, candle: Candle) {
+        if self.candles.len() == self.limit {
+            self.candles.pop_front();
+        }
+        self.candles.push_back(candle);
+    }
+}
+
+/// Candle series of every configured resolution of one symbol.
+///
+/// Written by the symbol's single writer and read by the HTTP handlers; the
+/// history is variable-sized, so unlike the statistics it sits behind a mutex.
+pub(crate) struct CandleHistory {
+    series: Mutex<Vec<CandleSeries>>,
+}
+
+impl CandleHistory {
+    /// Creates one series per `intervals`, each seeded with `price` at `at`.
+    pub(crate) fn new(intervals: &[WindowSpec], limit: usize, at: u64, price: f64) -> Self {
+        let series = intervals
+            .iter()
+            .map(|interval| CandleSeries::new(*interval, limit, at, price))
+            .collect();
+        Self {
+            series: Mutex::new(series),
+        }
+    }
+
+    /// A history of `intervals` retaining `limit` candles each, continuing the
+    /// series of the intervals this one already tracks, which are moved out of it;
+    /// the others are seeded with `price` at `at`.
+    pub(crate) fn reconfigured(
+        &self,
+        intervals: &[WindowSpec],
+        limit: usize,
+        at: u64,
+        price: f64,
+    ) -> Self {
+        let mut current = mem::take(&mut *self.series.lock().unwrap_or_else(|e| e.into_inner()));
+        let series = intervals
+            .iter()
+            .map(
+                |interval| match current.iter().position(|s| s.interval == *interval) {
+                    Some(i) => {
+                        let mut kept = current.swap_remove(i);
+                        kept.set_limit(limit);
+                        kept
+                    }
+                    None => CandleSeries::new(*interval, limit, at, price),
+                },
+            )
+            .collect();
+        Self {
+            series: Mutex::new(series),
+        }
+    }
+
+    /// Adds `price` traded at `at` to every series.
+    pub(crate) fn push(&self, at: u64, price: f64, quantity: f64) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        for s in series.iter_mut() {
+            s.push(at, price, quantity);
+        }
+    }
+
+    /// The last `limit` candles of `interval`, or `None` if it is not tracked.
+    pub(crate) fn latest(&self, interval: WindowSpec, limit: usize) -> Option<Vec<Candle>> {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series
+            .iter()
+            .find(|s| s.interval == interval)
+            .map(|s| s.latest(limit))
+    }
+}
+
+#[cfg(test)]
+mod candle_tests {
+    use super::*;
+
+    fn minute() -> WindowSpec {
+        "1m".parse().unwrap()
+    }
+
+    #[test]
+    fn test_prices_are_bucketed_into_ohlcv() {
+        let mut series = CandleSeries::new(minute(), 10, 60_500, 100.0);
+        series.push(61_000, 105.0, 2.0);
+        series.push(90_000, 95.0, 1.0);
+        series.push(119_999, 101.0, 0.5);
+        series.push(120_000, 102.0, 1.0);
+
+        let candles = series.latest(10);
+        assert_eq!(candles.len(), 2);
+        let first = candles[0];
+        assert_eq!(first.open_time, 60_000);
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (100.0, 105.0, 95.0, 101.0)
+        );
+        assert_eq!((first.volume, first.trades), (3.5, 4));
+        assert_eq!(first.quote_volume, 105.0 * 2.0 + 95.0 + 101.0 * 0.5);
+        assert_eq!((candles[1].open_time, candles[1].open), (120_000, 102.0));
+    }
+
+    #[test]
+    fn test_quiet_intervals_get_flat_candles() {
+        let mut series = CandleSeries::new(minute(), 10, 0, 100.0);
+        series.push(200_000, 110.0, 1.0);
+
+        let candles = series.latest(10);
+        let opens: Vec<u64> = candles.iter().map(|c| c.open_time).collect();
+        assert_eq!(opens, [0, 60_000, 120_000, 180_000]);
+        assert_eq!(candles[1], Candle::flat(60_000, 100.0));
+        assert_eq!(candles[3].open, 110.0);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut series = CandleSeries::new(minute(), 3, 0, 1.0);
+        for i in 1..10u64 {
+            series.push(i * 60_000, i as f64, 0.0);
+        }
+        let candles = series.latest(100);
+        assert_eq!(candles.len(), 3);
+        assert_eq!(candles[0].open_time, 7 * 60_000);
+        assert_eq!(series.latest(1)[0].close, 9.0);
+
+        // A gap far longer than the history only keeps the newest flat candles
+        series.push(1_000 * 60_000, 5.0, 0.0);
+        let opens: Vec<u64> = series
+            .latest(3)
+            .iter()
+            .map(|c| c.open_time / 60_000)
+            .collect();
+        assert_eq!(opens, [998, 999, 1000]);
+    }
+
+    #[test]
+    fn test_late_price_counts_towards_open_candle() {
+        let mut series = CandleSeries::new(minute(), 5, 120_000, 10.0);
+        series.push(30_000, 12.0, 1.0);
+        let candles = series.latest(5);
+        assert_eq!(candles.len(), 1);
+        assert_eq!((candles[0].high, candles[0].close), (12.0, 12.0));
+    }
+
+    #[test]
+    fn test_history_serves_configured_intervals_only() {
+        let history = CandleHistory::new(&[minute(), "5m".parse().unwrap()], 10, 0, 1.0);
+        history.push(360_000, 2.0, 1.0);
+        assert_eq!(history.latest(minute(), 10).unwrap().len(), 7);
+        assert_eq!(history.latest("5m".parse().unwrap(), 10).unwrap().len(), 2);
+        assert!(history.latest("1h".parse().unwrap(), 10).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Default consecutive failures that trip a closed breaker open.
+pub const DEFAULT_BREAKER_FAILURE_THRESHOLD: u64 = 5;
+/// Default time in milliseconds a tripped breaker stays open before probing.
+pub const DEFAULT_BREAKER_OPEN_TIMEOUT: u64 = 30_000;
+
+/// State of a [`CircuitBreaker`].
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Requests flow normally
+    Closed = 0,
+    /// Requests are suspended until the open timeout elapses
+    Open = 1,
+    /// A single probe request decides between `Closed` and `Open`
+    HalfOpen = 2,
+}
+
+impl BreakerState {
+    /// Inverse of `state as u8`; unknown values map to `Open`.
+    pub const fn from_u8(val: u8) -> Self {
+        match val {
+            0 => Self::Closed,
+            2 => Self::HalfOpen,
+            _ => Self::Open,
+        }
+    }
+}
+
+/// Thresholds for a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerPolicy {
+    /// Consecutive failures that trip the breaker open (`0` disables it)
+    pub failure_threshold: u64,
+    /// How long the breaker stays open before a half-open probe
+    pub open_timeout: Duration,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_BREAKER_FAILURE_THRESHOLD,
+            open_timeout: Duration::from_millis(DEFAULT_BREAKER_OPEN_TIMEOUT),
+        }
+    }
+}
+
+/// Closed / open / half-open circuit breaker for a single source.
+///
+/// Owned by the polling task, so it needs no synchronisation; the task mirrors
+/// its state into the shared `SourceStatus` for reporting.
+pub struct CircuitBreaker {
+    policy: BreakerPolicy,
+    state: BreakerState,
+    /// Failures since the breaker last closed
+    consecutive_failures: u64,
+    /// When the breaker last tripped open
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker.
+    pub fn new(policy: BreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+
+    /// Follows `policy` from now on, keeping the state and failure count; an open
+    /// breaker probes after the new timeout.
+    pub fn set_policy(&mut self, policy: BreakerPolicy) {
+        self.policy = policy;
+    }
+
+    /// Current state.
+    pub fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    /// Failures since the breaker last closed.
+    pub fn consecutive_failures(&self) -> u64 {
+        self.consecutive_failures
+    }
+
+    /// Asks whether a request may be sent at `now`.
+    ///
+    /// An open breaker whose timeout has elapsed moves to half-open and lets the
+    /// probe through; otherwise `Err` carries the time left until that happens.
+    pub fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        match self.remaining_open(now) {
+            Some(remaining) => Err(remaining),
+            None => {
+                if self.state == BreakerState::Open {
+                    self.state = BreakerState::HalfOpen;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Time left before an open breaker allows a probe, `None` if not blocking.
+    pub fn remaining_open(&self, now: Instant) -> Option<Duration> {
+        match (self.state, self.opened_at) {
+            (BreakerState::Open, Some(opened_at)) => {
+                let reopen_at = opened_at + self.policy.open_timeout;
+                reopen_at
+                    .checked_duration_since(now)
+                    .filter(|d| !d.is_zero())
+            }
+            _ => None,
+        }
+    }
+
+    /// Records a successful request, closing the breaker.
+    pub fn on_success(&mut self) {
+        self.state = BreakerState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    /// Records a failed request at `now`.
+    ///
+    /// A failed half-open probe re-opens the breaker immediately; a closed breaker
+    /// opens once the failure threshold is reached.
+    pub fn on_failure(&mut self, now: Instant) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let trip = match self.state {
+            BreakerState::HalfOpen => true,
+            BreakerState::Closed => {
+                self.policy.failure_threshold > 0
+                    && self.consecutive_failures >= self.policy.failure_threshold
+            }
+            BreakerState::Open => false,
+        };
+        if trip {
+            self.state = BreakerState::Open;
+            self.opened_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod breaker_tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(BreakerPolicy {
+            failure_threshold: 3,
+            open_timeout: Duration::from_secs(10),
+        })
+    }
+
+    #[test]
+    fn test_trips_open_after_threshold() {
+        let mut b = breaker();
+        let now = Instant::now();
+        b.on_failure(now);
+        b.on_failure(now);
+        assert_eq!(b.state(), BreakerState::Closed);
+        assert!(b.try_acquire(now).is_ok());
+        b.on_failure(now);
+        assert_eq!(b.state(), BreakerState::Open);
+        assert_eq!(b.try_acquire(now), Err(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_half_open_after_timeout_then_close_on_success() {
+        let mut b = breaker();
+        let now = Instant::now();
+        (0..3).for_each(|_| b.on_failure(now));
+
+        let later = now + Duration::from_secs(4);
+        assert_eq!(b.try_acquire(later), Err(Duration::from_secs(6)));
+
+        let after = now + Duration::from_secs(10);
+        assert!(b.try_acquire(after).is_ok());
+        assert_eq!(b.state(), BreakerState::HalfOpen);
+
+        b.on_success();
+        assert_eq!(b.state(), BreakerState::Closed);
+        assert_eq!(b.consecutive_failures(), 0);
+    }
+
+    #[test]
+    fn test_failed_probe_reopens() {
+        let mut b = breaker();
+        let now = Instant::now();
+        (0..3).for_each(|_| b.on_failure(now));
+
+        let probe_at = now + Duration::from_secs(11);
+        assert!(b.try_acquire(probe_at).is_ok());
+        b.on_failure(probe_at);
+        assert_eq!(b.state(), BreakerState::Open);
+        assert_eq!(b.remaining_open(probe_at), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_new_policy_keeps_state() {
+        let mut b = breaker();
+        let now = Instant::now();
+        (0..3).for_each(|_| b.on_failure(now));
+
+        b.set_policy(BreakerPolicy {
+            failure_threshold: 3,
+            open_timeout: Duration::from_secs(2),
+        });
+        assert_eq!(b.state(), BreakerState::Open);
+        assert_eq!(b.consecutive_failures(), 3);
+        assert_eq!(b.try_acquire(now), Err(Duration::from_secs(2)));
+        assert!(b.try_acquire(now + Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn test_zero_threshold_never_trips() {
+        let mut b = CircuitBreaker::new(BreakerPolicy {
+            failure_threshold: 0,
+            open_timeout: Duration::from_secs(1),
+        });
+        let now = Instant::now();
+        (0..100).for_each(|_| b.on_failure(now));
+        assert_eq!(b.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_state_u8_roundtrip() {
+        for state in [
+            BreakerState::Closed,
+            BreakerState::Open,
+            BreakerState::HalfOpen,
+        ] {
+            assert_eq!(BreakerState::from_u8(state as u8), state);
+        }
+    }
+}
@@ -1,6 +1,38 @@
-use dotenv::dotenv;
-use reqwest::{Client, ClientBuilder};
-use std::{env, time::Duration};
+use reqwest::{Client, ClientBuilder, Url};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    env, fmt,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::watch;
+
+use crate::{
+    candles::{DEFAULT_CANDLE_LIMIT, DEFAULT_CANDLES},
+    circuit_breaker::{
+        BreakerPolicy, DEFAULT_BREAKER_FAILURE_THRESHOLD, DEFAULT_BREAKER_OPEN_TIMEOUT,
+    },
+    config_file::ConfigFile,
+    consensus::{
+        ConsensusMethod, ConsensusPolicy, DEFAULT_CONSENSUS_MAX_AGE, DEFAULT_CONSENSUS_TRIM,
+    },
+    data_processor::{DEFAULT_BOLLINGER_K, StatsConfig},
+    decimal::TickSize,
+    exchange::{Exchange, Provider, SourceUrl},
+    indicator::{DEFAULT_INDICATORS, IndicatorRegistry},
+    json_adapter::JsonAdapter,
+    quantile::DEFAULT_QUANTILES,
+    retry::{
+        DEFAULT_RETRY_INITIAL_DELAY, DEFAULT_RETRY_JITTER, DEFAULT_RETRY_MAX_DELAY,
+        DEFAULT_RETRY_MAX_FAILURES, DEFAULT_RETRY_MULTIPLIER, RetryPolicy,
+    },
+    utils::is_stream_url,
+    window::DEFAULT_WINDOWS,
+    ws_manager::DEFAULT_WS_IDLE_TIMEOUT,
+};
 
 /// Default timeout for HTTP requests in milliseconds.
 pub const DEFAULT_TIME_OUT: u64 = 1000;
@@ -10,74 +42,694 @@ pub const POOL_MAX_IDLE_PER_HOST: usize = 1;
 pub const DEFAULT_IP: &str = "127.0.0.1";
 /// Default port to bind to if not provided in environment.
 pub const DEFAULT_PORT: u16 = 8000;
+/// Default fraction of unhealthy sources at which `/health` returns 503.
+pub const DEFAULT_HEALTH_UNHEALTHY_FRACTION: f64 = 0.5;
+/// Config file read when `CONFIG_FILE` is not set, if it exists.
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+/// Default milliseconds between checks of the config file for changes.
+pub const DEFAULT_CONFIG_WATCH_INTERVAL: u64 = 2000;
+/// Default multiple of `INTERVAL` after which a symbol without updates is stale.
+pub const DEFAULT_STALE_AFTER_INTERVALS: f64 = 10.0;
 
-/// Application configuration loaded from environment variables.
+/// Application configuration loaded from environment variables and an optional
+/// TOML [`ConfigFile`].
 ///
 /// This struct holds all configuration needed for the application, including:
 /// - Target URLs for fetching data
-/// - Interval for polling
-/// - SMA window size
+/// - Interval for polling, and per-symbol overrides
+/// - SMA window size, EMAs, Bollinger band width, quantiles, indicators, time windows,
+///   candles and tick sizes
 /// - HTTP client instance
 /// - IP and port for binding
+/// - Retry policy and circuit breaker thresholds for failed fetches
 pub struct AppConfig {
-    /// List of URLs to fetch data from
-    pub urls: Vec<String>,
+    /// List of URLs to fetch data from, each tagged with its exchange
+    pub urls: Vec<SourceUrl>,
     /// Polling interval
     pub interval: Duration,
-    /// Window size for calculating SMA
-    pub sma_n: usize,
+    /// Symbol -> polling interval overriding `interval`
+    pub symbol_intervals: BTreeMap<String, Duration>,
+    /// SMA window size, EMAs, Bollinger band width, quantiles, indicators, time windows,
+    /// candles and tick sizes of the statistics computed per symbol
+    pub stats: StatsConfig,
     /// Reqwest HTTP client configured with timeout and connection pool
     pub client: Client,
     /// IP address for the service to bind to
     pub ip: String,
     /// Port for the service to bind to
     pub port: u16,
+    /// Backoff applied by every `RpcManager` after a failed fetch
+    pub retry_policy: RetryPolicy,
+    /// Circuit breaker thresholds applied by every `RpcManager`
+    pub breaker_policy: BreakerPolicy,
+    /// Fraction of unhealthy sources at which `/health` stops returning 200
+    pub unhealthy_fraction: f64,
+    /// Symbols that must be fresh for `/ready`, empty for every tracked symbol
+    pub ready_symbols: Vec<String>,
+    /// Poll all REST symbols sharing an endpoint with one multi-symbol request
+    pub batch_poll: bool,
+    /// How quotes of a symbol fed by several sources are combined
+    pub consensus_policy: ConsensusPolicy,
+    /// Lowercase provider name -> how to read its JSON responses
+    pub providers: HashMap<String, Arc<JsonAdapter>>,
+    /// Bearer token required by the `/admin` endpoints, `None` to disable them
+    pub admin_token: Option<String>,
+    /// Timeout of the HTTP client
+    pub timeout: Duration,
+    /// Silence after which a WebSocket stream is reconnected
+    pub ws_idle_timeout: Duration,
+    /// Config file the configuration was read from, if any
+    pub config_file: Option<PathBuf>,
+    /// How often the config file is checked for changes, `None` to only reload on
+    /// SIGHUP or `/admin/reload`
+    pub config_watch: Option<Duration>,
 }
 
-/// Helper function to clean URLs from extra characters like `[` and `]`.
+/// Receives every configuration applied by a reload, starting with the current one.
+pub type ConfigUpdates = watch::Receiver<Arc<AppConfig>>;
+
+/// Helper function to clean URLs from extra characters like `[`, `]` and quotes.
 fn clean_urls(url: &str) -> String {
-    let url = url.trim_matches(|c| c == '[' || c == ']');
+    let url = url.trim_matches(|c: char| c == '[' || c == ']' || c == '"' || c.is_whitespace());
     url.to_string()
 }
 
+/// Longest accepted polling interval in milliseconds (one day).
+pub const MAX_INTERVAL: u64 = 86_400_000;
+
+/// Settings that may be set in the environment or, in lowercase, at the top level of
+/// the config file; `PROVIDER_<NAME>` settings are accepted as well.
+pub const SETTINGS: &[&str] = &[
+    "CONFIG_WATCH_INTERVAL",
+    "URLS",
+    "INTERVAL",
+    "SMA_N",
+    "EMA",
+    "BOLLINGER_K",
+    "QUANTILES",
+    "INDICATORS",
+    "WINDOWS",
+    "CANDLES",
+    "CANDLE_LIMIT",
+    "TICK_SIZE",
+    "TICK_SIZES",
+    "TIME_OUT",
+    "WS_IDLE_TIMEOUT",
+    "IP",
+    "PORT",
+    "RETRY_INITIAL_DELAY",
+    "RETRY_MULTIPLIER",
+    "RETRY_MAX_DELAY",
+    "RETRY_JITTER",
+    "RETRY_MAX_FAILURES",
+    "BREAKER_FAILURE_THRESHOLD",
+    "BREAKER_OPEN_TIMEOUT",
+    "HEALTH_UNHEALTHY_FRACTION",
+    "STALE_AFTER_INTERVALS",
+    "READY_SYMBOLS",
+    "BATCH_POLL",
+    "ADMIN_TOKEN",
+    "CONSENSUS_METHOD",
+    "CONSENSUS_TRIM",
+    "CONSENSUS_MAX_AGE",
+];
+
+/// A problem with one setting, found while loading the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The config file cannot be read or parsed
+    File(String),
+    /// A required setting is not set
+    Missing(String),
+    /// A setting has a value that cannot be used
+    Invalid { key: String, reason: String },
+    /// A source URL is malformed or cannot be polled
+    Url { url: String, reason: String },
+    /// The same source URL is configured more than once
+    DuplicateSource(String),
+    /// Several `[[symbols]]` entries configure the same symbol
+    DuplicateSymbol(String),
+}
+
+impl ConfigError {
+    /// `Invalid` error of `key`.
+    pub fn invalid(key: impl Into<String>, reason: impl fmt::Display) -> Self {
+        Self::Invalid {
+            key: key.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(reason) => write!(f, "config file: {reason}"),
+            Self::Missing(key) => write!(f, "{key} is not set"),
+            Self::Invalid { key, reason } => write!(f, "invalid {key}: {reason}"),
+            Self::Url { url, reason } => write!(f, "URL {url}: {reason}"),
+            Self::DuplicateSource(url) => write!(f, "URL {url} is configured more than once"),
+            Self::DuplicateSymbol(symbol) => {
+                write!(f, "symbol {symbol} has more than one [[symbols]] entry")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Every problem found while loading the configuration, so that they can all be
+/// fixed at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} configuration error(s)", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n- {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Checks that `url` can be polled: a valid `http(s)` or `ws(s)` URL naming its
+/// symbol (or whose JSON provider reads it from the response), with a provider
+/// in `providers` for JSON provider tags.
+pub fn validate_source(
+    url: &SourceUrl,
+    providers: &HashMap<String, Arc<JsonAdapter>>,
+) -> Result<(), ConfigError> {
+    let error = |reason: String| ConfigError::Url {
+        url: url.url.clone(),
+        reason,
+    };
+    let parsed = Url::parse(&url.url).map_err(|e| error(e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "ws" | "wss") {
+        return Err(error(format!("unsupported scheme `{}`", parsed.scheme())));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(error("no host".to_string()));
+    }
+
+    match &url.provider {
+        Provider::Json(name) => {
+            let Some(adapter) = providers.get(name) else {
+                return Err(error(format!(
+                    "provider `{name}` needs PROVIDER_{}",
+                    name.to_ascii_uppercase()
+                )));
+            };
+            if url.symbol().is_none() && adapter.symbol.is_none() {
+                return Err(error(format!(
+                    "no symbol in the URL and PROVIDER_{} reads none",
+                    name.to_ascii_uppercase()
+                )));
+            }
+        }
+        Provider::Exchange(exchange) => {
+            if url.symbol().is_none() {
+                return Err(error(format!("no {exchange} symbol in the URL")));
+            }
+            if is_stream_url(&url.url) && *exchange != Exchange::Binance {
+                return Err(error(format!("{exchange} streams are not supported")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Environment variables, falling back to the top-level keys of the config file.
+///
+/// Problems are collected in `errors` rather than returned, so that every setting
+/// is checked.
+struct Settings<'a> {
+    env: &'a HashMap<String, String>,
+    file: &'a ConfigFile,
+    errors: RefCell<Vec<ConfigError>>,
+}
+
+impl Settings<'_> {
+    /// Reads `key` from the environment, else from the config file.
+    fn var(&self, key: &str) -> Option<String> {
+        debug_assert!(SETTINGS.contains(&key), "{key} missing from SETTINGS");
+        self.env
+            .get(key)
+            .cloned()
+            .or_else(|| self.file.setting(key))
+    }
+
+    /// Records `error`.
+    fn error(&self, error: ConfigError) {
+        self.errors.borrow_mut().push(error);
+    }
+
+    /// Records an `Invalid` error of `key` unless `valid`.
+    fn check(&self, valid: bool, key: &str, reason: &str) {
+        if !valid {
+            self.error(ConfigError::invalid(key, reason));
+        }
+    }
+
+    /// Reads a required setting; `None` after recording why if missing or invalid.
+    fn required<T: FromStr<Err: fmt::Display>>(&self, key: &str) -> Option<T> {
+        let Some(value) = self.var(key) else {
+            self.error(ConfigError::Missing(key.to_string()));
+            return None;
+        };
+        value
+            .trim()
+            .parse()
+            .map_err(|e| self.error(ConfigError::invalid(key, e)))
+            .ok()
+    }
+
+    /// Reads an optional setting, falling back to `default` if missing, or after
+    /// recording the error if invalid.
+    fn or<T: FromStr<Err: fmt::Display>>(&self, key: &str, default: T) -> T {
+        match self.var(key) {
+            Some(value) => value.trim().parse().unwrap_or_else(|e| {
+                self.error(ConfigError::invalid(key, e));
+                default
+            }),
+            None => default,
+        }
+    }
+
+    /// Reads the comma-separated list `key`, `default` if missing.
+    fn list<T: FromStr<Err = String>>(&self, key: &str, default: &str) -> Vec<T> {
+        let list = self.var(key).unwrap_or(default.to_string());
+        parse_list(key, &list).unwrap_or_else(|e| {
+            self.error(e);
+            Vec::new()
+        })
+    }
+}
+
+/// Environment variables over the variables of the `.env` file, if any.
+///
+/// `.env` is read on every call instead of being copied into the process
+/// environment once, so reloads pick up its edits.
+// `dotenv_iter` is deprecated in favour of loading into the process environment,
+// which never overrides a variable set by an earlier load.
+#[allow(deprecated)]
+pub(crate) fn env_vars() -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = dotenv::dotenv_iter()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect();
+    vars.extend(env::vars());
+    vars
+}
+
+/// Parses the comma-separated `list` of setting `key`; empty entries are skipped.
+pub(crate) fn parse_list<T: FromStr<Err = String>>(
+    key: &str,
+    list: &str,
+) -> Result<Vec<T>, ConfigError> {
+    list.split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| item.parse::<T>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ConfigError::invalid(key, e))
+}
+
 impl AppConfig {
-    /// Load configuration from `.env` file and system environment variables.
+    /// Load configuration from `.env` file, system environment variables and the
+    /// config file.
+    ///
+    /// The config file is `CONFIG_FILE`, or `config.toml` if it exists. Its top-level
+    /// keys are the variables below in lowercase and are overridden by the
+    /// environment; its `[[symbols]]` entries add sources with their own interval
+    /// and statistics (see [`ConfigFile`]). `URLS` may then be omitted.
     ///
     /// # Environment Variables
-    /// - `URLS` (comma-separated list of URLs)
+    /// - `CONFIG_FILE` (optional path of the TOML config file)
+    /// - `CONFIG_WATCH_INTERVAL` (optional milliseconds between checks of the config file
+    ///   for changes, `0` to disable)
+    /// - `URLS` (comma-separated list of URLs, optionally tagged `<exchange>:<url>`)
     /// - `INTERVAL` (polling interval in milliseconds)
     /// - `SMA_N` (SMA window size)
+    /// - `EMA` (optional comma-separated EMA spans like `20` or alphas like `0.1`)
+    /// - `BOLLINGER_K` (optional standard deviations between the SMA and each band)
+    /// - `QUANTILES` (optional comma-separated quantiles like `0.05,0.5,0.95` or `p99`)
+    /// - `INDICATORS` (optional comma-separated indicators like `rsi(14),macd(12,26,9)`)
+    /// - `WINDOWS` (optional comma-separated time windows like `1m,5m,1h,24h`)
+    /// - `CANDLES` (optional comma-separated candle resolutions like `1m,5m,15m,1h`)
+    /// - `CANDLE_LIMIT` (optional number of candles retained per resolution)
+    /// - `TICK_SIZE` (optional tick size like `0.01` every price is rounded to)
+    /// - `TICK_SIZES` (optional per-symbol tick sizes like `BTCUSDT:0.01,SHIBUSDT:0.00000001`)
     /// - `TIME_OUT` (optional HTTP timeout in milliseconds)
+    /// - `WS_IDLE_TIMEOUT` (optional silence in milliseconds after which a stream reconnects)
     /// - `IP` (optional IP address to bind to)
     /// - `PORT` (optional port to bind to)
+    /// - `RETRY_INITIAL_DELAY` (optional first retry delay in milliseconds)
+    /// - `RETRY_MULTIPLIER` (optional backoff growth factor)
+    /// - `RETRY_MAX_DELAY` (optional retry delay cap in milliseconds)
+    /// - `RETRY_JITTER` (optional jitter fraction in `[0, 1]`)
+    /// - `RETRY_MAX_FAILURES` (optional failures before a source is degraded)
+    /// - `BREAKER_FAILURE_THRESHOLD` (optional failures before the breaker opens)
+    /// - `BREAKER_OPEN_TIMEOUT` (optional open duration in milliseconds)
+    /// - `HEALTH_UNHEALTHY_FRACTION` (optional unhealthy fraction failing `/health`)
+    /// - `STALE_AFTER_INTERVALS` (optional multiple of `INTERVAL` after which a symbol
+    ///   without updates is stale, `0` to disable)
+    /// - `READY_SYMBOLS` (optional comma-separated symbols `/ready` requires, default all)
+    /// - `BATCH_POLL` (optional `true` to poll REST symbols with one batched request)
+    /// - `CONSENSUS_METHOD` (optional `median`, `trimmed_mean` or `weighted`)
+    /// - `CONSENSUS_TRIM` (optional fraction trimmed from each end by `trimmed_mean`)
+    /// - `CONSENSUS_MAX_AGE` (optional age in milliseconds after which a quote is ignored)
+    /// - `PROVIDER_<NAME>` (optional JSON adapter `price=<path>;symbol=<path>;time=<path>`
+    ///   for URLs tagged `<name>:`)
     ///
     /// # Returns
-    /// Returns `Ok(AppConfig)` on success, or a boxed error if parsing fails.
-    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
-        // Load .env file if present
-        dotenv().ok();
+    /// Returns `Ok(AppConfig)` on success, or every invalid or missing setting.
+    pub fn from_env() -> Result<Self, ConfigErrors> {
+        Self::from_env_with(&IndicatorRegistry::default())
+    }
 
-        // Read and clean URLs
-        let urls: Vec<String> = std::env::var("URLS")?
-            .split(',')
-            .map(|s| clean_urls(s))
-            .collect();
+    /// Polling interval of `symbol`.
+    pub fn interval_for(&self, symbol: &str) -> Duration {
+        self.symbol_intervals
+            .get(symbol)
+            .copied()
+            .unwrap_or(self.interval)
+    }
+
+    /// Like [`AppConfig::from_env`], resolving `INDICATORS` against `registry` so that
+    /// custom indicators can be configured next to the built-in ones.
+    pub fn from_env_with(registry: &IndicatorRegistry) -> Result<Self, ConfigErrors> {
+        let env = env_vars();
+
+        // Optional TOML file under the environment: `CONFIG_FILE`, else `config.toml` if present
+        let config_file = match env.get("CONFIG_FILE") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.exists()),
+        };
+        Self::from_parts(&env, config_file, registry)
+    }
+
+    /// Builds and validates the configuration from environment variables `env` and
+    /// the config file at `config_file`, if any; reloads pass the file found at
+    /// startup instead of looking it up again.
+    pub(crate) fn from_parts(
+        env: &HashMap<String, String>,
+        config_file: Option<PathBuf>,
+        registry: &IndicatorRegistry,
+    ) -> Result<Self, ConfigErrors> {
+        let file = match &config_file {
+            Some(path) => {
+                ConfigFile::load(path).map_err(|e| ConfigErrors(vec![ConfigError::File(e)]))?
+            }
+            None => ConfigFile::default(),
+        };
+        let mut config = Self::from_sources(env, &file, registry)?;
+        config.config_file = config_file;
+        Ok(config)
+    }
+
+    /// Builds and validates the configuration from environment variables `env` and
+    /// config file `file`, reporting every problem found.
+    fn from_sources(
+        env: &HashMap<String, String>,
+        file: &ConfigFile,
+        registry: &IndicatorRegistry,
+    ) -> Result<Self, ConfigErrors> {
+        let settings = Settings {
+            env,
+            file,
+            errors: RefCell::new(Vec::new()),
+        };
+
+        // A misspelt key in the file would otherwise be silently ignored
+        for key in file.setting_keys() {
+            let known = SETTINGS.iter().any(|s| s.to_ascii_lowercase() == key)
+                || key.starts_with("provider_");
+            if !known {
+                settings.error(ConfigError::invalid(key, "unknown setting"));
+            }
+        }
+
+        // Read and clean URLs, then add the sources of the file's symbol entries
+        let mut urls: Vec<SourceUrl> = match settings.var("URLS") {
+            Some(list) => list
+                .split(',')
+                .map(clean_urls)
+                .filter(|url| !url.is_empty())
+                .map(|url| SourceUrl::parse(&url))
+                .collect(),
+            None => {
+                if file.symbols.is_empty() {
+                    settings.error(ConfigError::Missing("URLS".to_string()));
+                }
+                Vec::new()
+            }
+        };
+        urls.extend(file.symbols.iter().map(|entry| entry.source_url()));
 
         // Parse interval and SMA window size
-        let interval: u64 = env::var("INTERVAL")?.parse()?;
-        let sma_n: usize = env::var("SMA_N")?.parse()?;
+        let interval: u64 = settings.required("INTERVAL").unwrap_or(1);
+        settings.check(
+            (1..=MAX_INTERVAL).contains(&interval),
+            "INTERVAL",
+            "must be between 1 and 86400000 ms",
+        );
+        let sma_n: usize = settings.required("SMA_N").unwrap_or(1);
+        settings.check(sma_n > 0, "SMA_N", "must be > 0");
 
-        // Parse optional timeout, fallback to default if missing or invalid
-        let time_out = env::var("TIME_OUT")
-            .map(|d| d.parse::<u64>().unwrap_or(DEFAULT_TIME_OUT))
-            .unwrap_or(DEFAULT_TIME_OUT);
+        // Optional EMAs, each a span or an alpha
+        let emas = settings.list("EMA", "");
+        let bollinger_k: f64 = settings.or("BOLLINGER_K", DEFAULT_BOLLINGER_K);
+        settings.check(
+            bollinger_k.is_finite() && bollinger_k >= 0.0,
+            "BOLLINGER_K",
+            "must be >= 0",
+        );
+
+        // Quantiles, an empty list disables them
+        let quantiles = settings.list("QUANTILES", DEFAULT_QUANTILES);
+
+        // Indicators resolved against the registry, an empty list disables them
+        let indicators = registry
+            .resolve_list(
+                &settings
+                    .var("INDICATORS")
+                    .unwrap_or(DEFAULT_INDICATORS.to_string()),
+            )
+            .unwrap_or_else(|e| {
+                settings.error(ConfigError::invalid("INDICATORS", e));
+                Vec::new()
+            });
+
+        // Time windows and candle resolutions, an empty list disables them
+        let windows = settings.list("WINDOWS", DEFAULT_WINDOWS);
+        let candles = settings.list("CANDLES", DEFAULT_CANDLES);
+        let candle_limit: usize = settings.or("CANDLE_LIMIT", DEFAULT_CANDLE_LIMIT);
+        settings.check(candle_limit > 0, "CANDLE_LIMIT", "must be > 0");
+
+        // Optional tick sizes switching symbols to exact decimal prices
+        let tick_size = settings
+            .var("TICK_SIZE")
+            .filter(|tick| !tick.trim().is_empty())
+            .and_then(|tick| {
+                tick.parse::<TickSize>()
+                    .map_err(|e| settings.error(ConfigError::invalid("TICK_SIZE", e)))
+                    .ok()
+            });
+        let mut symbol_tick_sizes = BTreeMap::new();
+        for entry in settings.var("TICK_SIZES").unwrap_or_default().split(',') {
+            if entry.trim().is_empty() {
+                continue;
+            }
+            let Some((symbol, tick)) = entry.split_once(':') else {
+                settings.error(ConfigError::invalid(
+                    "TICK_SIZES",
+                    format!("entry `{entry}` is not SYMBOL:TICK"),
+                ));
+                continue;
+            };
+            match tick.parse::<TickSize>() {
+                Ok(tick) => {
+                    symbol_tick_sizes.insert(symbol.trim().to_uppercase(), tick);
+                }
+                Err(e) => settings.error(ConfigError::invalid("TICK_SIZES", e)),
+            }
+        }
+
+        // Optional timeout
+        let time_out: u64 = settings.or("TIME_OUT", DEFAULT_TIME_OUT);
+        settings.check(time_out > 0, "TIME_OUT", "must be > 0");
         let timeout = Duration::from_millis(time_out);
+        let ws_idle_timeout: u64 = settings.or("WS_IDLE_TIMEOUT", DEFAULT_WS_IDLE_TIMEOUT);
+        settings.check(ws_idle_timeout > 0, "WS_IDLE_TIMEOUT", "must be > 0");
 
         // Optional IP and port, fallback to defaults
-        let ip = env::var("IP").unwrap_or(DEFAULT_IP.to_string());
-        let port = env::var("PORT")
-            .map(|d| d.parse::<u16>().unwrap_or(DEFAULT_PORT))
-            .unwrap_or(DEFAULT_PORT);
+        let ip = settings.var("IP").unwrap_or(DEFAULT_IP.to_string());
+        settings.check(!ip.trim().is_empty(), "IP", "must not be empty");
+        let port = settings.or("PORT", DEFAULT_PORT);
+
+        // Optional retry policy, every field falls back to its default
+        let retry_policy = RetryPolicy {
+            initial_delay: Duration::from_millis(
+                settings.or("RETRY_INITIAL_DELAY", DEFAULT_RETRY_INITIAL_DELAY),
+            ),
+            multiplier: settings.or("RETRY_MULTIPLIER", DEFAULT_RETRY_MULTIPLIER),
+            max_delay: Duration::from_millis(
+                settings.or("RETRY_MAX_DELAY", DEFAULT_RETRY_MAX_DELAY),
+            ),
+            jitter: settings.or("RETRY_JITTER", DEFAULT_RETRY_JITTER),
+            max_failures: settings.or("RETRY_MAX_FAILURES", DEFAULT_RETRY_MAX_FAILURES),
+        };
+        settings.check(
+            retry_policy.multiplier >= 1.0,
+            "RETRY_MULTIPLIER",
+            "must be >= 1",
+        );
+        settings.check(
+            (0.0..=1.0).contains(&retry_policy.jitter),
+            "RETRY_JITTER",
+            "must be between 0 and 1",
+        );
+        settings.check(
+            retry_policy.max_delay >= retry_policy.initial_delay,
+            "RETRY_MAX_DELAY",
+            "must be >= RETRY_INITIAL_DELAY",
+        );
+
+        // Optional circuit breaker thresholds
+        let breaker_policy = BreakerPolicy {
+            failure_threshold: settings.or(
+                "BREAKER_FAILURE_THRESHOLD",
+                DEFAULT_BREAKER_FAILURE_THRESHOLD,
+            ),
+            open_timeout: Duration::from_millis(
+                settings.or("BREAKER_OPEN_TIMEOUT", DEFAULT_BREAKER_OPEN_TIMEOUT),
+            ),
+        };
+        settings.check(
+            !breaker_policy.open_timeout.is_zero(),
+            "BREAKER_OPEN_TIMEOUT",
+            "must be > 0",
+        );
+        let unhealthy_fraction: f64 = settings.or(
+            "HEALTH_UNHEALTHY_FRACTION",
+            DEFAULT_HEALTH_UNHEALTHY_FRACTION,
+        );
+        settings.check(
+            unhealthy_fraction > 0.0 && unhealthy_fraction <= 1.0,
+            "HEALTH_UNHEALTHY_FRACTION",
+            "must be in (0, 1]",
+        );
+
+        // Freshness: symbols without updates for this long are stale and fail `/ready`
+        let stale_after_intervals: f64 =
+            settings.or("STALE_AFTER_INTERVALS", DEFAULT_STALE_AFTER_INTERVALS);
+        settings.check(
+            stale_after_intervals.is_finite() && stale_after_intervals >= 0.0,
+            "STALE_AFTER_INTERVALS",
+            "must be >= 0",
+        );
+        let stale_after = (stale_after_intervals > 0.0)
+            .then(|| Duration::from_millis(interval).mul_f64(stale_after_intervals));
+        let ready_symbols: Vec<String> = settings
+            .var("READY_SYMBOLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let batch_poll = settings.or("BATCH_POLL", false);
+        let admin_token = settings.var("ADMIN_TOKEN").filter(|t| !t.is_empty());
+        let config_watch_ms = settings.or("CONFIG_WATCH_INTERVAL", DEFAULT_CONFIG_WATCH_INTERVAL);
+        let config_watch = (config_watch_ms > 0).then(|| Duration::from_millis(config_watch_ms));
+
+        // Optional consensus settings for symbols fed by several sources
+        let consensus_policy = ConsensusPolicy {
+            method: settings.or("CONSENSUS_METHOD", ConsensusMethod::default()),
+            trim: settings.or("CONSENSUS_TRIM", DEFAULT_CONSENSUS_TRIM),
+            max_age: Duration::from_millis(
+                settings.or("CONSENSUS_MAX_AGE", DEFAULT_CONSENSUS_MAX_AGE),
+            ),
+        };
+        settings.check(
+            (0.0..0.5).contains(&consensus_policy.trim),
+            "CONSENSUS_TRIM",
+            "must be in [0, 0.5)",
+        );
+
+        // JSON providers, every `<name>:` tag not naming an exchange needs one
+        let mut providers = HashMap::new();
+        let mut env_providers: Vec<(String, String)> = env
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix("PROVIDER_")?;
+                Some((name.to_string(), value.clone()))
+            })
+            .collect();
+        env_providers.sort();
+        // Environment variables come last to override the file
+        for (name, value) in file.prefixed("PROVIDER_").into_iter().chain(env_providers) {
+            match value.parse::<JsonAdapter>() {
+                Ok(adapter) => {
+                    providers.insert(name.to_ascii_lowercase(), Arc::new(adapter));
+                }
+                Err(e) => settings.error(ConfigError::invalid(format!("PROVIDER_{name}"), e)),
+            }
+        }
+
+        // Every source must be pollable, and configured once
+        let mut seen = HashSet::new();
+        for url in &urls {
+            if let Err(e) = validate_source(url, &providers) {
+                settings.error(e);
+            }
+            if !seen.insert(&url.url) {
+                settings.error(ConfigError::DuplicateSource(url.url.clone()));
+            }
+        }
+
+        // Settings of the file's symbol entries
+        let mut symbol_intervals = BTreeMap::new();
+        let mut symbol_overrides = BTreeMap::new();
+        let mut entry_symbols = HashSet::new();
+        for entry in &file.symbols {
+            let Some(symbol) = entry.symbol() else {
+                // Unless already reported with its URL, the symbol must be set
+                if validate_source(&entry.source_url(), &providers).is_ok() {
+                    settings.error(ConfigError::invalid(
+                        format!("symbols.url {}", entry.url),
+                        "no symbol in the URL, set `symbol`",
+                    ));
+                }
+                continue;
+            };
+            if !entry_symbols.insert(symbol.clone()) {
+                settings.error(ConfigError::DuplicateSymbol(symbol));
+                continue;
+            }
+            if let Some(interval) = entry.interval {
+                settings.check(
+                    (1..=MAX_INTERVAL).contains(&interval),
+                    &format!("symbols.{symbol}.interval"),
+                    "must be between 1 and 86400000 ms",
+                );
+                symbol_intervals.insert(symbol.clone(), Duration::from_millis(interval));
+            }
+            match entry.stats_override(registry, stale_after_intervals) {
+                Ok(stats) => {
+                    symbol_overrides.insert(symbol, stats);
+                }
+                Err(errors) => settings.errors.borrow_mut().extend(errors),
+            }
+        }
+
+        let errors = settings.errors.into_inner();
+        if !errors.is_empty() {
+            return Err(ConfigErrors(errors));
+        }
 
         // Build reqwest HTTP client with timeout and connection pool settings
         let client = ClientBuilder::new()
@@ -90,10 +742,303 @@ impl AppConfig {
         Ok(Self {
             urls,
             interval: Duration::from_millis(interval),
-            sma_n,
+            symbol_intervals,
+            stats: StatsConfig::new(sma_n)
+                .with_emas(emas)
+                .with_bollinger_k(bollinger_k)
+                .with_quantiles(quantiles)
+                .with_indicators(indicators)
+                .with_windows(windows)
+                .with_candles(candles, candle_limit)
+                .with_tick_sizes(tick_size, symbol_tick_sizes)
+                .with_stale_after(stale_after)
+                .with_symbol_overrides(symbol_overrides),
             client,
             ip,
             port,
+            retry_policy,
+            breaker_policy,
+            unhealthy_fraction,
+            ready_symbols,
+            batch_poll,
+            consensus_policy,
+            providers,
+            admin_token,
+            timeout,
+            ws_idle_timeout: Duration::from_millis(ws_idle_timeout),
+            config_file: None,
+            config_watch,
         })
     }
 }
+
+#[cfg(test)]
+impl AppConfig {
+    /// Configuration without sources: a 100 ms interval, an SMA of 2 and defaults
+    /// elsewhere.
+    pub(crate) fn for_tests() -> Self {
+        Self {
+            urls: Vec::new(),
+            interval: Duration::from_millis(100),
+            symbol_intervals: BTreeMap::new(),
+            stats: StatsConfig::new(2),
+            client: reqwest::Client::new(),
+            ip: "127.0.0.1".into(),
+            port: 0,
+            retry_policy: RetryPolicy::default(),
+            breaker_policy: BreakerPolicy::default(),
+            unhealthy_fraction: 1.0,
+            ready_symbols: Vec::new(),
+            batch_poll: false,
+            consensus_policy: ConsensusPolicy::default(),
+            providers: HashMap::new(),
+            admin_token: None,
+            timeout: Duration::from_millis(1000),
+            ws_idle_timeout: Duration::from_secs(30),
+            config_file: None,
+            config_watch: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn load(vars: &[(&str, &str)], file: &str) -> Result<AppConfig, ConfigErrors> {
+        let file: ConfigFile = file.parse().unwrap();
+        AppConfig::from_sources(&env(vars), &file, &IndicatorRegistry::default())
+    }
+
+    #[test]
+    fn test_valid_config_prefers_env_over_file() {
+        let config = load(
+            &[
+                (
+                    "URLS",
+                    "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT",
+                ),
+                ("INTERVAL", "500"),
+                ("SMA_N", "10"),
+            ],
+            "interval = 1000\nsma_n = 50\nport = 9000",
+        )
+        .unwrap();
+        assert_eq!(config.interval, Duration::from_millis(500));
+        assert_eq!(config.stats.sma_n, 10);
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.timeout, Duration::from_millis(DEFAULT_TIME_OUT));
+    }
+
+    #[test]
+    fn test_every_error_is_reported() {
+        let btc = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
+        let urls = format!("{btc},{btc},https://api.binance.com/api/v3/ticker/price,ftp://x.com/a");
+        let result = load(
+            &[
+                ("URLS", &urls),
+                ("INTERVAL", "0"),
+                ("SMA_N", "0"),
+                ("TIME_OUT", "abc"),
+                ("PORT", "99999"),
+                ("WINDOWS", "1m,0s"),
+            ],
+            r#"
+                [[symbols]]
+                url = "https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT"
+                exchange = "okx"
+                sma_n = 0
+
+                [[symbols]]
+                url = "okx:https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT&x=1"
+            "#,
+        );
+        let Err(ConfigErrors(errors)) = result else {
+            panic!("invalid config accepted");
+        };
+
+        let keys: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| match e {
+                ConfigError::Invalid { key, .. } => Some(key.as_str()),
+                _ => None,
+            })
+            .collect();
+        for key in [
+            "INTERVAL",
+            "SMA_N",
+            "TIME_OUT",
+            "PORT",
+            "WINDOWS",
+            "symbols.SOLUSDT.sma_n",
+        ] {
+            assert!(keys.contains(&key), "{key} not in {errors:?}");
+        }
+        assert!(errors.contains(&ConfigError::DuplicateSource(btc.to_string())));
+        assert!(errors.contains(&ConfigError::DuplicateSymbol("SOLUSDT".to_string())));
+        let bad_urls = errors
+            .iter()
+            .filter(|e| matches!(e, ConfigError::Url { .. }))
+            .count();
+        assert_eq!(bad_urls, 2);
+    }
+
+    #[test]
+    fn test_missing_required_settings() {
+        let Err(ConfigErrors(errors)) = load(&[], "") else {
+            panic!("config without URLS, INTERVAL and SMA_N is valid");
+        };
+        assert!(errors.contains(&ConfigError::Missing("URLS".to_string())));
+        assert!(errors.contains(&ConfigError::Missing("INTERVAL".to_string())));
+        assert!(errors.contains(&ConfigError::Missing("SMA_N".to_string())));
+    }
+
+    const BTC: &str = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
+
+    /// Errors of loading `vars` on top of a valid interval and SMA window, and `file`.
+    fn errors(vars: &[(&str, &str)], file: &str) -> Vec<ConfigError> {
+        let mut all = vec![("INTERVAL", "1000"), ("SMA_N", "5")];
+        all.extend_from_slice(vars);
+        match load(&all, file) {
+            Ok(_) => panic!("invalid config accepted: {vars:?} {file}"),
+            Err(ConfigErrors(errors)) => errors,
+        }
+    }
+
+    #[test]
+    fn test_url_without_symbol() {
+        let url = "https://api.binance.com/api/v3/ticker/price";
+        let errors = errors(&[("URLS", url)], "");
+        assert_eq!(
+            errors,
+            [ConfigError::Url {
+                url: url.to_string(),
+                reason: "no binance symbol in the URL".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_malformed_urls() {
+        let errors = errors(&[("URLS", "ftp://x.com/a?symbol=BTCUSDT,not a url")], "");
+        let urls: Vec<&str> = errors
+            .iter()
+            .map(|e| match e {
+                ConfigError::Url { url, .. } => url.as_str(),
+                e => panic!("unexpected {e}"),
+            })
+            .collect();
+        assert_eq!(urls, ["ftp://x.com/a?symbol=BTCUSDT", "not a url"]);
+    }
+
+    #[test]
+    fn test_duplicate_source() {
+        let errors = errors(&[("URLS", &format!("{BTC},{BTC}"))], "");
+        assert_eq!(errors, [ConfigError::DuplicateSource(BTC.to_string())]);
+    }
+
+    #[test]
+    fn test_duplicate_symbol_entries() {
+        let file = r#"
+            [[symbols]]
+            url = "okx:https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT"
+
+            [[symbols]]
+            url = "https://api.bybit.com/v5/market/tickers?category=spot&symbol=SOLUSDT"
+            exchange = "bybit"
+        "#;
+        let errors = errors(&[], file);
+        assert_eq!(
+            errors,
+            [ConfigError::DuplicateSymbol("SOLUSDT".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_intervals() {
+        let file = r#"
+            [[symbols]]
+            url = "okx:https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT"
+            interval = 0
+        "#;
+        let errors = errors(&[("URLS", BTC), ("INTERVAL", "86400001")], file);
+        let keys: Vec<&str> = errors
+            .iter()
+            .map(|e| match e {
+                ConfigError::Invalid { key, .. } => key.as_str(),
+                e => panic!("unexpected {e}"),
+            })
+            .collect();
+        assert_eq!(keys, ["INTERVAL", "symbols.SOLUSDT.interval"]);
+    }
+
+    #[test]
+    fn test_retry_and_breaker_policies() {
+        let errors = errors(
+            &[
+                ("URLS", BTC),
+                ("RETRY_INITIAL_DELAY", "1000"),
+                ("RETRY_MAX_DELAY", "500"),
+                ("BREAKER_OPEN_TIMEOUT", "0"),
+            ],
+            "",
+        );
+        assert_eq!(
+            errors,
+            [
+                ConfigError::invalid("RETRY_MAX_DELAY", "must be >= RETRY_INITIAL_DELAY"),
+                ConfigError::invalid("BREAKER_OPEN_TIMEOUT", "must be > 0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreadable_or_malformed_file() {
+        let dir = std::env::temp_dir().join(format!("aboss-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "interval = [1000").unwrap();
+        let registry = IndicatorRegistry::default();
+        let vars = env(&[("URLS", BTC), ("INTERVAL", "1000"), ("SMA_N", "5")]);
+
+        for path in [path.clone(), dir.join("missing.toml")] {
+            let result = AppConfig::from_parts(&vars, Some(path.clone()), &registry);
+            let Err(ConfigErrors(errors)) = result else {
+                panic!("{} accepted", path.display());
+            };
+            assert!(
+                matches!(errors.as_slice(), [ConfigError::File(_)]),
+                "{errors:?}"
+            );
+        }
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_file_settings_are_rejected() {
+        let file = r#"
+            urls = ["https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT"]
+            interval = 1000
+            sma_n = 5
+            intervall = 500
+            SMA_N = 10
+            provider_gemini = "price=last"
+        "#;
+        let Err(ConfigErrors(errors)) = load(&[], file) else {
+            panic!("misspelt settings accepted");
+        };
+        assert_eq!(
+            errors,
+            [
+                ConfigError::invalid("SMA_N", "unknown setting"),
+                ConfigError::invalid("intervall", "unknown setting"),
+            ]
+        );
+    }
+}
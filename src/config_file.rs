@@ -0,0 +1,291 @@
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+use toml::Value;
+
+use crate::{
+    config::{ConfigError, parse_list},
+    data_processor::StatsOverride,
+    exchange::SourceUrl,
+    indicator::IndicatorRegistry,
+};
+
+/// TOML configuration file, read by [`AppConfig`](crate::config::AppConfig) under the
+/// environment.
+///
+/// Top-level keys are the environment variables in lowercase (`interval`, `sma_n`,
+/// `time_out`, ...; any other key is rejected when the configuration is loaded); lists
+/// may be written as arrays. Each `[[symbols]]` table adds a source with its own
+/// settings:
+///
+/// ```toml
+/// interval = 1000
+/// sma_n = 50
+/// urls = ["https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT"]
+///
+/// [[symbols]]
+/// url = "https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT"
+/// exchange = "okx"
+/// interval = 250
+/// sma_n = 200
+/// windows = ["1m", "5m"]
+/// indicators = ["rsi(7)"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct ConfigFile {
+    /// Per-symbol sources and settings
+    #[serde(default)]
+    pub symbols: Vec<SymbolEntry>,
+    /// Every other top-level key
+    #[serde(flatten)]
+    settings: BTreeMap<String, Value>,
+}
+
+impl ConfigFile {
+    /// Reads and parses the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        text.parse()
+            .map_err(|e| format!("invalid {}: {e}", path.display()))
+    }
+
+    /// Top-level value of the environment variable `key`, as the string the
+    /// variable would hold.
+    pub fn setting(&self, key: &str) -> Option<String> {
+        self.settings
+            .get(&key.to_ascii_lowercase())
+            .map(setting_string)
+    }
+
+    /// Every top-level key except `symbols`, as written in the file.
+    pub fn setting_keys(&self) -> impl Iterator<Item = &str> {
+        self.settings.keys().map(String::as_str)
+    }
+
+    /// Top-level `<prefix><name>` settings as `(NAME, value)`, like environment
+    /// variables such as `PROVIDER_<NAME>`.
+    pub fn prefixed(&self, prefix: &str) -> Vec<(String, String)> {
+        let prefix = prefix.to_ascii_lowercase();
+        self.settings
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix(&prefix)?;
+                Some((name.to_ascii_uppercase(), setting_string(value)))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ConfigFile {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+/// A `[[symbols]]` entry: one source and the settings of the symbol it feeds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SymbolEntry {
+    /// Endpoint URL, optionally tagged `<exchange>:<url>` as in `URLS`
+    pub url: String,
+    /// Exchange or JSON provider serving `url`, instead of a tag
+    pub exchange: Option<String>,
+    /// Symbol the settings apply to, by default the one in `url`
+    pub symbol: Option<String>,
+    /// Polling interval in milliseconds
+    pub interval: Option<u64>,
+    /// SMA window size
+    pub sma_n: Option<usize>,
+    /// EMA spans or alphas
+    pub ema: Option<Value>,
+    /// Time windows
+    pub windows: Option<Value>,
+    /// Candle resolutions
+    pub candles: Option<Value>,
+    /// Quantiles
+    pub quantiles: Option<Value>,
+    /// Indicators
+    pub indicators: Option<Value>,
+    /// Tick size
+    pub tick_size: Option<Value>,
+}
+
+impl SymbolEntry {
+    /// The source of the entry.
+    pub fn source_url(&self) -> SourceUrl {
+        match &self.exchange {
+            Some(exchange) => SourceUrl::parse(&format!("{exchange}:{}", self.url)),
+            None => SourceUrl::parse(&self.url),
+        }
+    }
+
+    /// Symbol the settings apply to.
+    pub fn symbol(&self) -> Option<String> {
+        match &self.symbol {
+            Some(symbol) => Some(symbol.trim().to_uppercase()),
+            None => self.source_url().symbol(),
+        }
+    }
+
+    /// Polling interval of the entry, if it has its own.
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval.map(Duration::from_millis)
+    }
+
+    /// Statistics settings of the entry; a symbol with its own interval is stale
+    /// after `stale_after_intervals` of it.
+    ///
+    /// Every invalid setting is reported, keyed `symbols.<SYMBOL>.<setting>`.
+    pub fn stats_override(
+        &self,
+        registry: &IndicatorRegistry,
+        stale_after_intervals: f64,
+    ) -> Result<StatsOverride, Vec<ConfigError>> {
+        let symbol = self.symbol().unwrap_or_else(|| self.url.clone());
+        let key = |setting: &str| format!("symbols.{symbol}.{setting}");
+        let mut errors = Vec::new();
+        let emas = entry_list(&key("ema"), &self.ema, &mut errors);
+        let windows = entry_list(&key("windows"), &self.windows, &mut errors);
+        let candles = entry_list(&key("candles"), &self.candles, &mut errors);
+        let quantiles = entry_list(&key("quantiles"), &self.quantiles, &mut errors);
+
+        let tick_size = self
+            .tick_size
+            .as_ref()
+            .map(setting_string)
+            .and_then(|tick| {
+                tick.parse()
+                    .map_err(|e| errors.push(ConfigError::invalid(key("tick_size"), e)))
+                    .ok()
+            });
+        let indicators = self.indicators.as_ref().map(setting_string).and_then(|i| {
+            registry
+                .resolve_list(&i)
+                .map_err(|e| errors.push(ConfigError::invalid(key("indicators"), e)))
+                .ok()
+        });
+        if self.sma_n == Some(0) {
+            errors.push(ConfigError::invalid(key("sma_n"), "must be > 0"));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(StatsOverride {
+            sma_n: self.sma_n,
+            emas,
+            windows,
+            candles,
+            quantiles,
+            indicators,
+            tick_size,
+            stale_after: self
+                .interval()
+                .filter(|_| stale_after_intervals > 0.0)
+                .map(|interval| interval.mul_f64(stale_after_intervals)),
+        })
+    }
+}
+
+/// Parses the list setting `key` of an entry, if set; `None` after recording the
+/// error if invalid.
+fn entry_list<T: FromStr<Err = String>>(
+    key: &str,
+    value: &Option<Value>,
+    errors: &mut Vec<ConfigError>,
+) -> Option<Vec<T>> {
+    let list = value.as_ref().map(setting_string)?;
+    parse_list(key, &list).map_err(|e| errors.push(e)).ok()
+}
+
+/// `value` as an environment variable would hold it: strings as is, arrays
+/// joined with commas.
+fn setting_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(setting_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod config_file_tests {
+    use super::*;
+    use crate::{data_processor::StatsConfig, window::WindowSpec};
+
+    const FILE: &str = r#"
+        interval = 1000
+        sma_n = 50
+        urls = ["https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT"]
+        tick_size = 0.01
+        provider_gemini = "price=last"
+
+        [[symbols]]
+        url = "https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT"
+        exchange = "okx"
+        interval = 250
+        sma_n = 200
+        windows = ["1m", "5m"]
+        indicators = ["rsi(7)"]
+        tick_size = "0.001"
+    "#;
+
+    #[test]
+    fn test_top_level_keys_read_like_env_vars() {
+        let file: ConfigFile = FILE.parse().unwrap();
+        assert_eq!(file.setting("INTERVAL").as_deref(), Some("1000"));
+        assert_eq!(file.setting("TICK_SIZE").as_deref(), Some("0.01"));
+        assert_eq!(
+            file.setting("URLS").as_deref(),
+            Some("https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT")
+        );
+        assert_eq!(file.setting("PORT"), None);
+        assert_eq!(
+            file.prefixed("PROVIDER_"),
+            vec![("GEMINI".to_string(), "price=last".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_symbol_entry_overrides_stats() {
+        let file: ConfigFile = FILE.parse().unwrap();
+        let entry = &file.symbols[0];
+        assert_eq!(entry.symbol().as_deref(), Some("SOLUSDT"));
+        assert_eq!(entry.interval(), Some(Duration::from_millis(250)));
+
+        let o = entry
+            .stats_override(&IndicatorRegistry::default(), 10.0)
+            .unwrap();
+        let stats = StatsConfig::new(50)
+            .with_stale_after(Some(Duration::from_secs(10)))
+            .with_symbol_overrides(BTreeMap::from([("SOLUSDT".to_string(), o)]));
+
+        let sol = stats.for_symbol("SOLUSDT");
+        assert_eq!(sol.sma_n, 200);
+        assert_eq!(
+            sol.windows,
+            vec!["1m".parse::<WindowSpec>().unwrap(), "5m".parse().unwrap()]
+        );
+        assert_eq!(sol.indicators.len(), 1);
+        assert_eq!(sol.tick_size.unwrap().to_string(), "0.001");
+        assert_eq!(sol.stale_after, Some(Duration::from_millis(2500)));
+
+        let btc = stats.for_symbol("BTCUSDT");
+        assert_eq!(btc.sma_n, 50);
+        assert_eq!(btc.stale_after, Some(Duration::from_secs(10)));
+
+        assert!(
+            "[[symbols]]\nurl = \"x\"\nsma = 3"
+                .parse::<ConfigFile>()
+                .is_err()
+        );
+    }
+}
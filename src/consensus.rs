@@ -0,0 +1,521 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{
+    config::AppConfig,
+    data_processor::{DataProcessor, DataProcessorReader, DataProcessorWriter, StatsConfig},
+    decimal::TickSize,
+    dto::{ConsensusResponse, ContributionResponse},
+    source::{PriceSink, PriceUpdate},
+    utils::now_millis,
+};
+
+/// Default fraction of quotes dropped from each end by the trimmed mean.
+pub const DEFAULT_CONSENSUS_TRIM: f64 = 0.2;
+/// Default age in milliseconds after which a quote no longer counts.
+pub const DEFAULT_CONSENSUS_MAX_AGE: u64 = 10_000;
+
+/// How the latest quotes of several sources are combined into one price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusMethod {
+    /// Middle quote, or the mean of the two middle quotes
+    #[default]
+    Median,
+    /// Mean of the quotes left after dropping the `trim` fraction from each end
+    TrimmedMean,
+    /// Mean weighted by the traded volume each source reports
+    Weighted,
+}
+
+impl fmt::Display for ConsensusMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Median => "median",
+            Self::TrimmedMean => "trimmed_mean",
+            Self::Weighted => "weighted",
+        })
+    }
+}
+
+impl FromStr for ConsensusMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "median" => Ok(Self::Median),
+            "trimmed_mean" | "trimmed" => Ok(Self::TrimmedMean),
+            "weighted" | "liquidity_weighted" => Ok(Self::Weighted),
+            other => Err(format!("unknown consensus method `{other}`")),
+        }
+    }
+}
+
+/// Settings shared by every [`ConsensusBook`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsensusPolicy {
+    /// Combination method
+    pub method: ConsensusMethod,
+    /// Fraction of quotes dropped from each end by `TrimmedMean`, in `[0, 0.5)`
+    pub trim: f64,
+    /// Quotes older than this are ignored
+    pub max_age: Duration,
+}
+
+impl Default for ConsensusPolicy {
+    fn default() -> Self {
+        Self {
+            method: ConsensusMethod::default(),
+            trim: DEFAULT_CONSENSUS_TRIM,
+            max_age: Duration::from_millis(DEFAULT_CONSENSUS_MAX_AGE),
+        }
+    }
+}
+
+impl ConsensusPolicy {
+    /// Computes the weight of every quote in the consensus.
+    ///
+    /// The weights sum to `1`, so the consensus is `Σ weight * price`; a weight of
+    /// `0` means the quote was left out (e.g. trimmed, or not a median).
+    ///
+    /// # Parameters
+    /// - `quotes`: `(price, volume)` of every fresh quote.
+    ///
+    /// # Note
+    /// For `Weighted`, quotes without a volume are weighted by the mean volume of
+    /// the others; if no quote has a positive volume, every quote weighs the same.
+    pub fn weights(&self, quotes: &[(f64, Option<f64>)]) -> Vec<f64> {
+        let n = quotes.len();
+        let mut weights = vec![0.0; n];
+        if n == 0 {
+            return weights;
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| quotes[a].0.total_cmp(&quotes[b].0));
+
+        match self.method {
+            ConsensusMethod::Median => {
+                if n % 2 == 1 {
+                    weights[order[n / 2]] = 1.0;
+                } else {
+                    weights[order[n / 2 - 1]] = 0.5;
+                    weights[order[n / 2]] = 0.5;
+                }
+            }
+            ConsensusMethod::TrimmedMean => {
+                // Always keep at least one quote
+                let cut = ((n as f64 * self.trim.clamp(0.0, 0.5)) as usize).min((n - 1) / 2);
+                let kept = &order[cut..n - cut];
+                let share = 1.0 / kept.len() as f64;
+                kept.iter().for_each(|&i| weights[i] = share);
+            }
+            ConsensusMethod::Weighted => {
+                let known: Vec<f64> = quotes
+                    .iter()
+                    .filter_map(|(_, v)| v.filter(|v| v.is_finite() && *v > 0.0))
+                    .collect();
+                let fallback = if known.is_empty() {
+                    1.0
+                } else {
+                    known.iter().sum::<f64>() / known.len() as f64
+                };
+                quotes.iter().enumerate().for_each(|(i, (_, v))| {
+                    weights[i] = v.filter(|v| v.is_finite() && *v > 0.0).unwrap_or(fallback);
+                });
+                let total: f64 = weights.iter().sum();
+                weights.iter_mut().for_each(|w| *w /= total);
+            }
+        }
+        weights
+    }
+
+    /// Combines `quotes` into one price, `None` if there are none.
+    pub fn combine(&self, quotes: &[(f64, Option<f64>)]) -> Option<f64> {
+        if quotes.is_empty() {
+            return None;
+        }
+        let weights = self.weights(quotes);
+        Some(quotes.iter().zip(weights).map(|((p, _), w)| p * w).sum())
+    }
+}
+
+/// Latest quote of one source.
+#[derive(Debug, Clone, Copy)]
+struct Quote {
+    price: f64,
+    volume: Option<f64>,
+    received_at: u64,
+}
+
+impl Quote {
+    /// Returns `true` if the quote is not older than `max_age` at `now`.
+    fn is_fresh(&self, now: u64, max_age: Duration) -> bool {
+        now.saturating_sub(self.received_at) <= max_age.as_millis() as u64
+    }
+}
+
+/// Mutable part of a [`ConsensusBook`].
+struct BookState {
+    /// How quotes are combined, replaced by reloads
+    policy: ConsensusPolicy,
+    /// Source name -> latest quote
+    quotes: BTreeMap<String, Quote>,
+    /// Single writer of the symbol's statistics
+    writer: DataProcessorWriter,
+    /// Tick size the consensus and the report are rounded to
+    tick_size: Option<TickSize>,
+    /// Last consensus written
+    price: f64,
+    /// Unix time in milliseconds of the last consensus
+    updated_at: u64,
+}
+
+/// Combines the quotes of every source attached to one logical symbol.
+///
+/// Each source's `RpcManager` submits through its own [`ConsensusInput`]; every
+/// submission recomputes the consensus over the fresh quotes and writes it to the
+/// symbol's `DataProcessorWriter`, so the statistics only ever see consensus prices.
+/// With a tick size the consensus is snapped to it like any other price, and the
+/// report rounds every price to it.
+pub struct ConsensusBook {
+    symbol: String,
+    state: Mutex<BookState>,
+}
+
+impl ConsensusBook {
+    /// Creates a book seeded with one price per source.
+    ///
+    /// # Parameters
+    /// - `symbol`: Logical symbol, e.g. `"BTCUSDT"`.
+    /// - `policy`: How quotes are combined.
+    /// - `stats`: Statistics of the symbol's `DataProcessor`.
+    /// - `seeds`: Source name -> seed price; must not be empty.
+    ///
+    /// # Returns
+    /// The book and the reader of the consensus statistics.
+    pub fn new(
+        symbol: String,
+        policy: ConsensusPolicy,
+        stats: &StatsConfig,
+        seeds: impl IntoIterator<Item = (String, f64)>,
+    ) -> (Self, DataProcessorReader) {
+        let now = now_millis();
+        let quotes: BTreeMap<String, Quote> = seeds
+            .into_iter()
+            .map(|(source, price)| {
+                let quote = Quote {
+                    price,
+                    volume: None,
+                    received_at: now,
+                };
+                (source, quote)
+            })
+            .collect();
+        let seeded: Vec<(f64, Option<f64>)> = quotes.values().map(|q| (q.price, None)).collect();
+        let stats = stats.for_symbol(&symbol);
+        let price = policy
+            .combine(&seeded)
+            .map(|price| round(stats.tick_size, price))
+            .expect("consensus needs at least one seed");
+
+        let (reader, writer) = DataProcessor::split_with(&stats, price);
+        let state = BookState {
+            policy,
+            quotes,
+            writer,
+            tick_size: stats.tick_size,
+            price,
+            updated_at: now,
+        };
+        let book = Self {
+            symbol,
+            state: Mutex::new(state),
+        };
+        (book, reader)
+    }
+
+    /// Logical symbol of the book.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Records the latest quote of `source` and writes the new consensus.
+    ///
+    /// # Returns
+    /// The consensus written.
+    pub fn submit(&self, source: &str, update: &PriceUpdate) -> f64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let quote = Quote {
+            price: update.price,
+            volume: update.volume,
+            received_at: update.received_at,
+        };
+        state.quotes.insert(source.to_string(), quote);
+
+        let fresh: Vec<(f64, Option<f64>)> = state
+            .quotes
+            .values()
+            .filter(|q| q.is_fresh(update.received_at, state.policy.max_age))
+            .map(|q| (q.price, q.volume))
+            .collect();
+        // The submitted quote is always fresh, so there is at least one
+        if let Some(price) = state.policy.combine(&fresh) {
+            let price = round(state.tick_size, price);
+            state
+                .writer
+                .write_trade(price, update.received_at, update.quantity.unwrap_or(0.0));
+            state.price = price;
+            state.updated_at = update.received_at;
+        }
+        state.price
+    }
+
+    /// Builds the `/consensus` report: the consensus and how each source contributed.
+    pub fn report(&self) -> ConsensusResponse {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = now_millis();
+
+        let fresh: Vec<&String> = state
+            .quotes
+            .iter()
+            .filter(|(_, q)| q.is_fresh(now, state.policy.max_age))
+            .map(|(source, _)| source)
+            .collect();
+        let fresh_quotes: Vec<(f64, Option<f64>)> = fresh
+            .iter()
+            .map(|s| (state.quotes[*s].price, state.quotes[*s].volume))
+            .collect();
+        let weights = state.policy.weights(&fresh_quotes);
+
+        let sources = state
+            .quotes
+            .iter()
+            .map(|(source, q)| {
+                let weight = fresh
+                    .iter()
+                    .position(|s| *s == source)
+                    .map_or(0.0, |i| weights[i]);
+                let price = round(state.tick_size, q.price);
+                let deviation = round(state.tick_size, price - state.price);
+                ContributionResponse {
+                    source: source.clone(),
+                    price,
+                    volume: q.volume,
+                    weight,
+                    stale: !q.is_fresh(now, state.policy.max_age),
+                    age_ms: now.saturating_sub(q.received_at),
+                    deviation,
+                    deviation_bps: deviation / state.price * 10_000.0,
+                }
+            })
+            .collect();
+
+        ConsensusResponse {
+            symbol: self.symbol.clone(),
+            method: state.policy.method,
+            price: state.price,
+            updated_at: state.updated_at,
+            sources,
+        }
+    }
+
+    /// Combines the quotes submitted from now on following `policy`.
+    pub fn set_policy(&self, policy: ConsensusPolicy) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.policy = policy;
+    }
+
+    /// Switches the consensus statistics to `stats`, as resolved for the symbol
+    /// (see [`DataProcessorWriter::reconfigure`]).
+    pub fn set_stats(&self, stats: &StatsConfig) {
+        let stats = stats.for_symbol(&self.symbol);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.writer.reconfigure(&stats);
+        state.tick_size = stats.tick_size;
+    }
+}
+
+/// `price` rounded to `tick_size`, if any.
+fn round(tick_size: Option<TickSize>, price: f64) -> f64 {
+    tick_size.map_or(price, |tick| tick.round(price))
+}
+
+/// [`PriceSink`] submitting one source's prices to a [`ConsensusBook`].
+pub struct ConsensusInput {
+    book: Arc<ConsensusBook>,
+    source: String,
+}
+
+impl ConsensusInput {
+    /// Creates the input of `source` into `book`.
+    pub fn new(book: Arc<ConsensusBook>, source: String) -> Self {
+        Self { book, source }
+    }
+}
+
+impl PriceSink for ConsensusInput {
+    fn write(&self, update: &PriceUpdate) {
+        self.book.submit(&self.source, update);
+    }
+
+    fn reconfigure(&mut self, _symbol: &str, config: &AppConfig) {
+        // Every input of the book applies the same settings; repeating them is a no-op
+        self.book.set_policy(config.consensus_policy);
+        self.book.set_stats(&config.stats);
+    }
+}
+
+#[cfg(test)]
+mod consensus_tests {
+    use super::*;
+
+    fn policy(method: ConsensusMethod) -> ConsensusPolicy {
+        ConsensusPolicy {
+            method,
+            ..ConsensusPolicy::default()
+        }
+    }
+
+    fn prices(values: &[f64]) -> Vec<(f64, Option<f64>)> {
+        values.iter().map(|p| (*p, None)).collect()
+    }
+
+    #[test]
+    fn test_median_ignores_outlier() {
+        let median = policy(ConsensusMethod::Median);
+        assert_eq!(
+            median.combine(&prices(&[101.0, 100.0, 5000.0])),
+            Some(101.0)
+        );
+        assert_eq!(
+            median.combine(&prices(&[101.0, 100.0, 5000.0, 99.0])),
+            Some(100.5)
+        );
+        assert_eq!(
+            median.weights(&prices(&[101.0, 100.0, 5000.0])),
+            [1.0, 0.0, 0.0]
+        );
+        assert_eq!(median.combine(&[]), None);
+    }
+
+    #[test]
+    fn test_trimmed_mean_drops_both_ends() {
+        let trimmed = ConsensusPolicy {
+            trim: 0.2,
+            ..policy(ConsensusMethod::TrimmedMean)
+        };
+        let quotes = prices(&[1.0, 100.0, 102.0, 104.0, 9000.0]);
+        assert_eq!(trimmed.combine(&quotes), Some(102.0));
+        assert_eq!(trimmed.weights(&quotes)[0], 0.0);
+
+        // Never trims everything away
+        let all = ConsensusPolicy {
+            trim: 0.49,
+            ..trimmed
+        };
+        assert_eq!(all.combine(&prices(&[1.0, 2.0])), Some(1.5));
+    }
+
+    #[test]
+    fn test_weighted_uses_volume_with_fallback() {
+        let weighted = policy(ConsensusMethod::Weighted);
+        let quotes = [(100.0, Some(3.0)), (110.0, Some(1.0))];
+        assert_eq!(weighted.combine(&quotes), Some(102.5));
+
+        // The missing volume counts as the mean of the known ones (2.0)
+        let quotes = [(100.0, Some(3.0)), (110.0, Some(1.0)), (104.0, None)];
+        let weights = weighted.weights(&quotes);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((weights[2] - 2.0 / 6.0).abs() < 1e-12);
+
+        assert_eq!(weighted.combine(&prices(&[100.0, 110.0])), Some(105.0));
+    }
+
+    #[test]
+    fn test_book_writes_consensus_and_reports_contributions() {
+        let seeds = [("a".to_string(), 100.0), ("b".to_string(), 102.0)];
+        let (book, reader) = ConsensusBook::new(
+            "BTCUSDT".into(),
+            policy(ConsensusMethod::Median),
+            &StatsConfig::new(4),
+            seeds,
+        );
+        assert_eq!(reader.read().data.max, 101.0);
+
+        assert_eq!(
+            book.submit("c", &PriceUpdate::new("BTCUSDT", 104.0, None)),
+            102.0
+        );
+        let snap = reader.read();
+        assert_eq!(snap.version, 1);
+        assert_eq!(snap.data.max, 102.0);
+        assert_eq!(snap.data.data_point, 2);
+
+        let report = book.report();
+        assert_eq!(report.symbol, "BTCUSDT");
+        assert_eq!(report.price, 102.0);
+        let b = report.sources.iter().find(|s| s.source == "b").unwrap();
+        assert_eq!(b.weight, 1.0);
+        assert_eq!(b.deviation, 0.0);
+        let c = report.sources.iter().find(|s| s.source == "c").unwrap();
+        assert_eq!(c.weight, 0.0);
+        assert_eq!(c.deviation, 2.0);
+        assert!(!c.stale);
+    }
+
+    #[test]
+    fn test_book_rounds_to_the_tick_size() {
+        let stats = StatsConfig::new(4).with_tick_sizes(Some("0.01".parse().unwrap()), [].into());
+        let seeds = [("a".to_string(), 0.1), ("b".to_string(), 0.2)];
+        let (book, reader) = ConsensusBook::new(
+            "BTCUSDT".into(),
+            policy(ConsensusMethod::Median),
+            &stats,
+            seeds,
+        );
+        assert_eq!(reader.read().data.max, 0.15);
+
+        // The median 0.1555 is written and reported as 0.16
+        let price = book.submit("b", &PriceUpdate::new("BTCUSDT", 0.211, None));
+        assert_eq!(price, 0.16);
+        assert_eq!(reader.read().data.max, 0.16);
+        let report = book.report();
+        assert_eq!(report.price, 0.16);
+        let a = report.sources.iter().find(|s| s.source == "a").unwrap();
+        assert_eq!(a.deviation, -0.06);
+        let b = report.sources.iter().find(|s| s.source == "b").unwrap();
+        assert_eq!((b.price, b.deviation), (0.21, 0.05));
+    }
+
+    #[test]
+    fn test_stale_quotes_are_ignored() {
+        let seeds = [("a".to_string(), 100.0), ("b".to_string(), 200.0)];
+        let (book, _reader) = ConsensusBook::new(
+            "BTCUSDT".into(),
+            policy(ConsensusMethod::Median),
+            &StatsConfig::new(4),
+            seeds,
+        );
+
+        // Submitted long after the seeds, which are then stale
+        let mut update = PriceUpdate::new("BTCUSDT", 300.0, None);
+        update.received_at += DEFAULT_CONSENSUS_MAX_AGE + 1;
+        assert_eq!(book.submit("c", &update), 300.0);
+    }
+
+    #[test]
+    fn test_method_parse() {
+        assert_eq!("median".parse(), Ok(ConsensusMethod::Median));
+        assert_eq!("Trimmed_Mean".parse(), Ok(ConsensusMethod::TrimmedMean));
+        assert_eq!("weighted".parse(), Ok(ConsensusMethod::Weighted));
+        assert!("mode".parse::<ConsensusMethod>().is_err());
+    }
+}
@@ -1,34 +1,410 @@
 use std::{
     alloc::{Layout, alloc, dealloc},
-    cell::Cell,
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt, iter,
     marker::PhantomData,
+    mem::{self, size_of, transmute},
     ptr::NonNull,
+    str::FromStr,
     sync::{
-        Arc,
-        atomic::{
-            AtomicUsize,
-            Ordering::{Acquire, Release},
-            fence,
-        },
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering::Relaxed},
     },
+    time::Duration,
 };
 
-use crate::utils::{bound_index, calculate_stream_mean};
+use crate::{
+    candles::{Candle, CandleHistory, DEFAULT_CANDLE_LIMIT},
+    decimal::{Decimal, TickSize},
+    indicator::{Indicator, IndicatorSpec},
+    quantile::{P2Quantile, QuantileSpec, SortedWindow},
+    seqlock::SeqLock,
+    utils::{NeumaierSum, bound_index, calculate_stream_mean, now_millis},
+    window::{MonotonicDeque, TimeWindow, WINDOW_STATS_WORDS, WindowSpec, WindowStats},
+};
 
 /// Raw statistical data snapshot.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct RawData {
-    /// Minimum value observed
+    /// Minimum value observed since start (session low)
     pub min: f64,
-    /// Maximum value observed
+    /// Maximum value observed since start (session high)
     pub max: f64,
+    /// Minimum over the SMA window (window low)
+    pub window_min: f64,
+    /// Maximum over the SMA window (window high)
+    pub window_max: f64,
     /// Streaming mean (average) of all values
     pub curr_avg: f64,
     /// Simple moving average over the configured window
     pub sma: f64,
+    /// Population variance over the SMA window
+    pub variance: f64,
+    /// Standard deviation over the SMA window
+    pub std_dev: f64,
+    /// Upper Bollinger band, `sma + k * std_dev`
+    pub bollinger_upper: f64,
+    /// Lower Bollinger band, `sma - k * std_dev`
+    pub bollinger_lower: f64,
     /// Number of data points observed
     pub data_point: u64,
+    /// Local time of the seed sample in Unix milliseconds
+    pub first_seen: u64,
+    /// Local receive time of the latest sample in Unix milliseconds
+    pub last_update: u64,
+    /// Latest exchange-provided event time in Unix milliseconds, `0` if the source
+    /// never reported one
+    pub event_time: u64,
+}
+
+/// Number of 64-bit words `RawData` occupies inside the seqlock payload.
+const RAW_DATA_WORDS: usize = size_of::<RawData>() / size_of::<u64>();
+
+// Every field of `RawData` is 8 bytes wide, so it maps exactly onto `u64` words
+const _: () = assert!(size_of::<RawData>().is_multiple_of(size_of::<u64>()));
+
+impl RawData {
+    #[inline(always)]
+    fn to_words(self) -> [u64; RAW_DATA_WORDS] {
+        // SAFETY: RawData is `repr(C)` and made only of 8-byte plain-old-data fields
+        unsafe { transmute::<RawData, [u64; RAW_DATA_WORDS]>(self) }
+    }
+
+    #[inline(always)]
+    fn from_words(words: [u64; RAW_DATA_WORDS]) -> Self {
+        // SAFETY: every bit pattern is a valid `f64` / `u64`
+        unsafe { transmute::<[u64; RAW_DATA_WORDS], RawData>(words) }
+    }
+}
+
+/// Smoothing of an exponential moving average.
+///
+/// Each write updates the average as `ema += alpha * (price - ema)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmaSpec {
+    /// Span `N` in samples, `alpha = 2 / (N + 1)`
+    Span(u32),
+    /// Smoothing factor in `(0, 1]`
+    Alpha(f64),
+}
+
+impl EmaSpec {
+    /// Smoothing factor applied per write.
+    pub fn alpha(self) -> f64 {
+        match self {
+            Self::Span(span) => 2.0 / (span as f64 + 1.0),
+            Self::Alpha(alpha) => alpha,
+        }
+    }
+}
+
+impl fmt::Display for EmaSpec {
+    /// Name of the EMA in responses: `ema_20` or `ema_alpha_0.1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Span(span) => write!(f, "ema_{span}"),
+            Self::Alpha(alpha) => write!(f, "ema_alpha_{alpha}"),
+        }
+    }
+}
+
+impl FromStr for EmaSpec {
+    type Err = String;
+
+    /// Parses a span (`20`) or a smoothing factor (`0.1`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(span) = s.parse::<u32>() {
+            return match span {
+                0 => Err("EMA span must be > 0".to_string()),
+                span => Ok(Self::Span(span)),
+            };
+        }
+        match s.parse::<f64>() {
+            Ok(alpha) if alpha > 0.0 && alpha <= 1.0 => Ok(Self::Alpha(alpha)),
+            _ => Err(format!(
+                "invalid EMA `{s}`, expected a span or alpha in (0, 1]"
+            )),
+        }
+    }
+}
+
+/// Default number of standard deviations between the SMA and each Bollinger band.
+pub const DEFAULT_BOLLINGER_K: f64 = 2.0;
+
+/// Statistics computed by every `DataProcessor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsConfig {
+    /// Window size of the simple moving average
+    pub sma_n: usize,
+    /// Exponential moving averages maintained next to the SMA
+    pub emas: Vec<EmaSpec>,
+    /// Standard deviations between the SMA and each Bollinger band
+    pub bollinger_k: f64,
+    /// Time-based rolling windows maintained next to the count-based SMA window
+    pub windows: Vec<WindowSpec>,
+    /// Resolutions of the OHLCV candles kept per symbol
+    pub candles: Vec<WindowSpec>,
+    /// Number of candles retained per resolution
+    pub candle_limit: usize,
+    /// Quantiles estimated over the lifetime stream and the SMA window
+    pub quantiles: Vec<QuantileSpec>,
+    /// Pluggable indicators updated with every price
+    pub indicators: Vec<IndicatorSpec>,
+    /// Tick size of the symbol, `None` to keep prices as plain `f64`
+    pub tick_size: Option<TickSize>,
+    /// Symbol -> tick size overriding `tick_size`, resolved by `for_symbol`
+    pub symbol_tick_sizes: BTreeMap<String, TickSize>,
+    /// Age of the latest sample after which the symbol is reported stale, `None`
+    /// to never report it stale
+    pub stale_after: Option<Duration>,
+    /// Symbol -> settings overriding the ones above, resolved by `for_symbol`
+    pub symbol_overrides: BTreeMap<String, StatsOverride>,
+}
+
+/// Settings of a single symbol replacing those of its `StatsConfig`; `None`
+/// keeps the shared value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsOverride {
+    pub sma_n: Option<usize>,
+    pub emas: Option<Vec<EmaSpec>>,
+    pub windows: Option<Vec<WindowSpec>>,
+    pub candles: Option<Vec<WindowSpec>>,
+    pub quantiles: Option<Vec<QuantileSpec>>,
+    pub indicators: Option<Vec<IndicatorSpec>>,
+    pub tick_size: Option<TickSize>,
+    pub stale_after: Option<Duration>,
+}
+
+impl StatsConfig {
+    /// SMA over `sma_n` samples, no EMA, `DEFAULT_BOLLINGER_K` bands, no time window,
+    /// no candles, no quantiles, no indicator, no tick size and never stale.
+    pub fn new(sma_n: usize) -> Self {
+        Self {
+            sma_n,
+            emas: Vec::new(),
+            bollinger_k: DEFAULT_BOLLINGER_K,
+            windows: Vec::new(),
+            candles: Vec::new(),
+            candle_limit: DEFAULT_CANDLE_LIMIT,
+            quantiles: Vec::new(),
+            indicators: Vec::new(),
+            tick_size: None,
+            symbol_tick_sizes: BTreeMap::new(),
+            stale_after: None,
+            symbol_overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the width of the Bollinger bands in standard deviations.
+    pub fn with_bollinger_k(mut self, bollinger_k: f64) -> Self {
+        self.bollinger_k = bollinger_k;
+        self
+    }
+
+    /// Sets the exponential moving averages.
+    pub fn with_emas(mut self, emas: Vec<EmaSpec>) -> Self {
+        self.emas = emas;
+        self
+    }
+
+    /// Sets the time-based rolling windows.
+    pub fn with_windows(mut self, windows: Vec<WindowSpec>) -> Self {
+        self.windows = windows;
+        self
+    }
+
+    /// Sets the candle resolutions and how many candles each retains.
+    pub fn with_candles(mut self, candles: Vec<WindowSpec>, candle_limit: usize) -> Self {
+        self.candles = candles;
+        self.candle_limit = candle_limit;
+        self
+    }
+
+    /// Sets the quantiles.
+    pub fn with_quantiles(mut self, quantiles: Vec<QuantileSpec>) -> Self {
+        self.quantiles = quantiles;
+        self
+    }
+
+    /// Sets the pluggable indicators.
+    pub fn with_indicators(mut self, indicators: Vec<IndicatorSpec>) -> Self {
+        self.indicators = indicators;
+        self
+    }
+
+    /// Sets the default tick size and the per-symbol overrides.
+    pub fn with_tick_sizes(
+        mut self,
+        tick_size: Option<TickSize>,
+        symbol_tick_sizes: BTreeMap<String, TickSize>,
+    ) -> Self {
+        self.tick_size = tick_size;
+        self.symbol_tick_sizes = symbol_tick_sizes;
+        self
+    }
+
+    /// Sets the age of the latest sample after which a symbol is stale.
+    pub fn with_stale_after(mut self, stale_after: Option<Duration>) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    /// Sets the per-symbol overrides.
+    pub fn with_symbol_overrides(
+        mut self,
+        symbol_overrides: BTreeMap<String, StatsOverride>,
+    ) -> Self {
+        self.symbol_overrides = symbol_overrides;
+        self
+    }
+
+    /// The configuration of `symbol`, with its tick size and other overrides applied.
+    pub fn for_symbol(&self, symbol: &str) -> Self {
+        let mut config = self.clone();
+        config.symbol_tick_sizes.clear();
+        config.symbol_overrides.clear();
+        if let Some(tick) = self.symbol_tick_sizes.get(symbol) {
+            config.tick_size = Some(*tick);
+        }
+        let Some(o) = self.symbol_overrides.get(symbol) else {
+            return config;
+        };
+        if let Some(sma_n) = o.sma_n {
+            config.sma_n = sma_n;
+        }
+        if let Some(emas) = &o.emas {
+            config.emas = emas.clone();
+        }
+        if let Some(windows) = &o.windows {
+            config.windows = windows.clone();
+        }
+        if let Some(candles) = &o.candles {
+            config.candles = candles.clone();
+        }
+        if let Some(quantiles) = &o.quantiles {
+            config.quantiles = quantiles.clone();
+        }
+        if let Some(indicators) = &o.indicators {
+            config.indicators = indicators.clone();
+        }
+        if let Some(tick) = o.tick_size {
+            config.tick_size = Some(tick);
+        }
+        if let Some(stale_after) = o.stale_after {
+            config.stale_after = Some(stale_after);
+        }
+        config
+    }
+
+    /// Describes how `DataProcessorWriter::reconfigure` switches a processor
+    /// computing `old` to this configuration, one entry per changed statistic.
+    ///
+    /// Statistics of both configurations carry on unchanged, as does a new
+    /// staleness limit. The others are rebuilt from the state the processor keeps,
+    /// the SMA window and the time windows, so they may cover less history than
+    /// those of a processor started with this configuration.
+    pub fn changes_from(&self, old: &StatsConfig) -> Vec<String> {
+        let mut changes = Vec::new();
+        let (from, to) = (old.sma_n, self.sma_n);
+        if to < from {
+            changes.push(format!("sma_n {from} -> {to}: kept the latest {to} prices"));
+        } else if to > from {
+            changes.push(format!(
+                "sma_n {from} -> {to}: filled from the latest {from} prices, the oldest one repeated"
+            ));
+        }
+        if self.bollinger_k != old.bollinger_k {
+            changes.push(format!(
+                "bollinger_k {} -> {}: bands recomputed",
+                old.bollinger_k, self.bollinger_k
+            ));
+        }
+        if self.tick_size != old.tick_size {
+            let name = |tick: Option<TickSize>| tick.map_or("none".into(), |t| t.to_string());
+            changes.push(format!(
+                "tick size {} -> {}: SMA window snapped to it",
+                name(old.tick_size),
+                name(self.tick_size)
+            ));
+        }
+
+        fn labels<T: fmt::Display>(prefix: &str, specs: &[T]) -> Vec<String> {
+            specs.iter().map(|spec| format!("{prefix}{spec}")).collect()
+        }
+        let mut added_removed = |old: Vec<String>, new: Vec<String>, added: &str| {
+            for name in new.iter().filter(|name| !old.contains(name)) {
+                changes.push(format!("{name} added: {added}"));
+            }
+            for name in old.iter().filter(|name| !new.contains(name)) {
+                changes.push(format!("{name} removed"));
+            }
+        };
+        added_removed(
+            labels("", &old.emas),
+            labels("", &self.emas),
+            "seeded from the SMA window",
+        );
+        added_removed(
+            labels("", &old.quantiles),
+            labels("", &self.quantiles),
+            "lifetime estimate seeded from the SMA window",
+        );
+        added_removed(
+            labels("", &old.indicators),
+            labels("", &self.indicators),
+            "warmed up from the SMA window",
+        );
+        let filled = match old.windows.iter().max() {
+            Some(longest) => format!("filled from window {longest}"),
+            None => "seeded with the latest price".to_string(),
+        };
+        added_removed(
+            labels("window ", &old.windows),
+            labels("window ", &self.windows),
+            &filled,
+        );
+        added_removed(
+            labels("candles ", &old.candles),
+            labels("candles ", &self.candles),
+            "opened with the latest price",
+        );
+        if self.candle_limit != old.candle_limit {
+            changes.push(format!(
+                "candle_limit {} -> {}",
+                old.candle_limit, self.candle_limit
+            ));
+        }
+        changes
+    }
+}
+
+/// A consistent statistics snapshot together with the version it was published at.
+///
+/// The version starts at `0` for the seed value and increases by one per write and
+/// per reconfiguration, so two snapshots with the same version are guaranteed to be
+/// identical.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Number of writes published before this snapshot
+    pub version: u64,
+    /// Statistics at `version`
+    pub data: RawData,
+    /// Exponential moving averages at `version`, in `StatsConfig::emas` order
+    pub emas: Vec<f64>,
+    /// Approximate lifetime quantiles at `version`, in `StatsConfig::quantiles` order
+    pub quantiles: Vec<f64>,
+    /// Exact quantiles over the SMA window at `version`, in `StatsConfig::quantiles` order
+    pub window_quantiles: Vec<f64>,
+    /// Indicator outputs at `version`, in `DataProcessorReader::indicator_names` order
+    pub indicators: Vec<f64>,
+    /// Prices each indicator still needs before all its outputs have a value, in
+    /// `StatsConfig::indicators` order
+    pub samples_needed: Vec<u64>,
+    /// Time window statistics at `version`, in `StatsConfig::windows` order
+    pub windows: Vec<WindowStats>,
 }
 
 /// Unsafe fixed-size queue for internal numeric storage.
@@ -54,6 +430,9 @@ impl<T: Copy> UnsafeQueue<T> {
     }
 
     /// Sets value at the given index. Unsafe: no bounds checks in release.
+    ///
+    /// # Safety
+    /// `idx` must be less than the queue capacity.
     #[inline(always)]
     pub unsafe fn set(&self, val: T, idx: usize) {
         debug_assert!(idx < self.capacity);
@@ -61,6 +440,9 @@ impl<T: Copy> UnsafeQueue<T> {
     }
 
     /// Gets value at the given index. Unsafe: no bounds checks in release.
+    ///
+    /// # Safety
+    /// `idx` must be less than the queue capacity and the slot must have been set.
     #[inline(always)]
     pub unsafe fn get(&self, idx: usize) -> T {
         debug_assert!(idx < self.capacity);
@@ -68,6 +450,9 @@ impl<T: Copy> UnsafeQueue<T> {
     }
 
     /// Swaps value at index with a new value, returning the old value.
+    ///
+    /// # Safety
+    /// `idx` must be less than the queue capacity and the slot must have been set.
     #[inline(always)]
     pub unsafe fn swap(&self, idx: usize, val: T) -> T {
         debug_assert!(idx < self.capacity);
@@ -86,22 +471,161 @@ impl<T: Copy> Drop for UnsafeQueue<T> {
     }
 }
 
-/// A lock-free processor that maintains streaming statistics and SMA (Simple Moving Average).
+/// Running sum of the SMA buffer.
+#[derive(Clone, Copy)]
+enum WindowSum {
+    /// Compensated floating-point sum
+    Float(NeumaierSum),
+    /// Exact fixed-point sum in ticks, for symbols with a tick size
+    Ticks(TickSize, i128),
+}
+
+impl WindowSum {
+    fn new(tick_size: Option<TickSize>) -> Self {
+        match tick_size {
+            Some(tick) => Self::Ticks(tick, 0),
+            None => Self::Float(NeumaierSum::default()),
+        }
+    }
+
+    /// Replaces `popped` by `pushed` in the sum.
+    fn replace(&mut self, popped: f64, pushed: f64) {
+        match self {
+            Self::Float(sum) => {
+                sum.add(pushed);
+                sum.add(-popped);
+            }
+            Self::Ticks(tick, sum) => {
+                *sum += tick.ticks(pushed) as i128 - tick.ticks(popped) as i128;
+            }
+        }
+    }
+
+    fn value(&self) -> f64 {
+        match self {
+            Self::Float(sum) => sum.value(),
+            Self::Ticks(tick, sum) => tick.price(*sum),
+        }
+    }
+}
+
+/// Minimum or maximum over the last `window` data points.
+struct RollingExtreme {
+    /// Candidates keyed by data point; never holds more than `window` entries
+    deque: RefCell<MonotonicDeque>,
+    /// Number of data points covered
+    window: u64,
+}
+
+impl RollingExtreme {
+    /// Creates the tracker seeded with `initial_data` as data point `1`.
+    fn new(window: usize, is_max: bool, initial_data: f64) -> Self {
+        Self::with_samples(window, is_max, [(1, initial_data)])
+    }
+
+    /// Creates the tracker holding `(data point, value)` samples, oldest first.
+    fn with_samples(
+        window: usize,
+        is_max: bool,
+        samples: impl IntoIterator<Item = (u64, f64)>,
+    ) -> Self {
+        let mut deque = MonotonicDeque::new(is_max, window);
+        samples
+            .into_iter()
+            .for_each(|(point, value)| deque.push(point, value));
+        Self {
+            deque: deque.into(),
+            window: window as u64,
+        }
+    }
+
+    /// Adds `value` as data point `data_point` and returns the extreme of the window
+    /// ending at it.
+    fn push(&self, data_point: u64, value: f64) -> f64 {
+        let mut deque = self.deque.borrow_mut();
+        deque.push(data_point, value);
+        // Evict the points that left the window; `value` itself always stays
+        deque.evict_before((data_point + 1).saturating_sub(self.window));
+        deque.front().expect("latest value is in the window")
+    }
+}
+
+/// A lock-free processor that maintains streaming statistics, SMA (Simple Moving Average)
+/// and EMAs (Exponential Moving Averages).
 ///
 /// Can be split into a `DataProcessorReader` and `DataProcessorWriter` for
-/// concurrent single-writer, multiple-reader usage.
+/// concurrent single-writer, multiple-reader usage. Snapshots are published
+/// through a [`SeqLock`], so a reader never observes a partially written `RawData`.
+/// The seqlock payload is `RawData`, one word per EMA, two words per quantile
+/// (lifetime, then window), one word per indicator output, one word per indicator
+/// with the samples it still needs, then one `WindowStats` per time window.
+///
+/// A reconfiguration replaces the processor by a successor carrying its state
+/// over (see [`DataProcessorWriter::reconfigure`]). Readers share the slot the
+/// writer publishes its current processor in, so a replaced processor is freed
+/// once the last [`DataProcessorView`] of it is dropped.
 pub struct DataProcessor {
-    raw_data: [Cell<RawData>; 2],
-    /// Indicates which index (0 or 1) is safe for readers.
-    active2read: AtomicUsize,
+    /// Published snapshot shared with readers
+    published: SeqLock,
+    /// Writer-local copy of the latest snapshot
+    curr_raw: Cell<RawData>,
     /// Circular buffer for SMA calculations
     queue: UnsafeQueue<f64>,
-    /// Current running SMA value
-    curr_sma_avg: Cell<f64>,
+    /// Compensated (or exact, with a tick size) sum of the SMA buffer, so the SMA
+    /// does not drift over long runs
+    curr_sma_sum: Cell<WindowSum>,
+    /// Tick size every price is snapped to
+    tick_size: Option<TickSize>,
+    /// Age in milliseconds of the latest sample after which the data is stale,
+    /// `0` for never; readers may change it at runtime
+    stale_after_ms: AtomicU64,
     /// Current index in the circular SMA buffer
     curr_queue_idx: Cell<usize>,
+    /// Sum of squared deviations from the SMA over the window (windowed Welford)
+    curr_sma_m2: Cell<f64>,
+    /// Bollinger band width in standard deviations
+    bollinger_k: f64,
+    /// Minimum over the SMA window
+    window_min: RollingExtreme,
+    /// Maximum over the SMA window
+    window_max: RollingExtreme,
+    /// Configured EMAs
+    ema_specs: Box<[EmaSpec]>,
+    /// Smoothing factor of each EMA
+    ema_alphas: Box<[f64]>,
+    /// Current value of each EMA
+    curr_emas: Box<[Cell<f64>]>,
+    /// Configured quantiles
+    quantile_specs: Box<[QuantileSpec]>,
+    /// P² estimator of each quantile over the lifetime stream
+    lifetime_quantiles: RefCell<Box<[P2Quantile]>>,
+    /// Sorted copy of the SMA buffer, only kept when quantiles are configured
+    sorted_window: RefCell<Option<SortedWindow>>,
+    /// Configured indicators
+    indicator_specs: Box<[IndicatorSpec]>,
+    /// Instances of the configured indicators with their number of outputs
+    indicators: RefCell<Box<[IndicatorSlot]>>,
+    /// Output names of every indicator, flattened in configuration order
+    indicator_names: Box<[String]>,
+    /// Scratch buffer the indicators write their outputs into
+    indicator_values: RefCell<Box<[f64]>>,
+    /// Scratch buffer the indicators write their remaining warm-up into
+    samples_needed: RefCell<Box<[u64]>>,
+    /// Configured time windows
+    window_specs: Box<[WindowSpec]>,
+    /// Samples and running statistics of each time window
+    time_windows: RefCell<Box<[TimeWindow]>>,
+    /// Time of the latest sample in Unix milliseconds
+    last_at: Cell<u64>,
+    /// OHLCV candles of every configured resolution
+    candles: CandleHistory,
+    /// Statistics computed, with the overrides of the symbol applied
+    config: RefCell<StatsConfig>,
 }
 
+/// Slot holding the processor currently written, shared by the writer and readers.
+type CurrentProcessor = Arc<RwLock<Arc<DataProcessor>>>;
+
 impl DataProcessor {
     /// Splits the processor into a reader and writer pair.
     ///
@@ -112,38 +636,149 @@ impl DataProcessor {
         sma_n_size: usize,
         initial_data: f64,
     ) -> (DataProcessorReader, DataProcessorWriter) {
+        Self::split_with(&StatsConfig::new(sma_n_size), initial_data)
+    }
+
+    /// Splits a processor computing the statistics of `config` into a reader and
+    /// writer pair.
+    ///
+    /// # Arguments
+    /// - `config`: SMA window, EMAs, quantiles, indicators, time windows and candles
+    ///   to maintain
+    /// - `initial_data`: initial seed value for statistics, also seeds every EMA,
+    ///   indicator, time window and candle as a sample taken now
+    pub fn split_with(
+        config: &StatsConfig,
+        initial_data: f64,
+    ) -> (DataProcessorReader, DataProcessorWriter) {
+        let sma_n_size = config.sma_n;
         assert!(sma_n_size > 0, "SMA window size must be > 0");
+        let initial_data = config
+            .tick_size
+            .map_or(initial_data, |t| t.round(initial_data));
+        let now = now_millis();
 
         let raw_data = RawData {
             curr_avg: initial_data,
             max: initial_data,
             min: initial_data,
+            window_min: initial_data,
+            window_max: initial_data,
             sma: initial_data,
+            variance: 0.0,
+            std_dev: 0.0,
+            bollinger_upper: initial_data,
+            bollinger_lower: initial_data,
             data_point: 1,
+            first_seen: now,
+            last_update: now,
+            event_time: 0,
         };
-        let active2read = 0.into();
 
         let queue = UnsafeQueue::new(sma_n_size);
+        let mut sma_sum = WindowSum::new(config.tick_size);
         for idx in 0..sma_n_size {
             // Initialize SMA buffer with the seed value
             unsafe {
                 queue.set(initial_data, idx);
             }
+            sma_sum.replace(0.0, initial_data);
         }
 
+        let curr_emas: Box<[Cell<f64>]> = config.emas.iter().map(|_| initial_data.into()).collect();
+        let time_windows: Box<[TimeWindow]> = config
+            .windows
+            .iter()
+            .map(|spec| TimeWindow::new(*spec, now, initial_data))
+            .collect();
+        let lifetime_quantiles: Box<[P2Quantile]> = config
+            .quantiles
+            .iter()
+            .map(|q| {
+                let mut estimator = P2Quantile::new(q.value());
+                estimator.push(initial_data);
+                estimator
+            })
+            .collect();
+        let sorted_window =
+            (!config.quantiles.is_empty()).then(|| SortedWindow::new(sma_n_size, initial_data));
+        let mut names = Vec::new();
+        let mut indicators: Box<[IndicatorSlot]> = config
+            .indicators
+            .iter()
+            .map(|spec| {
+                let indicator = spec.build();
+                let outputs = indicator.outputs();
+                let width = outputs.len();
+                names.extend(outputs);
+                (indicator, width)
+            })
+            .collect();
+        let indicator_names = names.into_boxed_slice();
+        let mut indicator_values = vec![0.0; indicator_names.len()].into_boxed_slice();
+        let mut samples_needed = vec![0; indicators.len()].into_boxed_slice();
+        update_indicators(
+            &mut indicators,
+            &mut indicator_values,
+            &mut samples_needed,
+            initial_data,
+            now,
+        );
+        let words: Vec<u64> = raw_data
+            .to_words()
+            .into_iter()
+            .chain(curr_emas.iter().map(|e| e.get().to_bits()))
+            // Every quantile of a single value is that value
+            .chain(
+                config
+                    .quantiles
+                    .iter()
+                    .flat_map(|_| [initial_data.to_bits(); 2]),
+            )
+            .chain(indicator_values.iter().map(|v| v.to_bits()))
+            .chain(samples_needed.iter().copied())
+            .chain(time_windows.iter().flat_map(|w| w.stats().to_words()))
+            .collect();
+
         let inner = Arc::new(Self {
-            raw_data: [raw_data.into(), raw_data.into()],
-            active2read,
+            published: SeqLock::new(&words),
+            curr_raw: raw_data.into(),
             queue,
-            curr_sma_avg: initial_data.into(),
+            curr_sma_sum: sma_sum.into(),
+            tick_size: config.tick_size,
+            stale_after_ms: AtomicU64::new(stale_after_ms(config.stale_after)),
             curr_queue_idx: 0.into(),
+            curr_sma_m2: 0.0.into(),
+            bollinger_k: config.bollinger_k,
+            // The seed fills the whole SMA buffer, so it stays in the window until
+            // `sma_n` writes pushed it out, exactly like a point written at `data_point` 1
+            window_min: RollingExtreme::new(sma_n_size, false, initial_data),
+            window_max: RollingExtreme::new(sma_n_size, true, initial_data),
+            ema_specs: config.emas.clone().into_boxed_slice(),
+            ema_alphas: config.emas.iter().map(|e| e.alpha()).collect(),
+            curr_emas,
+            quantile_specs: config.quantiles.clone().into_boxed_slice(),
+            lifetime_quantiles: lifetime_quantiles.into(),
+            sorted_window: sorted_window.into(),
+            indicator_specs: config.indicators.clone().into_boxed_slice(),
+            indicators: indicators.into(),
+            indicator_names,
+            indicator_values: indicator_values.into(),
+            samples_needed: samples_needed.into(),
+            window_specs: config.windows.clone().into_boxed_slice(),
+            time_windows: time_windows.into(),
+            last_at: now.into(),
+            candles: CandleHistory::new(&config.candles, config.candle_limit, now, initial_data),
+            config: config.clone().into(),
         });
 
+        let current = Arc::new(RwLock::new(inner.clone()));
         let reader = DataProcessorReader {
-            inner: inner.clone(),
+            current: current.clone(),
         };
         let writer = DataProcessorWriter {
             inner,
+            current,
             _no_clone: NoClone,
         };
         (reader, writer)
@@ -152,58 +787,388 @@ impl DataProcessor {
     /// Updates statistics with a new data point.
     ///
     /// Updates:
-    /// - min / max
+    /// - price snapped to the tick size, if configured
+    /// - lifetime min / max and min / max over the SMA window
     /// - streaming mean (`curr_avg`)
     /// - simple moving average (`sma`)
-    /// - data point count
-    fn write(&self, new_data: f64) {
-        // Load current reader index (0 or 1)
-        let idx = self.active2read.load(Acquire);
-        fence(Acquire); // ensure memory ordering for data
-        let old_raw = self.raw_data[idx].get();
+    /// - rolling variance, standard deviation and Bollinger bands over the SMA window
+    /// - exponential moving averages
+    /// - lifetime (P²) and SMA window (exact) quantiles
+    /// - pluggable indicators
+    /// - time window statistics and candles, sampled at `event_time` if the exchange
+    ///   reported one and at `received_at` otherwise (Unix milliseconds)
+    /// - candle volume, adding `quantity`
+    /// - data point count, last update and event times
+    fn write(
+        &self,
+        new_data: f64,
+        exact: Option<Decimal>,
+        received_at: u64,
+        event_time: Option<u64>,
+        quantity: f64,
+    ) {
+        // Prices off the tick grid (e.g. a consensus between two quotes) are snapped to
+        // it; exact prices are converted to ticks without going through `f64`
+        let new_data = match (self.tick_size, exact) {
+            (Some(tick), Some(exact)) => tick.price(tick.ticks_of(exact) as i128),
+            (Some(tick), None) => tick.round(new_data),
+            (None, _) => new_data,
+        };
+
+        // Only the writer mutates `curr_raw`, so no synchronisation is needed here
+        let old_raw = self.curr_raw.get();
 
         // Update min/max and data point count
         let min = old_raw.min.min(new_data);
         let max = old_raw.max.max(new_data);
         let data_point = old_raw.data_point + 1;
+        let window_min = self.window_min.push(data_point, new_data);
+        let window_max = self.window_max.push(data_point, new_data);
 
         // Streaming mean (online update)
         let curr_avg = calculate_stream_mean(old_raw.curr_avg, new_data, data_point);
 
         // Simple Moving Average (SMA) update
-        let sma = {
+        let (sma, variance) = {
             let b_idx = bound_index(self.curr_queue_idx.get(), self.queue.capacity);
             self.curr_queue_idx.set(b_idx + 1);
 
             // Swap new value into circular buffer and get popped value
             let popped = unsafe { self.queue.swap(b_idx, new_data) };
 
-            // Update running SMA in O(1) time
-            let new_sma = self.curr_sma_avg.get() - (popped / self.queue.capacity as f64)
-                + (new_data / self.queue.capacity as f64);
-            self.curr_sma_avg.set(new_sma);
-            new_sma
+            // Update running SMA in O(1) time; the compensated sum keeps it within a
+            // few ulps of the exact window mean however long the processor runs
+            let old_sma = old_raw.sma;
+            let mut sum = self.curr_sma_sum.get();
+            sum.replace(popped, new_data);
+            self.curr_sma_sum.set(sum);
+            let new_sma = sum.value() / self.queue.capacity as f64;
+
+            // Windowed Welford: replace `popped` by `new_data` in the squared deviations.
+            // Rounding can push an all-equal window slightly below zero, so clamp it.
+            let m2 = (self.curr_sma_m2.get()
+                + (new_data - popped) * (new_data - new_sma + popped - old_sma))
+                .max(0.0);
+            self.curr_sma_m2.set(m2);
+
+            // Keep the sorted copy of the window in step for the window quantiles
+            if let Some(sorted) = self.sorted_window.borrow_mut().as_mut() {
+                sorted.replace(popped, new_data);
+            }
+            (new_sma, m2 / self.queue.capacity as f64)
         };
+        let std_dev = variance.sqrt();
 
         let new_raw = RawData {
             curr_avg,
             max,
             min,
+            window_min,
+            window_max,
             sma,
+            variance,
+            std_dev,
+            bollinger_upper: sma + self.bollinger_k * std_dev,
+            bollinger_lower: sma - self.bollinger_k * std_dev,
             data_point,
+            first_seen: old_raw.first_seen,
+            last_update: received_at.max(old_raw.last_update),
+            event_time: event_time.map_or(old_raw.event_time, |e| e.max(old_raw.event_time)),
+        };
+        self.curr_raw.set(new_raw);
+
+        // EMA update in O(1) per average
+        for (ema, alpha) in self.curr_emas.iter().zip(&self.ema_alphas) {
+            ema.set(ema.get() + alpha * (new_data - ema.get()));
+        }
+
+        // Time windows expect ordered samples, so a sample older than the latest
+        // one (clock skew between sources) counts as taken with it
+        let at = event_time.unwrap_or(received_at).max(self.last_at.get());
+        self.last_at.set(at);
+        self.candles.push(at, new_data, quantity);
+        let mut time_windows = self.time_windows.borrow_mut();
+        for window in time_windows.iter_mut() {
+            window.push(at, new_data);
+        }
+        let windows = time_windows.iter().flat_map(|w| w.stats().to_words());
+
+        let mut lifetime_quantiles = self.lifetime_quantiles.borrow_mut();
+        for quantile in lifetime_quantiles.iter_mut() {
+            quantile.push(new_data);
+        }
+        let lifetime = lifetime_quantiles.iter().map(|q| q.estimate().to_bits());
+        let sorted_window = self.sorted_window.borrow();
+        let window = sorted_window.iter().flat_map(|s| {
+            self.quantile_specs
+                .iter()
+                .map(|q| s.quantile(q.value()).to_bits())
+        });
+
+        let mut indicator_values = self.indicator_values.borrow_mut();
+        let mut samples_needed = self.samples_needed.borrow_mut();
+        update_indicators(
+            &mut self.indicators.borrow_mut(),
+            &mut indicator_values,
+            &mut samples_needed,
+            new_data,
+            at,
+        );
+        let indicators = indicator_values.iter().map(|v| v.to_bits());
+
+        // Publish through the seqlock so readers retry instead of copying a torn value;
+        // everything is updated above, so readers only wait for the words to be copied
+        let emas = self.curr_emas.iter().map(|e| e.get().to_bits());
+        self.published.write_from(
+            new_raw
+                .to_words()
+                .into_iter()
+                .chain(emas)
+                .chain(lifetime)
+                .chain(window)
+                .chain(indicators)
+                .chain(samples_needed.iter().copied())
+                .chain(windows),
+        );
+    }
+
+    /// Builds the processor replacing this one for `config`.
+    ///
+    /// Statistics of both configurations carry on; the others are rebuilt from the
+    /// SMA window and the longest time window as described by
+    /// [`StatsConfig::changes_from`]. Only the writer may call it: the indicators
+    /// and candles move to the successor.
+    fn successor(&self, config: &StatsConfig) -> Self {
+        let sma_n = config.sma_n;
+        assert!(sma_n > 0, "SMA window size must be > 0");
+        let tick_size = config.tick_size;
+        let snap = |price: f64| tick_size.map_or(price, |t| t.round(price));
+        let old_raw = self.curr_raw.get();
+        let at = self.last_at.get();
+
+        // Prices of the SMA window, oldest first; the next write overwrites the oldest
+        let capacity = self.queue.capacity;
+        let oldest = bound_index(self.curr_queue_idx.get(), capacity);
+        let history: Vec<f64> = (0..capacity)
+            .map(|i| snap(unsafe { self.queue.get((oldest + i) % capacity) }))
+            .collect();
+        let latest = history[capacity - 1];
+        let recent = &history[capacity.saturating_sub(sma_n)..];
+        // A longer window repeats the oldest price, as the seed fills a new one
+        let window: Vec<f64> = iter::repeat_n(recent[0], sma_n - recent.len())
+            .chain(recent.iter().copied())
+            .collect();
+
+        let queue = UnsafeQueue::new(sma_n);
+        let mut sma_sum = WindowSum::new(tick_size);
+        for (idx, price) in window.iter().enumerate() {
+            unsafe {
+                queue.set(*price, idx);
+            }
+            sma_sum.replace(0.0, *price);
+        }
+        let sma = sma_sum.value() / sma_n as f64;
+        let m2: f64 = window.iter().map(|price| (price - sma).powi(2)).sum();
+        let std_dev = (m2 / sma_n as f64).sqrt();
+        // Data point of each price; the repeated ones leave the window with the oldest
+        let points = (1..=sma_n as u64)
+            .map(|i| (old_raw.data_point + i).saturating_sub(sma_n as u64).max(1));
+        let samples = || points.clone().zip(window.iter().copied());
+        let raw_data = RawData {
+            max: snap(old_raw.max),
+            min: snap(old_raw.min),
+            window_min: window.iter().copied().fold(f64::INFINITY, f64::min),
+            window_max: window.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            sma,
+            variance: m2 / sma_n as f64,
+            std_dev,
+            bollinger_upper: sma + config.bollinger_k * std_dev,
+            bollinger_lower: sma - config.bollinger_k * std_dev,
+            ..old_raw
         };
-        let bounded_idx = bound_index(idx + 1, 2);
 
-        // Write into the "inactive" slot so readers see consistent snapshot
-        self.raw_data[bounded_idx].set(new_raw);
-        fence(Release); // ensure ordering before publishing
-        self.active2read.store(bounded_idx, Release); // switch active reader index
+        // Kept EMAs and lifetime quantiles carry on, new ones start from the window
+        let curr_emas: Box<[Cell<f64>]> = config
+            .emas
+            .iter()
+            .map(|spec| match self.ema_specs.iter().position(|s| s == spec) {
+                Some(i) => self.curr_emas[i].get().into(),
+                None => recent[1..]
+                    .iter()
+                    .fold(recent[0], |ema, price| ema + spec.alpha() * (price - ema))
+                    .into(),
+            })
+            .collect();
+        let old_quantiles = self.lifetime_quantiles.borrow();
+        let lifetime_quantiles: Box<[P2Quantile]> = config
+            .quantiles
+            .iter()
+            .map(
+                |spec| match self.quantile_specs.iter().position(|s| s == spec) {
+                    Some(i) => old_quantiles[i].clone(),
+                    None => {
+                        let mut estimator = P2Quantile::new(spec.value());
+                        recent.iter().for_each(|price| estimator.push(*price));
+                        estimator
+                    }
+                },
+            )
+            .collect();
+        let sorted_window =
+            (!config.quantiles.is_empty()).then(|| SortedWindow::from_values(&window));
+
+        // Kept indicators move over, new ones are warmed up from the window
+        let mut old_indicators: Vec<Option<IndicatorSlot>> =
+            mem::take(&mut *self.indicators.borrow_mut())
+                .into_vec()
+                .into_iter()
+                .map(Some)
+                .collect();
+        let mut names = Vec::new();
+        let indicators: Box<[IndicatorSlot]> = config
+            .indicators
+            .iter()
+            .map(|spec| {
+                let kept = self
+                    .indicator_specs
+                    .iter()
+                    .zip(&mut old_indicators)
+                    .find(|(s, slot)| *s == spec && slot.is_some())
+                    .and_then(|(_, slot)| slot.take());
+                let (indicator, width) = kept.unwrap_or_else(|| {
+                    let mut indicator = spec.build();
+                    recent.iter().for_each(|price| indicator.update(*price, at));
+                    let width = indicator.outputs().len();
+                    (indicator, width)
+                });
+                names.extend(indicator.outputs());
+                (indicator, width)
+            })
+            .collect();
+        let indicator_names = names.into_boxed_slice();
+        let mut indicator_values = vec![0.0; indicator_names.len()].into_boxed_slice();
+        let mut samples_needed = vec![0; indicators.len()].into_boxed_slice();
+        read_indicators(&indicators, &mut indicator_values, &mut samples_needed);
+
+        // Kept time windows carry on, new ones are resized from the longest current one
+        let old_windows = self.time_windows.borrow();
+        let longest = (0..self.window_specs.len()).max_by_key(|i| self.window_specs[*i]);
+        let time_windows: Box<[TimeWindow]> = config
+            .windows
+            .iter()
+            .map(
+                |spec| match self.window_specs.iter().position(|s| s == spec).or(longest) {
+                    Some(i) => old_windows[i].resized(*spec, at),
+                    None => TimeWindow::new(*spec, at, latest),
+                },
+            )
+            .collect();
+
+        let window_quantiles = sorted_window.iter().flat_map(|s| {
+            config
+                .quantiles
+                .iter()
+                .map(|q| s.quantile(q.value()).to_bits())
+        });
+        let words: Vec<u64> = raw_data
+            .to_words()
+            .into_iter()
+            .chain(curr_emas.iter().map(|e| e.get().to_bits()))
+            .chain(lifetime_quantiles.iter().map(|q| q.estimate().to_bits()))
+            .chain(window_quantiles)
+            .chain(indicator_values.iter().map(|v| v.to_bits()))
+            .chain(samples_needed.iter().copied())
+            .chain(time_windows.iter().flat_map(|w| w.stats().to_words()))
+            .collect();
+
+        Self {
+            // Readers moving to the successor see a new version
+            published: SeqLock::with_version(&words, self.published.version() + 1),
+            curr_raw: raw_data.into(),
+            queue,
+            curr_sma_sum: sma_sum.into(),
+            tick_size,
+            stale_after_ms: AtomicU64::new(stale_after_ms(config.stale_after)),
+            curr_queue_idx: 0.into(),
+            curr_sma_m2: m2.into(),
+            bollinger_k: config.bollinger_k,
+            window_min: RollingExtreme::with_samples(sma_n, false, samples()),
+            window_max: RollingExtreme::with_samples(sma_n, true, samples()),
+            ema_specs: config.emas.clone().into_boxed_slice(),
+            ema_alphas: config.emas.iter().map(|e| e.alpha()).collect(),
+            curr_emas,
+            quantile_specs: config.quantiles.clone().into_boxed_slice(),
+            lifetime_quantiles: lifetime_quantiles.into(),
+            sorted_window: sorted_window.into(),
+            indicator_specs: config.indicators.clone().into_boxed_slice(),
+            indicators: indicators.into(),
+            indicator_names,
+            indicator_values: indicator_values.into(),
+            samples_needed: samples_needed.into(),
+            window_specs: config.windows.clone().into_boxed_slice(),
+            time_windows: time_windows.into(),
+            last_at: at.into(),
+            candles: self
+                .candles
+                .reconfigured(&config.candles, config.candle_limit, at, latest),
+            config: config.clone().into(),
+        }
     }
 
-    /// Reads the latest snapshot of statistics
-    pub fn read(&self) -> RawData {
-        let idx = self.active2read.load(Acquire);
-        self.raw_data[idx].get()
+    /// Reads the latest consistent snapshot of statistics
+    pub fn read(&self) -> Snapshot {
+        let mut words = vec![0; self.published.len()];
+        let version = self.published.read(&mut words);
+        let (raw, rest) = words.split_at(RAW_DATA_WORDS);
+        let (emas, rest) = rest.split_at(self.ema_specs.len());
+        let (quantiles, rest) = rest.split_at(self.quantile_specs.len());
+        let (window_quantiles, rest) = rest.split_at(self.quantile_specs.len());
+        let (indicators, rest) = rest.split_at(self.indicator_names.len());
+        let (samples_needed, windows) = rest.split_at(self.indicator_specs.len());
+        let floats = |words: &[u64]| words.iter().map(|w| f64::from_bits(*w)).collect();
+        Snapshot {
+            version,
+            data: RawData::from_words(raw.try_into().expect("payload starts with RawData")),
+            emas: floats(emas),
+            quantiles: floats(quantiles),
+            window_quantiles: floats(window_quantiles),
+            indicators: floats(indicators),
+            samples_needed: samples_needed.to_vec(),
+            windows: windows
+                .chunks_exact(WINDOW_STATS_WORDS)
+                .map(|w| WindowStats::from_words(w.try_into().expect("whole WindowStats")))
+                .collect(),
+        }
+    }
+}
+
+/// An indicator and the number of outputs it writes.
+type IndicatorSlot = (Box<dyn Indicator>, usize);
+
+/// Feeds `price` to every indicator and collects their outputs into `values` and
+/// their remaining warm-up into `samples_needed`.
+fn update_indicators(
+    indicators: &mut [IndicatorSlot],
+    values: &mut [f64],
+    samples_needed: &mut [u64],
+    price: f64,
+    at: u64,
+) {
+    for (indicator, _) in indicators.iter_mut() {
+        indicator.update(price, at);
+    }
+    read_indicators(indicators, values, samples_needed);
+}
+
+/// Collects the outputs of every indicator into `values` and their remaining
+/// warm-up into `samples_needed`.
+fn read_indicators(indicators: &[IndicatorSlot], values: &mut [f64], samples_needed: &mut [u64]) {
+    let mut rest = values;
+    for ((indicator, width), needed) in indicators.iter().zip(samples_needed) {
+        let (out, tail) = std::mem::take(&mut rest).split_at_mut(*width);
+        indicator.values(out);
+        *needed = indicator.samples_needed();
+        rest = tail;
     }
 }
 
@@ -213,27 +1178,188 @@ struct NoClone;
 /// Writer handle for `DataProcessor`
 pub struct DataProcessorWriter {
     inner: Arc<DataProcessor>,
+    /// Slot `inner` is published in for the readers
+    current: CurrentProcessor,
     _no_clone: NoClone,
 }
 
 impl DataProcessorWriter {
-    /// Add a new data point
+    /// Add a new data point sampled now
     pub fn write(&self, new_data: f64) {
-        self.inner.write(new_data);
+        self.inner.write(new_data, None, now_millis(), None, 0.0);
+    }
+
+    /// Add a new data point sampled at `at` (Unix milliseconds)
+    pub fn write_at(&self, new_data: f64, at: u64) {
+        self.inner.write(new_data, None, at, None, 0.0);
+    }
+
+    /// Add a trade of `quantity` at price `new_data` sampled at `at` (Unix milliseconds)
+    pub fn write_trade(&self, new_data: f64, at: u64, quantity: f64) {
+        self.inner.write(new_data, None, at, None, quantity);
+    }
+
+    /// Add a trade of `quantity` at price `new_data` received at `received_at`,
+    /// sampled at the exchange-provided `event_time` when there is one
+    pub fn write_update(
+        &self,
+        new_data: f64,
+        received_at: u64,
+        event_time: Option<u64>,
+        quantity: f64,
+    ) {
+        self.inner
+            .write(new_data, None, received_at, event_time, quantity);
+    }
+
+    /// Like [`DataProcessorWriter::write_update`] for a price parsed exactly; with a
+    /// tick size it is converted to ticks without rounding through `f64` first
+    pub fn write_exact_update(
+        &self,
+        price: Decimal,
+        received_at: u64,
+        event_time: Option<u64>,
+        quantity: f64,
+    ) {
+        self.inner.write(
+            price.to_f64(),
+            Some(price),
+            received_at,
+            event_time,
+            quantity,
+        );
+    }
+
+    /// Switches to the statistics of `config`, e.g. after a config reload.
+    ///
+    /// Statistics kept by `config` carry on with their state and a new staleness
+    /// limit applies as is; changed ones are rebuilt from the current state as
+    /// described by [`StatsConfig::changes_from`]. Readers switch to the new
+    /// statistics, the views taken before keep the replaced processor alive.
+    pub fn reconfigure(&mut self, config: &StatsConfig) {
+        let mut current = self.inner.config.borrow_mut();
+        if *current == *config {
+            return;
+        }
+        let same_stats = StatsConfig {
+            stale_after: config.stale_after,
+            ..current.clone()
+        } == *config;
+        if same_stats {
+            self.inner
+                .stale_after_ms
+                .store(stale_after_ms(config.stale_after), Relaxed);
+            *current = config.clone();
+            return;
+        }
+        drop(current);
+
+        let successor = Arc::new(self.inner.successor(config));
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = successor.clone();
+        self.inner = successor;
     }
 }
 
-/// Reader handle for `DataProcessor`
+/// `stale_after` in milliseconds, `0` for never (a zero limit is raised to 1ms).
+fn stale_after_ms(stale_after: Option<Duration>) -> u64 {
+    stale_after.map_or(0, |limit| (limit.as_millis() as u64).max(1))
+}
+
+/// Reader handle for `DataProcessor`, following it across reconfigurations
 #[derive(Clone)]
 pub struct DataProcessorReader {
-    inner: Arc<DataProcessor>,
+    current: CurrentProcessor,
 }
 
 impl DataProcessorReader {
-    /// Read the current statistics snapshot
-    pub fn read(&self) -> RawData {
+    /// A view of the statistics as currently configured, which does not follow
+    /// later reconfigurations: the snapshot and the specs read through it always
+    /// match, e.g. while building one response
+    pub fn latest(&self) -> DataProcessorView {
+        DataProcessorView {
+            inner: self
+                .current
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+
+    /// Read the current statistics snapshot and its version
+    pub fn read(&self) -> Snapshot {
+        self.latest().read()
+    }
+
+    /// Returns `true` if data last updated `age_ms` milliseconds ago is stale
+    pub fn is_stale(&self, age_ms: u64) -> bool {
+        self.latest().is_stale(age_ms)
+    }
+
+    /// Replaces the age after which the data is stale, e.g. after a config reload
+    pub fn set_stale_after(&self, stale_after: Option<Duration>) {
+        self.latest()
+            .inner
+            .stale_after_ms
+            .store(stale_after_ms(stale_after), Relaxed);
+    }
+}
+
+/// Statistics of a `DataProcessor` as configured when the view was taken
+#[derive(Clone)]
+pub struct DataProcessorView {
+    inner: Arc<DataProcessor>,
+}
+
+impl DataProcessorView {
+    /// Read the current statistics snapshot and its version
+    pub fn read(&self) -> Snapshot {
         self.inner.read()
     }
+
+    /// EMAs carried in every snapshot, in `Snapshot::emas` order
+    pub fn ema_specs(&self) -> &[EmaSpec] {
+        &self.inner.ema_specs
+    }
+
+    /// Quantiles carried in every snapshot, in `Snapshot::quantiles` and
+    /// `Snapshot::window_quantiles` order
+    pub fn quantile_specs(&self) -> &[QuantileSpec] {
+        &self.inner.quantile_specs
+    }
+
+    /// Tick size the statistics are reported in, if any
+    pub fn tick_size(&self) -> Option<TickSize> {
+        self.inner.tick_size
+    }
+
+    /// Returns `true` if data last updated `age_ms` milliseconds ago is stale
+    pub fn is_stale(&self, age_ms: u64) -> bool {
+        match self.inner.stale_after_ms.load(Relaxed) {
+            0 => false,
+            limit => age_ms > limit,
+        }
+    }
+
+    /// Indicator outputs carried in every snapshot, in `Snapshot::indicators` order
+    pub fn indicator_names(&self) -> &[String] {
+        &self.inner.indicator_names
+    }
+
+    /// Indicators carried in every snapshot, in `Snapshot::samples_needed` order
+    pub fn indicator_specs(&self) -> &[IndicatorSpec] {
+        &self.inner.indicator_specs
+    }
+
+    /// Time windows carried in every snapshot, in `Snapshot::windows` order
+    pub fn window_specs(&self) -> &[WindowSpec] {
+        &self.inner.window_specs
+    }
+
+    /// The last `limit` candles of `interval`, oldest first, or `None` if the
+    /// resolution is not tracked
+    pub fn candles(&self, interval: WindowSpec, limit: usize) -> Option<Vec<Candle>> {
+        self.inner.candles.latest(interval, limit)
+    }
 }
 
 // SAFETY: Single-writer, multi-reader semantics
@@ -242,9 +1368,10 @@ unsafe impl Send for DataProcessorWriter {}
 unsafe impl Send for DataProcessorReader {}
 unsafe impl Sync for DataProcessor {}
 
-#[cfg(test)]
+#[cfg(all(test, not(aboss_loom)))]
 mod dataproc_tests {
     use super::*;
+    use crate::quantile::exact_quantile;
     use std::sync::{
         Arc,
         atomic::{AtomicBool, Ordering as AtomicOrdering},
@@ -260,7 +1387,7 @@ mod dataproc_tests {
     #[test]
     fn test_initial_state() {
         let (r, _w) = DataProcessor::split(4, 1.0);
-        let s = r.read();
+        let s = r.read().data;
         assert_eq!(s.min, 1.0);
         assert_eq!(s.max, 1.0);
         assert!(approx_eq(s.curr_avg, 1.0, 1e-12));
@@ -272,19 +1399,19 @@ mod dataproc_tests {
     fn test_single_writer_updates_and_invariants() {
         let (r, w) = DataProcessor::split(3, 2.0);
         // initial
-        let s0 = r.read();
+        let s0 = r.read().data;
         assert_eq!(s0.data_point, 1);
 
         // write sequence
         w.write(4.0); // now seen values: 2.0(initial seeded *3), then 4.0
-        let s1 = r.read();
+        let s1 = r.read().data;
         assert!(s1.max >= s1.min);
         assert!(s1.data_point >= s0.data_point);
         // streaming mean should have increased
         assert!(s1.curr_avg >= s0.curr_avg);
 
         w.write(0.0);
-        let s2 = r.read();
+        let s2 = r.read().data;
         assert!(s2.min <= s1.min);
         assert!(s2.max >= s1.max);
         assert!(s2.data_point >= s1.data_point);
@@ -293,7 +1420,7 @@ mod dataproc_tests {
         w.write(10.0);
         w.write(5.0);
 
-        let s3 = r.read();
+        let s3 = r.read().data;
         // sanity: sma must be between min and max of the last window approximately
         assert!(s3.sma >= s3.min - 1e-12 && s3.sma <= s3.max + 1e-12);
     }
@@ -307,7 +1434,11 @@ mod dataproc_tests {
         // initial queue: [1,1,1,1], sma = 1
         let mut expected_buf = vec![1.0; window];
         let mut expected_sum: f64 = expected_buf.iter().sum();
-        assert!(approx_eq(r.read().sma, expected_sum / window as f64, 1e-12));
+        assert!(approx_eq(
+            r.read().data.sma,
+            expected_sum / window as f64,
+            1e-12
+        ));
 
         let inputs = [2.0, 3.0, 4.0, 5.0, 6.0];
         for &x in &inputs {
@@ -317,7 +1448,7 @@ mod dataproc_tests {
             expected_buf.push(x);
             expected_sum = expected_sum - popped + x;
 
-            let snap = r.read();
+            let snap = r.read().data;
             let expected_sma = expected_sum / window as f64;
             assert!(
                 approx_eq(snap.sma, expected_sma, 1e-9),
@@ -332,11 +1463,11 @@ mod dataproc_tests {
     #[test]
     fn test_streaming_mean_growth_and_monotonic_data_point() {
         let (r, w) = DataProcessor::split(5, 10.0);
-        let mut last = r.read();
+        let mut last = r.read().data;
         for i in 1..50 {
             let v = (i as f64) * 0.5;
             w.write(v);
-            let cur = r.read();
+            let cur = r.read().data;
             // data point increments by at least 1
             assert!(cur.data_point >= last.data_point);
             // curr_avg should be between min and max of observed values
@@ -362,7 +1493,7 @@ mod dataproc_tests {
             handles.push(thread::spawn(move || {
                 // each reader continuously reads and checks invariants
                 while !stop_c.load(AtomicOrdering::Relaxed) {
-                    let s = r.read();
+                    let s = r.read().data;
                     // basic invariants:
                     assert!(s.max >= s.min);
                     assert!(s.data_point >= 1);
@@ -394,7 +1525,7 @@ mod dataproc_tests {
         }
 
         // final sanity read from main thread
-        let final_snap = reader.read();
+        let final_snap = reader.read().data;
         assert!(final_snap.data_point > 1);
         assert!(final_snap.sma.is_finite());
     }
@@ -415,7 +1546,7 @@ mod dataproc_tests {
             let stopc = Arc::clone(&stop);
             readers.push(thread::spawn(move || {
                 while !stopc.load(AtomicOrdering::Relaxed) {
-                    let s = rr.read();
+                    let s = rr.read().data;
                     // quick consistency checks
                     assert!(s.max >= s.min);
                     assert!(s.data_point >= 1);
@@ -436,7 +1567,533 @@ mod dataproc_tests {
             th.join().unwrap();
         }
 
-        let s = r.read();
+        let s = r.read().data;
         assert!(s.data_point > 1);
     }
+
+    #[test]
+    fn test_rolling_variance_and_bands_match_naive_window() {
+        let window = 5usize;
+        let config = StatsConfig::new(window).with_bollinger_k(1.5);
+        let (r, w) = DataProcessor::split_with(&config, 50.0);
+        let s0 = r.read().data;
+        assert_eq!(s0.variance, 0.0);
+        assert_eq!(s0.bollinger_upper, 50.0);
+        assert_eq!(s0.bollinger_lower, 50.0);
+
+        let mut buf = vec![50.0; window];
+        for i in 0..1000 {
+            let x = 50.0 + (i as f64 * 1.3).sin() * 10.0 + (i % 11) as f64 * 0.25;
+            w.write(x);
+            buf.remove(0);
+            buf.push(x);
+
+            let mean = buf.iter().sum::<f64>() / window as f64;
+            let var = buf.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window as f64;
+            let s = r.read().data;
+            assert!(
+                approx_eq(s.variance, var, 1e-9),
+                "variance after {i}: got {} expected {var} (buf {buf:?})",
+                s.variance
+            );
+            assert!(approx_eq(s.std_dev, var.sqrt(), 1e-9));
+            assert!(approx_eq(s.bollinger_upper, s.sma + 1.5 * s.std_dev, 1e-9));
+            assert!(approx_eq(s.bollinger_lower, s.sma - 1.5 * s.std_dev, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_window_min_max_match_naive_window() {
+        let window = 6usize;
+        let (r, w) = DataProcessor::split(window, 50.0);
+        let mut buf = vec![50.0; window];
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        for i in 0..2000 {
+            // Long monotonic runs alternate with noise to exercise both deque ends
+            let x = match i / 50 % 3 {
+                0 => i as f64,
+                1 => -(i as f64),
+                _ => (crate::utils::xorshift64(&mut state) % 1000) as f64,
+            };
+            w.write(x);
+            buf.remove(0);
+            buf.push(x);
+
+            let s = r.read().data;
+            let lo = buf.iter().copied().fold(f64::INFINITY, f64::min);
+            let hi = buf.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(s.window_min, lo, "window min after {i} (buf {buf:?})");
+            assert_eq!(s.window_max, hi, "window max after {i} (buf {buf:?})");
+            assert!(s.min <= s.window_min && s.window_max <= s.max);
+        }
+    }
+
+    #[test]
+    fn test_seed_leaves_window_after_sma_n_writes() {
+        let (r, w) = DataProcessor::split(3, 100.0);
+        w.write(1.0);
+        w.write(2.0);
+        assert_eq!(r.read().data.window_max, 100.0);
+        w.write(3.0);
+        let s = r.read().data;
+        assert_eq!((s.window_min, s.window_max), (1.0, 3.0));
+        // The session high keeps the seed
+        assert_eq!(s.max, 100.0);
+    }
+
+    /// Custom indicator with two outputs: the latest sample time and the price sum.
+    struct LastSeen {
+        at: u64,
+        sum: f64,
+    }
+
+    impl Indicator for LastSeen {
+        fn outputs(&self) -> Vec<String> {
+            vec!["last_at".into(), "sum".into()]
+        }
+
+        fn update(&mut self, price: f64, at: u64) {
+            self.at = at;
+            self.sum += price;
+        }
+
+        fn values(&self, out: &mut [f64]) {
+            out[0] = self.at as f64;
+            out[1] = self.sum;
+        }
+    }
+
+    #[test]
+    fn test_custom_indicators_are_published_in_order() {
+        let mut registry = crate::indicator::IndicatorRegistry::default();
+        registry.register("last_seen", |_| Ok(Box::new(LastSeen { at: 0, sum: 0.0 })));
+        let indicators = registry.resolve_list("roc(1),last_seen").unwrap();
+        let config = StatsConfig::new(2).with_indicators(indicators);
+        let (r, w) = DataProcessor::split_with(&config, 10.0);
+        assert_eq!(r.latest().indicator_names(), ["roc_1", "last_at", "sum"]);
+        assert_eq!(r.read().indicators[2], 10.0);
+        // roc(1) needs a second price, custom indicators default to no warm-up
+        assert_eq!(r.read().samples_needed, [1, 0]);
+
+        let t0 = now_millis() + 1_000;
+        w.write_at(11.0, t0);
+        w.write_at(13.2, t0 + 1_000);
+        let s = r.read();
+        assert!(approx_eq(s.indicators[0], 20.0, 1e-9));
+        assert_eq!(s.indicators[1..], [(t0 + 1_000) as f64, 34.2]);
+        assert_eq!(s.samples_needed, [0, 0]);
+        // Unrelated sections of the payload are unaffected
+        assert_eq!(s.data.data_point, 3);
+    }
+
+    #[test]
+    fn test_time_windows_follow_sample_times() {
+        let windows = vec!["1m".parse().unwrap(), "5m".parse().unwrap()];
+        let config = StatsConfig::new(2).with_windows(windows);
+        let (r, w) = DataProcessor::split_with(&config, 100.0);
+        assert_eq!(r.latest().window_specs().len(), 2);
+        let t0 = now_millis();
+
+        w.write_at(110.0, t0 + 30_000);
+        w.write_at(120.0, t0 + 80_000);
+        let snap = r.read();
+        let (one, five) = (snap.windows[0], snap.windows[1]);
+        // The seed is more than a minute older than the latest sample
+        assert_eq!((one.count, one.first, one.last), (2, 110.0, 120.0));
+        assert_eq!(
+            (five.count, five.first, five.min, five.max),
+            (3, 100.0, 100.0, 120.0)
+        );
+        assert!((five.mean - 110.0).abs() < 1e-12);
+        assert!((five.change_pct - 20.0).abs() < 1e-12);
+
+        // An out-of-order sample counts as taken with the latest one
+        w.write_at(130.0, t0);
+        let one = r.read().windows[0];
+        assert_eq!((one.count, one.last, one.max), (3, 130.0, 130.0));
+        // The count-based statistics are unaffected
+        assert_eq!(r.read().data.data_point, 4);
+    }
+
+    #[test]
+    fn test_quantiles_track_exact_values() {
+        let specs: Vec<QuantileSpec> = ["0.05", "0.5", "0.95"]
+            .into_iter()
+            .map(|q| q.parse().unwrap())
+            .collect();
+        let window = 50usize;
+        let config = StatsConfig::new(window).with_quantiles(specs.clone());
+        let (r, w) = DataProcessor::split_with(&config, 100.0);
+        assert_eq!(r.read().quantiles, [100.0; 3]);
+        assert_eq!(r.read().window_quantiles, [100.0; 3]);
+
+        let mut all = vec![100.0];
+        let mut state = 7u64;
+        for i in 0..10_000 {
+            let noise = (crate::utils::xorshift64(&mut state) % 1_000) as f64 / 100.0;
+            let spike = if i % 97 == 0 { 1_000.0 } else { 0.0 };
+            let x = 100.0 + noise + spike;
+            w.write(x);
+            all.push(x);
+
+            let mut last: Vec<f64> = all[all.len().saturating_sub(window)..].to_vec();
+            last.resize(window, 100.0);
+            last.sort_by(f64::total_cmp);
+            let snap = r.read();
+            for (spec, q) in specs.iter().zip(&snap.window_quantiles) {
+                assert_eq!(*q, exact_quantile(&last, spec.value()));
+            }
+        }
+
+        // The median ignores the spikes the mean is dragged up by
+        all.sort_by(f64::total_cmp);
+        let snap = r.read();
+        for (spec, q) in specs.iter().zip(&snap.quantiles) {
+            let exact = exact_quantile(&all, spec.value());
+            assert!((q - exact).abs() < 0.2, "{spec}: {q} vs exact {exact}");
+        }
+        assert!(snap.data.curr_avg > snap.quantiles[1] + 5.0);
+    }
+
+    #[test]
+    fn test_sma_does_not_drift_over_long_runs() {
+        let window = 50;
+        let (r, w) = DataProcessor::split(window, 117_000.0);
+        let mut ring = vec![117_000.0; window];
+        let mut state = 0x0123_4567_89AB_CDEFu64;
+        for i in 0..1_000_000usize {
+            // Small moves around the price plus rare huge spikes, which leave large
+            // rounding errors behind in a naive running sum
+            let bits = crate::utils::xorshift64(&mut state);
+            let x = if bits.is_multiple_of(10_000) {
+                1e12
+            } else {
+                117_000.0 + (bits % 100_000) as f64 / 1_000.0
+            };
+            w.write(x);
+            ring[i % window] = x;
+
+            if i.is_multiple_of(997) || i > 999_000 {
+                let exact = ring.iter().sum::<f64>() / window as f64;
+                let sma = r.read().data.sma;
+                assert!(
+                    (sma - exact).abs() <= exact.abs() * 1e-13,
+                    "write {i}: sma {sma} exact {exact}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tick_size_snaps_prices_and_keeps_exact_sma() {
+        let tick = "0.00000001".parse().unwrap();
+        let config = StatsConfig::new(3).with_tick_sizes(Some(tick), BTreeMap::new());
+        let (r, w) = DataProcessor::split_with(&config, 0.000012341);
+        assert_eq!(r.latest().tick_size(), Some(tick));
+        assert_eq!(r.read().data.min, 0.00001234);
+
+        let mut state = 7u64;
+        let mut ring = [1234i128; 3];
+        for i in 0..10_000 {
+            let ticks = 1_000 + (crate::utils::xorshift64(&mut state) % 1_000) as i128;
+            w.write(ticks as f64 / 1e8);
+            ring[i % 3] = ticks;
+        }
+        // The window sum is kept in whole ticks, so it never drifts
+        let exact = tick.price(ring.iter().sum::<i128>()) / 3.0;
+        assert_eq!(r.read().data.sma, exact);
+    }
+
+    #[test]
+    fn test_exact_prices_are_snapped_without_f64_rounding() {
+        let tick = "0.01".parse().unwrap();
+        let config = StatsConfig::new(2).with_tick_sizes(Some(tick), BTreeMap::new());
+        let (r, w) = DataProcessor::split_with(&config, 1.0);
+
+        // As an `f64`, 1.005 is slightly below the halfway point and rounds down
+        w.write(1.005);
+        assert_eq!(r.read().data.max, 1.0);
+        w.write_exact_update("1.005".parse().unwrap(), now_millis(), None, 0.0);
+        assert_eq!(r.read().data.max, 1.01);
+    }
+
+    #[test]
+    fn test_snapshots_carry_sample_times() {
+        let before = now_millis();
+        let (r, w) = DataProcessor::split(3, 100.0);
+        let seed = r.read().data;
+        assert!(seed.first_seen >= before);
+        assert_eq!(seed.last_update, seed.first_seen);
+        assert_eq!(seed.event_time, 0);
+
+        let t0 = seed.first_seen + 5_000;
+        w.write_update(101.0, t0, Some(t0 - 200), 0.0);
+        let s = r.read().data;
+        assert_eq!((s.first_seen, s.last_update), (seed.first_seen, t0));
+        assert_eq!(s.event_time, t0 - 200);
+
+        // An update without an exchange clock keeps the last event time
+        w.write_at(102.0, t0 + 1_000);
+        let s = r.read().data;
+        assert_eq!((s.last_update, s.event_time), (t0 + 1_000, t0 - 200));
+    }
+
+    #[test]
+    fn test_variance_returns_to_zero_on_constant_window() {
+        let (r, w) = DataProcessor::split(3, 117_000.0);
+        for x in [117_000.1, 116_992.7, 117_042.0] {
+            w.write(x);
+        }
+        assert!(r.read().data.variance > 0.0);
+        for _ in 0..3 {
+            w.write(117_010.45);
+        }
+        let s = r.read().data;
+        assert!(s.variance >= 0.0);
+        assert!(s.std_dev < 1e-4, "std_dev {} should vanish", s.std_dev);
+    }
+
+    /// Closed-form EMA: `(1 - a)^n * x0 + sum(a * (1 - a)^(n - i) * x_i)`
+    fn reference_ema(seed: f64, xs: &[f64], alpha: f64) -> f64 {
+        let n = xs.len() as i32;
+        let mut ema = (1.0 - alpha).powi(n) * seed;
+        for (i, x) in xs.iter().enumerate() {
+            ema += alpha * (1.0 - alpha).powi(n - 1 - i as i32) * x;
+        }
+        ema
+    }
+
+    #[test]
+    fn test_emas_match_reference() {
+        let specs = vec![EmaSpec::Span(3), EmaSpec::Span(20), EmaSpec::Alpha(0.05)];
+        let config = StatsConfig::new(4).with_emas(specs.clone());
+        let (r, w) = DataProcessor::split_with(&config, 100.0);
+        assert_eq!(r.latest().ema_specs(), specs.as_slice());
+        assert_eq!(r.read().emas, [100.0, 100.0, 100.0]);
+
+        let mut xs = Vec::new();
+        for i in 0..500 {
+            let x = 100.0 + (i as f64 * 0.7).sin() * 5.0 + (i % 7) as f64;
+            w.write(x);
+            xs.push(x);
+
+            let snap = r.read();
+            for (spec, ema) in specs.iter().zip(&snap.emas) {
+                let expected = reference_ema(100.0, &xs, spec.alpha());
+                assert!(
+                    approx_eq(*ema, expected, 1e-9),
+                    "{spec} after {} writes: got {ema} expected {expected}",
+                    xs.len()
+                );
+            }
+        }
+        // The EMAs do not disturb the core statistics
+        assert_eq!(r.read().data.data_point, 501);
+    }
+
+    #[test]
+    fn test_ema_spec_parse_and_label() {
+        assert_eq!("20".parse(), Ok(EmaSpec::Span(20)));
+        assert_eq!("0.1".parse(), Ok(EmaSpec::Alpha(0.1)));
+        assert_eq!(EmaSpec::Span(20).alpha(), 2.0 / 21.0);
+        assert_eq!(EmaSpec::Span(1).alpha(), 1.0);
+        assert!("0".parse::<EmaSpec>().is_err());
+        assert!("1.5".parse::<EmaSpec>().is_err());
+        assert!("-0.1".parse::<EmaSpec>().is_err());
+        assert_eq!(EmaSpec::Span(12).to_string(), "ema_12");
+        assert_eq!(EmaSpec::Alpha(0.1).to_string(), "ema_alpha_0.1");
+    }
+
+    #[test]
+    fn test_version_tracks_writes() {
+        let (r, w) = DataProcessor::split(4, 1.0);
+        assert_eq!(r.read().version, 0);
+        for i in 1..=20u64 {
+            w.write(i as f64);
+            let snap = r.read();
+            assert_eq!(snap.version, i);
+            assert_eq!(snap.data.data_point, i + 1);
+        }
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_state_and_rebuilds_new_statistics() {
+        let registry = crate::indicator::IndicatorRegistry::default();
+        let old = StatsConfig::new(3)
+            .with_emas(vec![EmaSpec::Span(3)])
+            .with_quantiles(vec!["0.5".parse().unwrap()])
+            .with_indicators(registry.resolve_list("roc(1)").unwrap())
+            .with_windows(vec!["1m".parse().unwrap()])
+            .with_candles(vec!["1m".parse().unwrap()], 10);
+        let (r, mut w) = DataProcessor::split_with(&old, 10.0);
+        let t0 = now_millis() + 1_000;
+        for (i, price) in [11.0, 12.0, 13.0].into_iter().enumerate() {
+            w.write_at(price, t0 + i as u64 * 1_000);
+        }
+        let before = r.read();
+        let pinned = r.latest();
+
+        let new = StatsConfig::new(5)
+            .with_bollinger_k(3.0)
+            .with_emas(vec![EmaSpec::Span(3), EmaSpec::Span(2)])
+            .with_quantiles(vec!["0.5".parse().unwrap(), "0.9".parse().unwrap()])
+            .with_indicators(registry.resolve_list("roc(1),roc(2)").unwrap())
+            .with_windows(vec!["1m".parse().unwrap(), "5m".parse().unwrap()])
+            .with_candles(vec!["1m".parse().unwrap()], 10);
+        assert_eq!(
+            new.changes_from(&old),
+            [
+                "sma_n 3 -> 5: filled from the latest 3 prices, the oldest one repeated",
+                "bollinger_k 2 -> 3: bands recomputed",
+                "ema_2 added: seeded from the SMA window",
+                "p90 added: lifetime estimate seeded from the SMA window",
+                "roc(2) added: warmed up from the SMA window",
+                "window 5m added: filled from window 1m",
+            ]
+        );
+        w.reconfigure(&new);
+
+        let s = r.read();
+        assert_eq!(s.version, before.version + 1);
+        // Lifetime statistics carry on
+        let (d, b) = (s.data, before.data);
+        assert_eq!((d.data_point, d.min, d.max), (4, 10.0, 13.0));
+        assert_eq!((d.curr_avg, d.first_seen), (b.curr_avg, b.first_seen));
+        // The SMA window [11, 12, 13] is padded with its oldest price
+        assert!(approx_eq(d.sma, 11.6, 1e-12));
+        assert_eq!((d.window_min, d.window_max), (11.0, 13.0));
+        assert_eq!(s.window_quantiles[0], 11.0);
+        // Kept statistics are unchanged, new ones are rebuilt from the recent prices
+        assert_eq!(s.emas[0], before.emas[0]);
+        assert!(approx_eq(
+            s.emas[1],
+            reference_ema(11.0, &[12.0, 13.0], 2.0 / 3.0),
+            1e-12
+        ));
+        assert_eq!(s.quantiles[0], before.quantiles[0]);
+        assert_eq!(r.latest().indicator_names(), ["roc_1", "roc_2"]);
+        assert_eq!(s.indicators[0], before.indicators[0]);
+        assert!(approx_eq(s.indicators[1], 200.0 / 11.0, 1e-9));
+        assert_eq!(s.samples_needed, [0, 0]);
+        assert_eq!(s.windows, [before.windows[0]; 2]);
+        // A view taken before keeps reading the configuration it was taken with
+        assert_eq!(pinned.read().emas.len(), 1);
+        assert_eq!(r.latest().read().emas.len(), 2);
+
+        w.write_at(14.0, t0 + 3_000);
+        let d = r.read().data;
+        assert_eq!(d.data_point, 5);
+        assert!(approx_eq(d.sma, 12.2, 1e-12));
+
+        // Shrinking the window keeps the latest prices
+        let small = StatsConfig::new(2);
+        w.reconfigure(&small);
+        let d = r.read().data;
+        assert!(approx_eq(d.sma, 13.5, 1e-12));
+        assert_eq!((d.window_min, d.window_max, d.data_point), (13.0, 14.0, 5));
+        assert!(r.read().emas.is_empty() && r.latest().window_specs().is_empty());
+
+        // A new staleness limit alone is not a new configuration
+        let version = r.read().version;
+        w.reconfigure(&small.with_stale_after(Some(Duration::from_secs(1))));
+        assert_eq!(r.read().version, version);
+        assert!(r.is_stale(2_000));
+    }
+
+    #[test]
+    fn test_reconfigure_frees_replaced_processors() {
+        let candles = vec!["1m".parse().unwrap()];
+        let (r, mut w) = DataProcessor::split_with(&StatsConfig::new(2), 10.0);
+        let mut replaced = Vec::new();
+        for sma_n in 3..8 {
+            w.write(sma_n as f64);
+            let view = r.latest();
+            w.reconfigure(&StatsConfig::new(sma_n).with_candles(candles.clone(), 10));
+            // Only the view taken before still holds the replaced processor
+            assert_eq!(Arc::strong_count(&view.inner), 1);
+            replaced.push(Arc::downgrade(&view.inner));
+        }
+        assert!(replaced.iter().all(|p| p.upgrade().is_none()));
+        // The writer, the shared slot and this view
+        assert_eq!(Arc::strong_count(&r.latest().inner), 3);
+        assert_eq!(r.read().data.data_point, 6);
+        assert_eq!(r.latest().candles(candles[0], 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_readers_never_see_torn_snapshot() {
+        // Strictly increasing writes: after the k-th write max == k and data_point == k + 1,
+        // so any mix of two writes breaks the relation below.
+        let (reader, writer) = DataProcessor::split(8, 0.0);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let r = reader.clone();
+                let stop_c = Arc::clone(&stop);
+                thread::spawn(move || {
+                    let mut last_version = 0;
+                    while !stop_c.load(AtomicOrdering::Relaxed) {
+                        let snap = r.read();
+                        let s = snap.data;
+                        assert_eq!(s.max, (s.data_point - 1) as f64, "torn snapshot: {s:?}");
+                        assert_eq!(snap.version, s.data_point - 1);
+                        assert_eq!(s.min, 0.0);
+                        assert!(snap.version >= last_version);
+                        last_version = snap.version;
+                    }
+                })
+            })
+            .collect();
+
+        let writer_handle = thread::spawn(move || {
+            for i in 1..=300_000u64 {
+                writer.write(i as f64);
+            }
+        });
+
+        writer_handle.join().expect("writer panicked");
+        stop.store(true, AtomicOrdering::Relaxed);
+        for h in readers {
+            h.join().expect("reader panicked");
+        }
+    }
+}
+
+/// Model-checked reader/writer interleavings. Run with:
+/// `RUSTFLAGS="--cfg aboss_loom" cargo test --release --lib loom`
+#[cfg(all(test, aboss_loom))]
+mod loom_tests {
+    use super::*;
+    use loom::thread;
+
+    /// Bounded exploration: two preemptions already cover a reader being
+    /// descheduled across a full write, which is the interleaving that matters here.
+    fn model<F: Fn() + Sync + Send + 'static>(f: F) {
+        let mut builder = loom::model::Builder::new();
+        builder.preemption_bound = Some(2);
+        builder.check(f);
+    }
+
+    #[test]
+    fn loom_reader_never_sees_torn_raw_data() {
+        model(|| {
+            let (reader, writer) = DataProcessor::split(2, 0.0);
+
+            let writer_handle = thread::spawn(move || {
+                writer.write(1.0);
+                writer.write(2.0);
+            });
+
+            let snap = reader.read();
+            let s = snap.data;
+            assert_eq!(s.max, (s.data_point - 1) as f64);
+            assert_eq!(snap.version, s.data_point - 1);
+
+            writer_handle.join().unwrap();
+            let last = reader.read();
+            assert_eq!(last.version, 2);
+            assert_eq!(last.data.max, 2.0);
+        });
+    }
 }
@@ -0,0 +1,265 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Largest number of fractional digits a `Decimal` can carry.
+pub const MAX_SCALE: u32 = 18;
+
+/// Exact decimal number `mantissa * 10^-scale`, e.g. a price sent as `"0.00001234"`.
+///
+/// Parsing keeps every digit (trailing zeros included), so a price displays exactly
+/// as the exchange sent it; `to_f64` is only used where statistics are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i64,
+    scale: u32,
+}
+
+impl Decimal {
+    /// `mantissa * 10^-scale`; `scale` must not exceed `MAX_SCALE`.
+    pub const fn new(mantissa: i64, scale: u32) -> Self {
+        assert!(scale <= MAX_SCALE, "decimal scale too large");
+        Self { mantissa, scale }
+    }
+
+    /// Digits of the number without the decimal point.
+    pub fn mantissa(self) -> i64 {
+        self.mantissa
+    }
+
+    /// Number of fractional digits.
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    /// The `f64` closest to the number while the mantissa stays below 2^53.
+    pub fn to_f64(self) -> f64 {
+        // Both operands are exact, so the division rounds once
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{int}.{frac}")
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    /// Parses `123`, `-0.5` or `0.00001234`; exponents are not accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("invalid decimal `{s}`");
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int.is_empty() && frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if frac.len() > MAX_SCALE as usize {
+            return Err(format!(
+                "decimal `{s}` has more than {MAX_SCALE} fractional digits"
+            ));
+        }
+
+        let mut mantissa: i64 = 0;
+        for b in int.bytes().chain(frac.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((b - b'0') as i64))
+                .ok_or_else(|| format!("decimal `{s}` is too large"))?;
+        }
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: frac.len() as u32,
+        })
+    }
+}
+
+impl Serialize for Decimal {
+    /// Serializes as a string, like exchanges send prices.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Price increment of a symbol, e.g. `0.01` for BTCUSDT on Binance.
+///
+/// With a tick size, prices are snapped to multiples of it and price-denominated
+/// statistics are reported rounded to it, so responses show `117454.33` instead
+/// of artifacts like `117454.33400000003`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickSize(Decimal);
+
+impl TickSize {
+    /// Tick of `tick`, which must be positive.
+    pub fn new(tick: Decimal) -> Self {
+        assert!(tick.mantissa > 0, "tick size must be > 0");
+        Self(tick)
+    }
+
+    /// The tick as a decimal.
+    pub fn decimal(self) -> Decimal {
+        self.0
+    }
+
+    /// Tick of the square of a price (e.g. a variance), `tick^2`.
+    pub fn squared(self) -> Self {
+        let tick = self.0;
+        let scale = (tick.scale * 2).min(MAX_SCALE);
+        // Ticks too fine to square precisely fall back to the finest scale
+        let mantissa = tick
+            .mantissa
+            .checked_mul(tick.mantissa)
+            .filter(|_| tick.scale * 2 <= MAX_SCALE)
+            .unwrap_or(1);
+        Self(Decimal::new(mantissa, scale))
+    }
+
+    /// Number of ticks closest to the exact `price`, halfway cases rounded away from
+    /// zero like [`TickSize::round`].
+    pub fn ticks_of(self, price: Decimal) -> i64 {
+        // Both sides scaled to integers; at most 18 digits times 10^18 fits an i128
+        let num = price.mantissa as i128 * 10i128.pow(self.0.scale);
+        let den = self.0.mantissa as i128 * 10i128.pow(price.scale);
+        let (quotient, remainder) = (num / den, num % den);
+        let ticks = if 2 * remainder.abs() >= den {
+            quotient + num.signum()
+        } else {
+            quotient
+        };
+        ticks.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// `price` rounded to the tick, as an exact decimal with the tick's scale.
+    pub fn to_decimal(self, price: f64) -> Decimal {
+        let mantissa = self.ticks(price).saturating_mul(self.0.mantissa);
+        Decimal::new(mantissa, self.0.scale)
+    }
+
+    /// Number of ticks closest to `price`.
+    pub fn ticks(self, price: f64) -> i64 {
+        let per_unit = 10f64.powi(self.0.scale as i32) / self.0.mantissa as f64;
+        (price * per_unit).round() as i64
+    }
+
+    /// The price of `ticks` ticks, as the `f64` closest to the exact decimal.
+    pub fn price(self, ticks: i128) -> f64 {
+        (ticks * self.0.mantissa as i128) as f64 / 10f64.powi(self.0.scale as i32)
+    }
+
+    /// `price` rounded to the closest multiple of the tick; `NaN` stays `NaN`.
+    pub fn round(self, price: f64) -> f64 {
+        if !price.is_finite() {
+            return price;
+        }
+        self.price(self.ticks(price) as i128)
+    }
+}
+
+impl fmt::Display for TickSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for TickSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tick = s.parse::<Decimal>()?;
+        if tick.mantissa <= 0 {
+            return Err(format!("tick size `{}` must be > 0", s.trim()));
+        }
+        Ok(Self(tick))
+    }
+}
+
+#[cfg(test)]
+mod decimal_tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_parse_and_display_are_exact() {
+        for s in ["0.00001234", "117454.33", "4418.20", "-0.5", "42", "0.0"] {
+            assert_eq!(s.parse::<Decimal>().unwrap().to_string(), s);
+        }
+        let d: Decimal = "0.00001234".parse().unwrap();
+        assert_eq!((d.mantissa(), d.scale()), (1234, 8));
+        assert_eq!(d.to_f64(), 0.00001234);
+        assert_eq!("+1.5".parse(), Ok(Decimal::new(15, 1)));
+        assert_eq!(".5".parse(), Ok(Decimal::new(5, 1)));
+
+        for bad in [
+            "",
+            ".",
+            "1e-8",
+            "1.2.3",
+            "abc",
+            "99999999999999999999",
+            "- 1",
+        ] {
+            assert!(bad.parse::<Decimal>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_decimal_serde_uses_strings() {
+        let d: Decimal = serde_json::from_str(r#""4418.20""#).unwrap();
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""4418.20""#);
+        assert!(serde_json::from_str::<Decimal>("4418.2").is_err());
+    }
+
+    #[test]
+    fn test_tick_size_rounds_without_artifacts() {
+        let tick: TickSize = "0.01".parse().unwrap();
+        assert_eq!(tick.round(117454.33400000003), 117454.33);
+        assert_eq!(tick.round(0.1 + 0.2).to_string(), "0.3");
+        assert_eq!(tick.round(-1.005_000_1), -1.01);
+        assert!(tick.round(f64::NAN).is_nan());
+
+        let tick: TickSize = "0.00000001".parse().unwrap();
+        assert_eq!(tick.ticks(0.00001234), 1234);
+        assert_eq!(tick.round(0.000012344), 0.00001234);
+
+        let tick: TickSize = "0.5".parse().unwrap();
+        assert_eq!(tick.round(10.3), 10.5);
+        assert_eq!(tick.squared().to_string(), "0.25");
+
+        let tick: TickSize = "0.01".parse().unwrap();
+        let exact = |s: &str| tick.ticks_of(s.parse().unwrap());
+        assert_eq!(exact("117454.33"), 11_745_433);
+        assert_eq!(exact("117454.335"), 11_745_434);
+        assert_eq!(exact("-1.005"), -101);
+        assert_eq!(exact("42"), 4_200);
+        let tick: TickSize = "0.00000001".parse().unwrap();
+        assert_eq!(tick.ticks_of("0.000012340000".parse().unwrap()), 1234);
+        assert_eq!(tick.to_decimal(0.000012344).to_string(), "0.00001234");
+        let tick: TickSize = "0.10".parse().unwrap();
+        assert_eq!(tick.to_decimal(117454.3).to_string(), "117454.30");
+
+        assert!("0".parse::<TickSize>().is_err());
+        assert!("-0.01".parse::<TickSize>().is_err());
+    }
+}
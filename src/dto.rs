@@ -1,36 +1,172 @@
-use std::mem::{align_of, size_of, transmute};
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem::{align_of, size_of, transmute},
+    sync::Arc,
+};
 
-use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde::{
+    Deserialize, Serialize, Serializer,
+    ser::{SerializeMap, SerializeSeq},
+};
 
-use crate::data_processor::RawData;
+use crate::{
+    candles::Candle,
+    circuit_breaker::BreakerState,
+    consensus::ConsensusMethod,
+    data_processor::{DataProcessorReader, EmaSpec, RawData, Snapshot},
+    decimal::{Decimal, TickSize},
+    indicator::IndicatorSpec,
+    models::SourceStatus,
+    quantile::QuantileSpec,
+    utils::now_millis,
+    window::WindowSpec,
+};
 
 /// Response for health check endpoints
 ///
-/// Used to return a simple JSON status indicating that the service is healthy.
+/// Reports the overall status together with the circuit breaker and retry state
+/// of every source.
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
-    /// Always "ok" when the service is healthy
+    /// `"ok"` when every source is healthy, `"degraded"` when some are not,
+    /// `"unhealthy"` once the unhealthy fraction reaches the configured limit
     pub status: &'static str,
+    /// Per-symbol source health
+    pub sources: BTreeMap<String, SourceHealthResponse>,
 }
 
 impl HealthResponse {
-    /// Returns a JSON-formatted health status string.
+    /// Builds the report for `sources`.
     ///
-    /// # Example
-    ///
-    /// ```
-    /// use aboss_task::dto::HealthResponse;
-    /// let json_str = HealthResponse::health_status_json_string_default();
-    /// assert_eq!(json_str, r#"{"status":"ok"}"#);
-    /// ```
-    pub fn health_status_json_string_default() -> &'static str {
-        r#"{"status":"ok"}"#
+    /// The status becomes `"unhealthy"` when at least `unhealthy_fraction` of the
+    /// sources are unhealthy (and at least one is).
+    pub fn from_sources<'a, I>(sources: I, unhealthy_fraction: f64) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a Arc<SourceStatus>)>,
+    {
+        let sources: BTreeMap<String, SourceHealthResponse> = sources
+            .into_iter()
+            .map(|(symbol, status)| (symbol.clone(), status.as_ref().into()))
+            .collect();
+
+        let unhealthy = sources.values().filter(|s| !s.healthy).count();
+        let status = if unhealthy == 0 {
+            "ok"
+        } else if unhealthy as f64 >= unhealthy_fraction * sources.len() as f64 {
+            "unhealthy"
+        } else {
+            "degraded"
+        };
+        Self { status, sources }
+    }
+
+    /// Returns `true` if the service should not be considered healthy.
+    pub fn is_unhealthy(&self) -> bool {
+        self.status == "unhealthy"
     }
 }
 
 impl Default for HealthResponse {
     fn default() -> Self {
-        Self { status: "ok" }
+        Self {
+            status: "ok",
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+/// Readiness report returned by `/ready`.
+///
+/// Unlike `/health`, which reports whether sources can be fetched, readiness is
+/// driven by data freshness: the service is ready while every required symbol is
+/// tracked and updated within its staleness limit.
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+    /// `"ready"` or `"not_ready"`
+    pub status: &'static str,
+    /// Freshness of every required symbol
+    pub symbols: BTreeMap<String, FreshnessResponse>,
+}
+
+impl ReadyResponse {
+    /// Checks the freshness of every `required` symbol among `readers`.
+    pub fn from_readers(
+        required: &[String],
+        readers: &HashMap<String, DataProcessorReader>,
+    ) -> Self {
+        let now = now_millis();
+        let symbols: BTreeMap<String, FreshnessResponse> = required
+            .iter()
+            .map(|symbol| {
+                let freshness = match readers.get(symbol) {
+                    Some(reader) => {
+                        let reader = reader.latest();
+                        let age_ms = now.saturating_sub(reader.read().data.last_update);
+                        FreshnessResponse {
+                            tracked: true,
+                            age_ms: Some(age_ms),
+                            stale: reader.is_stale(age_ms),
+                        }
+                    }
+                    None => FreshnessResponse {
+                        tracked: false,
+                        age_ms: None,
+                        stale: true,
+                    },
+                };
+                (symbol.clone(), freshness)
+            })
+            .collect();
+        let status = if symbols.values().any(|s| s.stale) {
+            "not_ready"
+        } else {
+            "ready"
+        };
+        Self { status, symbols }
+    }
+
+    /// Returns `true` if every required symbol is fresh.
+    pub fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+}
+
+/// Freshness of one symbol as reported by `/ready`.
+#[derive(Debug, Serialize)]
+pub struct FreshnessResponse {
+    /// The symbol has a `DataProcessor`
+    pub tracked: bool,
+    /// Milliseconds since the latest update, `null` if not tracked
+    pub age_ms: Option<u64>,
+    /// Too old or not tracked
+    pub stale: bool,
+}
+
+/// Health of a single source as reported by `/health`.
+///
+/// Timestamps are Unix milliseconds, `null` if the event never happened.
+#[derive(Debug, Serialize)]
+pub struct SourceHealthResponse {
+    pub state: BreakerState,
+    pub healthy: bool,
+    pub consecutive_failures: u64,
+    pub last_success: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+    pub next_retry_at: Option<u64>,
+}
+
+impl From<&SourceStatus> for SourceHealthResponse {
+    fn from(value: &SourceStatus) -> Self {
+        Self {
+            state: value.breaker_state(),
+            healthy: value.is_healthy(),
+            consecutive_failures: value.failure_streak(),
+            last_success: value.last_success_at(),
+            last_error: value.last_error(),
+            last_error_at: value.last_error_at(),
+            next_retry_at: value.next_retry_at(),
+        }
     }
 }
 
@@ -38,12 +174,11 @@ impl Default for HealthResponse {
 ///
 /// # Fields
 /// - `symbol`: The trading pair symbol, e.g., `"BTCUSDT"`.
-/// - `price`: Current price, parsed from a string in the JSON response.
+/// - `price`: Current price, parsed exactly from a string in the JSON response.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BinancePrice {
     pub symbol: String,
-    #[serde(deserialize_with = "de_str_to_f64")]
-    pub price: f64,
+    pub price: Decimal,
 }
 
 /// Deserialize a string to `f64` for Binance API responses
@@ -63,11 +198,366 @@ where
 pub trait GetPrice {
     /// Returns the current price as `f64`.
     fn get_price(&self) -> f64;
+
+    /// Returns the current price exactly as the exchange sent it, if the response
+    /// parses it as a decimal.
+    fn get_decimal(&self) -> Option<Decimal> {
+        None
+    }
+
+    /// Returns the exchange-provided event time in Unix milliseconds, if any.
+    fn get_event_time(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the traded base volume over the exchange's reporting window
+    /// (usually 24h), if the response carries one.
+    fn get_volume(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the quantity traded at the price if the response is a single trade.
+    fn get_quantity(&self) -> Option<f64> {
+        None
+    }
 }
 
 impl GetPrice for BinancePrice {
     fn get_price(&self) -> f64 {
-        self.price
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+}
+
+/// Binance `<symbol>@trade` WebSocket stream message.
+///
+/// # Fields
+/// - `symbol`: The trading pair symbol (`"s"`).
+/// - `price`: Trade price (`"p"`), sent as a string.
+/// - `quantity`: Traded quantity (`"q"`), sent as a string.
+/// - `event_time`: Event time in Unix milliseconds (`"E"`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BinanceTrade {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p")]
+    pub price: Decimal,
+    #[serde(rename = "q", deserialize_with = "de_str_to_f64")]
+    pub quantity: f64,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+}
+
+impl GetPrice for BinanceTrade {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_event_time(&self) -> Option<u64> {
+        Some(self.event_time)
+    }
+
+    fn get_quantity(&self) -> Option<f64> {
+        Some(self.quantity)
+    }
+}
+
+/// Binance `<symbol>@ticker` (rolling 24h) WebSocket stream message.
+///
+/// # Fields
+/// - `symbol`: The trading pair symbol (`"s"`).
+/// - `price`: Last traded price (`"c"`), sent as a string.
+/// - `volume`: Traded base asset volume over 24h (`"v"`), sent as a string.
+/// - `event_time`: Event time in Unix milliseconds (`"E"`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BinanceTicker {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "c")]
+    pub price: Decimal,
+    #[serde(rename = "v", deserialize_with = "de_str_to_f64")]
+    pub volume: f64,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+}
+
+impl GetPrice for BinanceTicker {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_event_time(&self) -> Option<u64> {
+        Some(self.event_time)
+    }
+
+    fn get_volume(&self) -> Option<f64> {
+        Some(self.volume)
+    }
+}
+
+/// Coinbase Exchange `/products/<pair>/ticker` response.
+///
+/// # Fields
+/// - `price`: Last trade price, sent as a string.
+/// - `volume`: Traded base volume over 24h, sent as a string.
+/// - `trade_id`: Id of the last trade.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoinbaseTicker {
+    pub price: Decimal,
+    #[serde(deserialize_with = "de_str_to_f64")]
+    pub volume: f64,
+    pub trade_id: u64,
+}
+
+impl GetPrice for CoinbaseTicker {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_volume(&self) -> Option<f64> {
+        Some(self.volume)
+    }
+}
+
+/// Kraken `/0/public/Ticker?pair=<pair>` response.
+///
+/// Kraken nests the ticker under its own pair name (`XXBTZUSD` for `XBTUSD`) in a
+/// `result` map and reports failures in an `error` array with a `200` status, so
+/// the raw shape is flattened during deserialization and a non-empty `error` or
+/// an empty `result` is rejected.
+///
+/// # Fields
+/// - `pair`: Kraken's pair name, the key of the `result` map.
+/// - `price`: Last trade closed price (`c[0]`).
+/// - `volume`: Traded volume over the last 24h (`v[1]`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(try_from = "KrakenResponse")]
+pub struct KrakenTicker {
+    pub pair: String,
+    pub price: Decimal,
+    pub volume: f64,
+}
+
+/// Raw Kraken envelope, see [`KrakenTicker`].
+#[derive(Deserialize)]
+struct KrakenResponse {
+    error: Vec<String>,
+    #[serde(default)]
+    result: HashMap<String, KrakenPairTicker>,
+}
+
+/// Ticker of one Kraken pair; only the last trade (`c`: `[price, lot volume]`) and
+/// the volume (`v`: `[today, last 24h]`) are kept.
+#[derive(Deserialize)]
+struct KrakenPairTicker {
+    c: (String, String),
+    v: (String, String),
+}
+
+impl TryFrom<KrakenResponse> for KrakenTicker {
+    type Error = String;
+
+    fn try_from(value: KrakenResponse) -> Result<Self, Self::Error> {
+        if !value.error.is_empty() {
+            return Err(value.error.join(", "));
+        }
+        let (pair, ticker) = value
+            .result
+            .into_iter()
+            .next()
+            .ok_or("empty Kraken result")?;
+        let price = ticker.c.0.parse::<Decimal>()?;
+        let volume = ticker.v.1.parse::<f64>().map_err(|e| e.to_string())?;
+        Ok(Self {
+            pair,
+            price,
+            volume,
+        })
+    }
+}
+
+impl GetPrice for KrakenTicker {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_volume(&self) -> Option<f64> {
+        Some(self.volume)
+    }
+}
+
+/// OKX `/api/v5/market/ticker?instId=<pair>` response.
+///
+/// The ticker is the single element of `data`; a non-zero `code` is rejected.
+///
+/// # Fields
+/// - `inst_id`: Instrument id, e.g. `"BTC-USDT"`.
+/// - `price`: Last traded price (`last`).
+/// - `volume`: Traded base volume over 24h (`vol24h`).
+/// - `event_time`: Ticker time in Unix milliseconds (`ts`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(try_from = "OkxResponse")]
+pub struct OkxTicker {
+    pub inst_id: String,
+    pub price: Decimal,
+    pub volume: f64,
+    pub event_time: u64,
+}
+
+/// Raw OKX envelope, see [`OkxTicker`].
+#[derive(Deserialize)]
+struct OkxResponse {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    data: Vec<OkxData>,
+}
+
+/// One OKX ticker; numbers are sent as strings.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxData {
+    inst_id: String,
+    last: String,
+    vol24h: String,
+    ts: String,
+}
+
+impl TryFrom<OkxResponse> for OkxTicker {
+    type Error = String;
+
+    fn try_from(value: OkxResponse) -> Result<Self, Self::Error> {
+        if value.code != "0" {
+            return Err(format!("OKX error {}: {}", value.code, value.msg));
+        }
+        let data = value.data.into_iter().next().ok_or("empty OKX data")?;
+        Ok(Self {
+            inst_id: data.inst_id,
+            price: data.last.parse::<Decimal>()?,
+            volume: data.vol24h.parse::<f64>().map_err(|e| e.to_string())?,
+            event_time: data.ts.parse::<u64>().map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+impl GetPrice for OkxTicker {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_event_time(&self) -> Option<u64> {
+        Some(self.event_time)
+    }
+
+    fn get_volume(&self) -> Option<f64> {
+        Some(self.volume)
+    }
+}
+
+/// Bybit v5 `/v5/market/tickers?category=<category>&symbol=<symbol>` response.
+///
+/// The ticker is the single element of `result.list`; a non-zero `retCode` is rejected.
+///
+/// # Fields
+/// - `symbol`: The trading pair symbol, e.g. `"BTCUSDT"`.
+/// - `price`: Last traded price (`lastPrice`).
+/// - `volume`: Traded base volume over 24h (`volume24h`).
+/// - `event_time`: Response time in Unix milliseconds (`time`).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(try_from = "BybitResponse")]
+pub struct BybitTicker {
+    pub symbol: String,
+    pub price: Decimal,
+    pub volume: f64,
+    pub event_time: u64,
+}
+
+/// Raw Bybit envelope, see [`BybitTicker`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse {
+    ret_code: i64,
+    #[serde(default)]
+    ret_msg: String,
+    result: Option<BybitResult>,
+    time: u64,
+}
+
+#[derive(Deserialize)]
+struct BybitResult {
+    #[serde(default)]
+    list: Vec<BybitData>,
+}
+
+/// One Bybit ticker; numbers are sent as strings.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitData {
+    symbol: String,
+    last_price: String,
+    #[serde(rename = "volume24h")]
+    volume_24h: String,
+}
+
+impl TryFrom<BybitResponse> for BybitTicker {
+    type Error = String;
+
+    fn try_from(value: BybitResponse) -> Result<Self, Self::Error> {
+        if value.ret_code != 0 {
+            return Err(format!("Bybit error {}: {}", value.ret_code, value.ret_msg));
+        }
+        let data = value
+            .result
+            .and_then(|r| r.list.into_iter().next())
+            .ok_or("empty Bybit result")?;
+        Ok(Self {
+            symbol: data.symbol,
+            price: data.last_price.parse::<Decimal>()?,
+            volume: data.volume_24h.parse::<f64>().map_err(|e| e.to_string())?,
+            event_time: value.time,
+        })
+    }
+}
+
+impl GetPrice for BybitTicker {
+    fn get_price(&self) -> f64 {
+        self.price.to_f64()
+    }
+
+    fn get_decimal(&self) -> Option<Decimal> {
+        Some(self.price)
+    }
+
+    fn get_event_time(&self) -> Option<u64> {
+        Some(self.event_time)
+    }
+
+    fn get_volume(&self) -> Option<f64> {
+        Some(self.volume)
     }
 }
 
@@ -75,19 +565,64 @@ impl GetPrice for BinancePrice {
 ///
 /// Mirrors `RawData` exactly, so that it can be safely transmuted.
 /// Fields:
-/// - `min`: minimum value observed
-/// - `max`: maximum value observed
+/// - `min`: minimum value observed since start (session low)
+/// - `max`: maximum value observed since start (session high)
+/// - `window_min` / `window_max`: minimum and maximum over the SMA window
 /// - `curr_avg`: streaming mean of all observed values
 /// - `sma`: current Simple Moving Average
+/// - `variance` / `std_dev`: population variance and standard deviation over the SMA window
+/// - `bollinger_upper` / `bollinger_lower`: `sma` plus / minus `k` standard deviations
 /// - `data_point`: number of data points processed
+/// - `first_seen`: Unix milliseconds of the first sample
+/// - `last_update`: Unix milliseconds the latest sample was received
+/// - `event_time`: latest exchange-provided event time in Unix milliseconds, `null`
+///   if the source never reported one
 #[repr(C)]
 #[derive(Debug, Serialize)]
 pub struct StatsResponse {
     pub min: f64,
     pub max: f64,
+    pub window_min: f64,
+    pub window_max: f64,
     pub curr_avg: f64,
     pub sma: f64,
+    pub variance: f64,
+    pub std_dev: f64,
+    pub bollinger_upper: f64,
+    pub bollinger_lower: f64,
     pub data_point: u64,
+    pub first_seen: u64,
+    pub last_update: u64,
+    #[serde(serialize_with = "zero_as_null")]
+    pub event_time: u64,
+}
+
+/// Serializes an unset (`0`) timestamp as `null`.
+fn zero_as_null<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        0 => serializer.serialize_none(),
+        v => serializer.serialize_u64(*v),
+    }
+}
+
+impl StatsResponse {
+    /// Rounds every price-denominated field to `tick`, and the variance to `tick^2`.
+    pub fn round_to(&mut self, tick: TickSize) {
+        for price in [
+            &mut self.min,
+            &mut self.max,
+            &mut self.window_min,
+            &mut self.window_max,
+            &mut self.curr_avg,
+            &mut self.sma,
+            &mut self.std_dev,
+            &mut self.bollinger_upper,
+            &mut self.bollinger_lower,
+        ] {
+            *price = tick.round(*price);
+        }
+        self.variance = tick.squared().round(self.variance);
+    }
 }
 
 impl From<RawData> for StatsResponse {
@@ -97,12 +632,136 @@ impl From<RawData> for StatsResponse {
     }
 }
 
+/// Statistics of a symbol as returned by `/stats`.
+///
+/// Serializes as the fields of `StatsResponse`, the `age_ms` and `stale` flag of the
+/// data, one `ema_*` field per
+/// configured EMA and a `p*` (lifetime) and `window_p*` (SMA window) field per
+/// configured quantile, plus an `indicators` object holding the outputs of the
+/// configured indicators (`null` while warming up) and a `warm_up` object with the
+/// warm-up state of each indicator; `StatsResponse` itself keeps the `RawData`
+/// layout.
+#[derive(Debug, Serialize)]
+pub struct SymbolStatsResponse {
+    #[serde(flatten)]
+    pub stats: StatsResponse,
+    /// Milliseconds between `last_update` and the time the response was built
+    pub age_ms: u64,
+    /// `age_ms` exceeds the configured staleness limit
+    pub stale: bool,
+    /// EMA name (e.g. `ema_20`) -> value
+    #[serde(flatten)]
+    pub emas: BTreeMap<String, f64>,
+    /// Quantile name (e.g. `p95`, `window_p95`) -> value
+    #[serde(flatten)]
+    pub quantiles: BTreeMap<String, f64>,
+    /// Indicator output name (e.g. `roc_10`) -> value; nested so that names chosen
+    /// by custom indicators cannot shadow the built-in fields
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub indicators: BTreeMap<String, f64>,
+    /// Indicator (e.g. `rsi(14)`) -> warm-up state
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub warm_up: BTreeMap<String, IndicatorWarmUp>,
+}
+
+/// Warm-up state of one indicator in `/stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct IndicatorWarmUp {
+    /// Some outputs of the indicator are still `null`
+    pub warming_up: bool,
+    /// Prices the indicator still needs before every output has a value
+    pub samples_needed: u64,
+}
+
+impl SymbolStatsResponse {
+    /// Builds the response for `snapshot`, naming its EMAs after `ema_specs`, its
+    /// quantiles after `quantile_specs`, its indicator outputs after
+    /// `indicator_names` and their warm-up state after `indicator_specs`.
+    pub fn new(
+        snapshot: Snapshot,
+        ema_specs: &[EmaSpec],
+        quantile_specs: &[QuantileSpec],
+        indicator_names: &[String],
+        indicator_specs: &[IndicatorSpec],
+    ) -> Self {
+        let emas = ema_specs
+            .iter()
+            .zip(snapshot.emas)
+            .map(|(spec, ema)| (spec.to_string(), ema))
+            .collect();
+        let lifetime = quantile_specs
+            .iter()
+            .zip(snapshot.quantiles)
+            .map(|(spec, q)| (spec.to_string(), q));
+        let window = quantile_specs
+            .iter()
+            .zip(snapshot.window_quantiles)
+            .map(|(spec, q)| (format!("window_{spec}"), q));
+        let indicators = indicator_names
+            .iter()
+            .cloned()
+            .zip(snapshot.indicators)
+            .collect();
+        let warm_up = indicator_specs
+            .iter()
+            .zip(snapshot.samples_needed)
+            .map(|(spec, samples_needed)| {
+                let state = IndicatorWarmUp {
+                    warming_up: samples_needed > 0,
+                    samples_needed,
+                };
+                (spec.to_string(), state)
+            })
+            .collect();
+        Self {
+            age_ms: now_millis().saturating_sub(snapshot.data.last_update),
+            stale: false,
+            stats: snapshot.data.into(),
+            emas,
+            quantiles: lifetime.chain(window).collect(),
+            indicators,
+            warm_up,
+        }
+    }
+}
+
+impl SymbolStatsResponse {
+    /// Rounds the statistics, EMAs and quantiles to `tick`; indicator outputs are
+    /// left as computed since most are not prices.
+    pub fn round_to(&mut self, tick: TickSize) {
+        self.stats.round_to(tick);
+        for value in self.emas.values_mut().chain(self.quantiles.values_mut()) {
+            *value = tick.round(*value);
+        }
+    }
+}
+
+impl From<&DataProcessorReader> for SymbolStatsResponse {
+    /// Builds the response of the latest snapshot, rounded to the symbol's tick size
+    /// and flagged stale past its staleness limit.
+    fn from(value: &DataProcessorReader) -> Self {
+        let value = value.latest();
+        let mut response = Self::new(
+            value.read(),
+            value.ema_specs(),
+            value.quantile_specs(),
+            value.indicator_names(),
+            value.indicator_specs(),
+        );
+        response.stale = value.is_stale(response.age_ms);
+        if let Some(tick) = value.tick_size() {
+            response.round_to(tick);
+        }
+        response
+    }
+}
+
 /// Combined response for all state statistics of a symbol.
 ///
 /// Used for serializing symbol -> stats mapping.
 pub struct AllStatesResponse {
     pub symbol: String,
-    pub stats: StatsResponse,
+    pub stats: SymbolStatsResponse,
 }
 
 impl Serialize for AllStatesResponse {
@@ -117,8 +776,571 @@ impl Serialize for AllStatesResponse {
     }
 }
 
+/// Symbols started by `POST /admin/symbols`.
+#[derive(Debug, Serialize)]
+pub struct AddedSymbolsResponse {
+    pub symbols: Vec<String>,
+}
+
+/// Outcome of a configuration reload, returned by `/admin/reload`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadResponse {
+    /// Unix time in milliseconds of the reload
+    pub at: u64,
+    /// `"ok"`, `"partial"` when some changes were not applied, or `"failed"` when
+    /// the configuration could not be loaded
+    pub status: &'static str,
+    /// Symbols of the added sources
+    pub added: Vec<String>,
+    /// Symbols of the removed sources
+    pub removed: Vec<String>,
+    /// Symbol -> how its changed statistics settings are applied to its running
+    /// statistics, see `StatsConfig::changes_from`
+    pub statistics: BTreeMap<String, Vec<String>>,
+    /// Running sources were given new intervals, timeouts, retry, breaker,
+    /// consensus or statistics settings
+    pub reconfigured: bool,
+    /// Changes that were not applied, and why
+    pub errors: Vec<String>,
+}
+
+impl ReloadResponse {
+    /// Empty report of a reload at `at`.
+    pub fn new(at: u64) -> Self {
+        Self {
+            at,
+            status: "ok",
+            added: Vec::new(),
+            removed: Vec::new(),
+            statistics: BTreeMap::new(),
+            reconfigured: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Report of a reload whose configuration could not be loaded.
+    pub fn failed(at: u64, errors: Vec<String>) -> Self {
+        Self {
+            status: "failed",
+            errors,
+            ..Self::new(at)
+        }
+    }
+
+    /// Sets the status from the errors.
+    pub fn finish(mut self) -> Self {
+        if !self.errors.is_empty() {
+            self.status = "partial";
+        }
+        self
+    }
+
+    /// Returns `true` if the configuration could not be loaded.
+    pub fn is_failed(&self) -> bool {
+        self.status == "failed"
+    }
+}
+
+/// Statistics of one time window of a symbol, returned by `/stats?window=`.
+///
+/// Computed as of the latest sample, not the wall clock: samples only leave the
+/// window when a newer one arrives, so a symbol without recent updates still
+/// reports the window ending at its last price. `age_ms` and `stale` tell clients
+/// how far that is behind.
+#[derive(Debug, Serialize)]
+pub struct WindowStatsResponse {
+    pub symbol: String,
+    /// Window length, e.g. `"5m"`
+    pub window: String,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Oldest price in the window
+    pub first: f64,
+    /// Latest price
+    pub last: f64,
+    /// Change from `first` to `last` in percent
+    pub change_pct: f64,
+    /// Number of samples in the window
+    pub count: u64,
+    /// Milliseconds between the latest sample and the time the response was built
+    pub age_ms: u64,
+    /// `age_ms` exceeds the configured staleness limit
+    pub stale: bool,
+}
+
+impl WindowStatsResponse {
+    /// Builds the response for `window` of `reader`, or `None` if the window is
+    /// not tracked.
+    pub fn from_reader(
+        symbol: &str,
+        reader: &DataProcessorReader,
+        window: WindowSpec,
+    ) -> Option<Self> {
+        let reader = reader.latest();
+        let idx = reader.window_specs().iter().position(|w| *w == window)?;
+        let snapshot = reader.read();
+        let stats = snapshot.windows[idx];
+        let age_ms = now_millis().saturating_sub(snapshot.data.last_update);
+        let round = |price: f64| reader.tick_size().map_or(price, |t| t.round(price));
+        Some(Self {
+            symbol: symbol.to_string(),
+            window: window.to_string(),
+            mean: round(stats.mean),
+            min: round(stats.min),
+            max: round(stats.max),
+            first: round(stats.first),
+            last: round(stats.last),
+            change_pct: stats.change_pct,
+            count: stats.count,
+            age_ms,
+            stale: reader.is_stale(age_ms),
+        })
+    }
+}
+
+/// One candle of `/candles` in the Binance kline layout.
+///
+/// Serializes as the array
+/// `[open_time, open, high, low, close, volume, close_time, quote_volume, trades,
+/// taker_buy_volume, taker_buy_quote_volume, "0"]` with prices and volumes as
+/// strings, prices written with the digits of the tick size when there is one. The
+/// taker side of trades is not known, so both taker volumes are `"0"`.
+pub struct KlineResponse {
+    pub candle: Candle,
+    /// Resolution of the candle in milliseconds
+    pub interval_ms: u64,
+    /// Tick size of the symbol, if any
+    pub tick_size: Option<TickSize>,
+}
+
+impl Serialize for KlineResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let c = &self.candle;
+        let price = |price: f64| match self.tick_size {
+            Some(tick) => tick.to_decimal(price).to_string(),
+            None => price.to_string(),
+        };
+        let mut seq = serializer.serialize_seq(Some(12))?;
+        seq.serialize_element(&c.open_time)?;
+        seq.serialize_element(&price(c.open))?;
+        seq.serialize_element(&price(c.high))?;
+        seq.serialize_element(&price(c.low))?;
+        seq.serialize_element(&price(c.close))?;
+        seq.serialize_element(&c.volume.to_string())?;
+        seq.serialize_element(&(c.open_time + self.interval_ms - 1))?;
+        seq.serialize_element(&c.quote_volume.to_string())?;
+        seq.serialize_element(&c.trades)?;
+        seq.serialize_element("0")?;
+        seq.serialize_element("0")?;
+        seq.serialize_element("0")?;
+        seq.end()
+    }
+}
+
+/// Consensus price of a symbol fed by several sources, returned by `/consensus`.
+#[derive(Debug, Serialize)]
+pub struct ConsensusResponse {
+    pub symbol: String,
+    pub method: ConsensusMethod,
+    /// Last consensus written to the symbol's statistics
+    pub price: f64,
+    /// Unix time in milliseconds of the last consensus
+    pub updated_at: u64,
+    /// Latest quote of every source, ordered by source name
+    pub sources: Vec<ContributionResponse>,
+}
+
+/// Contribution of one source to a [`ConsensusResponse`].
+#[derive(Debug, Serialize)]
+pub struct ContributionResponse {
+    /// Source name, usually its URL
+    pub source: String,
+    /// Latest quoted price
+    pub price: f64,
+    /// Reported traded volume, if any
+    pub volume: Option<f64>,
+    /// Share of the consensus in `[0, 1]`; `0` when trimmed, not a median or stale
+    pub weight: f64,
+    /// Older than the consensus `max_age`, so currently ignored
+    pub stale: bool,
+    /// Milliseconds since the quote was received
+    pub age_ms: u64,
+    /// `price - consensus`
+    pub deviation: f64,
+    /// Deviation in basis points of the consensus
+    pub deviation_bps: f64,
+}
+
 // Compile-time checks to ensure transmute safety between RawData and StatsResponse
 const _: () = assert!(size_of::<RawData>() == size_of::<StatsResponse>());
 const _: () = assert!(align_of::<RawData>() == align_of::<StatsResponse>());
 
 // Future: Add memory offset checks per field in unit tests
+
+#[cfg(test)]
+mod price_tests {
+    use super::*;
+
+    #[test]
+    fn test_binance_trade_fixture() {
+        let json = r#"{"e":"trade","E":1672515782136,"s":"BNBBTC","t":12345,"p":"0.001","q":"100","T":1672515782136,"m":true,"M":true}"#;
+        let trade: BinanceTrade = serde_json::from_str(json).unwrap();
+        assert_eq!(trade.symbol, "BNBBTC");
+        assert_eq!(trade.get_price(), 0.001);
+        assert_eq!(trade.get_quantity(), Some(100.0));
+        assert_eq!(trade.event_time, 1672515782136);
+    }
+
+    #[test]
+    fn test_binance_ticker_fixture() {
+        let json = r#"{"e":"24hrTicker","E":1672515782136,"s":"BTCUSDT","p":"0.0015","P":"250.00","w":"0.0018","x":"0.0009","c":"117454.33","Q":"10","b":"117454.32","B":"10","a":"117454.34","A":"100","o":"117000.00","h":"118000.00","l":"116000.00","v":"10000","q":"18","O":0,"C":86400000,"F":0,"L":18150,"n":18151}"#;
+        let ticker: BinanceTicker = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.symbol, "BTCUSDT");
+        assert_eq!(ticker.get_price(), 117454.33);
+        assert_eq!(ticker.get_volume(), Some(10000.0));
+    }
+
+    #[test]
+    fn test_coinbase_ticker_fixture() {
+        let json = r#"{"ask":"117455.01","bid":"117455.00","volume":"8123.51","trade_id":86326522,"price":"117454.33","size":"0.00698254","time":"2025-08-11T08:14:31.833897Z","rfq_volume":"12.4"}"#;
+        let ticker: CoinbaseTicker = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.get_price(), 117454.33);
+        assert_eq!(ticker.get_volume(), Some(8123.51));
+        assert_eq!(ticker.trade_id, 86326522);
+        assert_eq!(ticker.get_event_time(), None);
+    }
+
+    #[test]
+    fn test_kraken_ticker_fixture() {
+        let json = r#"{"error":[],"result":{"XXBTZUSD":{"a":["117455.10000","1","1.000"],"b":["117455.00000","2","2.000"],"c":["117454.30000","0.00067643"],"v":["1021.4","3811.2"],"p":["117401.2","117210.9"],"t":[12011,51730],"l":["116990.0","116201.4"],"h":["117802.2","118010.0"],"o":"117101.80000"}}}"#;
+        let ticker: KrakenTicker = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.pair, "XXBTZUSD");
+        assert_eq!(ticker.get_price(), 117454.3);
+        assert_eq!(ticker.get_volume(), Some(3811.2));
+    }
+
+    #[test]
+    fn test_kraken_error_is_rejected() {
+        let json = r#"{"error":["EQuery:Unknown asset pair"]}"#;
+        let err = serde_json::from_str::<KrakenTicker>(json).unwrap_err();
+        assert!(err.to_string().contains("Unknown asset pair"));
+        assert!(serde_json::from_str::<KrakenTicker>(r#"{"error":[],"result":{}}"#).is_err());
+    }
+
+    #[test]
+    fn test_okx_ticker_fixture() {
+        let json = r#"{"code":"0","msg":"","data":[{"instType":"SPOT","instId":"BTC-USDT","last":"117454.3","lastSz":"0.1","askPx":"117454.4","askSz":"11","bidPx":"117454.3","bidSz":"5","open24h":"117001","high24h":"118000","low24h":"116000","volCcy24h":"2222","vol24h":"2222","sodUtc0":"117100","sodUtc8":"117200","ts":"1754900071000"}]}"#;
+        let ticker: OkxTicker = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.inst_id, "BTC-USDT");
+        assert_eq!(ticker.get_price(), 117454.3);
+        assert_eq!(ticker.get_volume(), Some(2222.0));
+        assert_eq!(ticker.get_event_time(), Some(1754900071000));
+
+        let json = r#"{"code":"51001","msg":"Instrument ID does not exist","data":[]}"#;
+        assert!(serde_json::from_str::<OkxTicker>(json).is_err());
+    }
+
+    #[test]
+    fn test_bybit_ticker_fixture() {
+        let json = r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","list":[{"symbol":"BTCUSDT","bid1Price":"117454.3","bid1Size":"0.1","ask1Price":"117454.4","ask1Size":"0.2","lastPrice":"117454.33","prevPrice24h":"117000","price24hPcnt":"0.0039","highPrice24h":"118000","lowPrice24h":"116000","turnover24h":"1000","volume24h":"10"}]},"retExtInfo":{},"time":1754900071000}"#;
+        let ticker: BybitTicker = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.symbol, "BTCUSDT");
+        assert_eq!(ticker.get_price(), 117454.33);
+        assert_eq!(ticker.get_volume(), Some(10.0));
+        assert_eq!(ticker.get_event_time(), Some(1754900071000));
+
+        let json = r#"{"retCode":10001,"retMsg":"Not supported symbols","result":{},"retExtInfo":{},"time":1754900071000}"#;
+        assert!(serde_json::from_str::<BybitTicker>(json).is_err());
+    }
+
+    #[test]
+    fn test_invalid_price_string_is_rejected() {
+        let json = r#"{"E":1,"s":"BTCUSDT","p":"not-a-number"}"#;
+        assert!(serde_json::from_str::<BinanceTrade>(json).is_err());
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+    use crate::data_processor::{DataProcessor, StatsConfig};
+    use crate::indicator::IndicatorRegistry;
+
+    #[test]
+    fn test_stats_response_flattens_emas() {
+        let config = StatsConfig::new(2).with_emas(vec![EmaSpec::Span(3), EmaSpec::Alpha(0.25)]);
+        let (reader, writer) = DataProcessor::split_with(&config, 10.0);
+        writer.write(14.0);
+
+        let json = serde_json::to_value(SymbolStatsResponse::from(&reader)).unwrap();
+        assert_eq!(json["max"], 14.0);
+        assert_eq!(json["window_min"], 10.0);
+        assert_eq!(json["window_max"], 14.0);
+        assert_eq!(json["data_point"], 2);
+        assert_eq!(json["variance"], 4.0);
+        assert_eq!(json["std_dev"], 2.0);
+        assert_eq!(json["bollinger_upper"], 16.0);
+        assert_eq!(json["bollinger_lower"], 8.0);
+        assert_eq!(json["ema_3"], 12.0);
+        assert_eq!(json["ema_alpha_0.25"], 11.0);
+    }
+
+    #[test]
+    fn test_stats_response_flattens_quantiles() {
+        let quantiles = vec![QuantileSpec::new(0.5), QuantileSpec::new(0.95)];
+        let config = StatsConfig::new(3).with_quantiles(quantiles);
+        let (reader, writer) = DataProcessor::split_with(&config, 10.0);
+        writer.write(20.0);
+
+        let json = serde_json::to_value(SymbolStatsResponse::from(&reader)).unwrap();
+        assert_eq!(json["p50"], 15.0);
+        assert_eq!(json["window_p50"], 10.0);
+        assert_eq!(json["window_p95"], 19.0);
+        assert_eq!(json["data_point"], 2);
+        assert!(json.get("indicators").is_none() && json.get("warm_up").is_none());
+    }
+
+    #[test]
+    fn test_stats_response_is_rounded_to_tick_size() {
+        let config = StatsConfig::new(3)
+            .with_emas(vec![EmaSpec::Span(3)])
+            .with_quantiles(vec![QuantileSpec::new(0.5)])
+            .with_tick_sizes(
+                None,
+                [("BTCUSDT".to_string(), "0.01".parse().unwrap())].into(),
+            );
+        let (reader, writer) = DataProcessor::split_with(&config.for_symbol("BTCUSDT"), 10.01);
+        writer.write(10.02);
+        writer.write(10.044);
+
+        let json = serde_json::to_string(&SymbolStatsResponse::from(&reader)).unwrap();
+        assert!(json.contains(r#""max":10.04,"#), "{json}");
+        assert!(json.contains(r#""sma":10.02,"#), "{json}");
+        assert!(json.contains(r#""curr_avg":10.02,"#), "{json}");
+        assert!(json.contains(r#""variance":0.0002,"#), "{json}");
+        assert!(json.contains(r#""ema_3":10.03,"#), "{json}");
+
+        // Symbols without a tick size keep full precision
+        let (reader, writer) = DataProcessor::split_with(&config.for_symbol("ETHUSDT"), 10.01);
+        writer.write(10.044);
+        assert_eq!(SymbolStatsResponse::from(&reader).stats.max, 10.044);
+    }
+
+    #[test]
+    fn test_stats_response_reports_times_and_age() {
+        let (reader, writer) = DataProcessor::split(2, 10.0);
+        let json = serde_json::to_value(SymbolStatsResponse::from(&reader)).unwrap();
+        assert!(json["event_time"].is_null());
+        assert_eq!(json["first_seen"], json["last_update"]);
+
+        let received_at = now_millis() - 3_000;
+        writer.write_update(11.0, received_at, Some(received_at - 50), 0.0);
+        let json = serde_json::to_value(SymbolStatsResponse::from(&reader)).unwrap();
+        assert_eq!(json["event_time"], received_at - 50);
+        // The late update does not move `last_update` back
+        let last_update = json["last_update"].as_u64().unwrap();
+        assert!(last_update > received_at);
+        let age = json["age_ms"].as_u64().unwrap();
+        assert!(last_update + age <= now_millis());
+    }
+
+    #[test]
+    fn test_binance_price_keeps_exact_decimal() {
+        let price: BinancePrice =
+            serde_json::from_str(r#"{"symbol":"SHIBUSDT","price":"0.00001234"}"#).unwrap();
+        assert_eq!(price.price.to_string(), "0.00001234");
+        assert_eq!(price.get_price(), 0.00001234);
+    }
+
+    #[test]
+    fn test_stats_response_nests_indicators() {
+        let indicators = IndicatorRegistry::default()
+            .resolve_list("roc(1),roc(5)")
+            .unwrap();
+        let config = StatsConfig::new(3).with_indicators(indicators);
+        let (reader, writer) = DataProcessor::split_with(&config, 10.0);
+        writer.write(15.0);
+
+        let json = serde_json::to_value(SymbolStatsResponse::from(&reader)).unwrap();
+        assert_eq!(json["indicators"]["roc_1"], 50.0);
+        // Still warming up
+        assert!(json["indicators"]["roc_5"].is_null());
+        assert!(json.get("roc_1").is_none());
+        assert_eq!(json["warm_up"]["roc(1)"]["warming_up"], false);
+        assert_eq!(json["warm_up"]["roc(5)"]["warming_up"], true);
+        assert_eq!(json["warm_up"]["roc(5)"]["samples_needed"], 4);
+    }
+
+    #[test]
+    fn test_kline_response_matches_binance_layout() {
+        let mut kline = KlineResponse {
+            candle: Candle {
+                open_time: 1_499_040_000_000,
+                open: 0.5,
+                high: 0.8,
+                low: 0.25,
+                close: 0.75,
+                volume: 10.0,
+                quote_volume: 6.5,
+                trades: 308,
+            },
+            interval_ms: 60_000,
+            tick_size: None,
+        };
+        let json = serde_json::to_string(&kline).unwrap();
+        assert_eq!(
+            json,
+            r#"[1499040000000,"0.5","0.8","0.25","0.75","10",1499040059999,"6.5",308,"0","0","0"]"#
+        );
+
+        // With a tick size prices carry its digits, like Binance sends them
+        kline.tick_size = Some("0.01".parse().unwrap());
+        kline.candle.high = 0.1 + 0.7;
+        let json = serde_json::to_value(&kline).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                1499040000000u64,
+                "0.50",
+                "0.80",
+                "0.25",
+                "0.75",
+                "10",
+                1499040059999u64,
+                "6.5",
+                308,
+                "0",
+                "0",
+                "0"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_window_stats_response_matches_configured_window() {
+        let config = StatsConfig::new(2).with_windows(vec!["5m".parse().unwrap()]);
+        let (reader, writer) = DataProcessor::split_with(&config, 100.0);
+        writer.write(105.0);
+
+        let res =
+            WindowStatsResponse::from_reader("BTCUSDT", &reader, "300s".parse().unwrap()).unwrap();
+        let json = serde_json::to_value(res).unwrap();
+        assert_eq!(json["window"], "5m");
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["mean"], 102.5);
+        assert_eq!(json["change_pct"], 5.0);
+        assert!(
+            WindowStatsResponse::from_reader("BTCUSDT", &reader, "1m".parse().unwrap()).is_none()
+        );
+    }
+
+    #[test]
+    fn test_window_stats_response_rounds_every_price() {
+        let config = StatsConfig::new(2)
+            .with_windows(vec!["5m".parse().unwrap()])
+            .with_tick_sizes(Some("0.01".parse().unwrap()), BTreeMap::new());
+        let (reader, writer) = DataProcessor::split_with(&config, 0.1);
+        writer.write(0.2);
+        writer.write(0.1 + 0.2);
+
+        let res =
+            WindowStatsResponse::from_reader("BTCUSDT", &reader, "5m".parse().unwrap()).unwrap();
+        let json = serde_json::to_string(&res).unwrap();
+        assert!(json.contains(r#""mean":0.2,"min":0.1,"max":0.3,"first":0.1,"last":0.3"#));
+    }
+}
+
+#[cfg(test)]
+mod health_tests {
+    use super::*;
+    use std::{collections::HashMap, time::Duration};
+
+    fn sources(unhealthy: usize, total: usize) -> HashMap<String, Arc<SourceStatus>> {
+        (0..total)
+            .map(|i| {
+                let status = SourceStatus::default();
+                if i < unhealthy {
+                    status.set_breaker_state(BreakerState::Open);
+                    status.record_failure(5, Duration::from_secs(1), true, "timeout".into());
+                } else {
+                    status.record_success();
+                }
+                (format!("SYM{i}"), Arc::new(status))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_all_healthy_is_ok() {
+        let map = sources(0, 3);
+        let res = HealthResponse::from_sources(&map, 0.5);
+        assert_eq!(res.status, "ok");
+        assert!(
+            res.sources
+                .values()
+                .all(|s| s.healthy && s.last_success.is_some())
+        );
+    }
+
+    #[test]
+    fn test_below_fraction_is_degraded() {
+        let map = sources(1, 3);
+        let res = HealthResponse::from_sources(&map, 0.5);
+        assert_eq!(res.status, "degraded");
+        assert!(!res.is_unhealthy());
+        let bad = &res.sources["SYM0"];
+        assert_eq!(bad.state, BreakerState::Open);
+        assert_eq!(bad.consecutive_failures, 5);
+        assert_eq!(bad.last_error.as_deref(), Some("timeout"));
+    }
+
+    #[test]
+    fn test_at_fraction_is_unhealthy() {
+        let map = sources(2, 4);
+        assert!(HealthResponse::from_sources(&map, 0.5).is_unhealthy());
+        let map = sources(4, 4);
+        assert!(HealthResponse::from_sources(&map, 1.0).is_unhealthy());
+    }
+
+    fn reader(stale_after: Duration) -> DataProcessorReader {
+        let config = crate::data_processor::StatsConfig::new(2).with_stale_after(Some(stale_after));
+        crate::data_processor::DataProcessor::split_with(&config, 1.0).0
+    }
+
+    #[test]
+    fn test_ready_requires_fresh_tracked_symbols() {
+        let readers: HashMap<String, DataProcessorReader> = [
+            ("FRESH".to_string(), reader(Duration::from_secs(60))),
+            ("OLD".to_string(), reader(Duration::from_millis(1))),
+        ]
+        .into();
+        std::thread::sleep(Duration::from_millis(5));
+
+        let res = ReadyResponse::from_readers(&["FRESH".to_string()], &readers);
+        assert!(res.is_ready());
+        assert!(res.symbols["FRESH"].tracked && !res.symbols["FRESH"].stale);
+
+        let required = ["FRESH".to_string(), "OLD".to_string()];
+        let res = ReadyResponse::from_readers(&required, &readers);
+        assert_eq!(res.status, "not_ready");
+        assert!(res.symbols["OLD"].stale && res.symbols["OLD"].age_ms >= Some(5));
+        let stats = SymbolStatsResponse::from(&readers["OLD"]);
+        assert!(stats.stale);
+        let config = crate::data_processor::StatsConfig::new(2)
+            .with_stale_after(Some(Duration::from_millis(1)))
+            .with_windows(vec!["1m".parse().unwrap()]);
+        let (reader, _writer) = crate::data_processor::DataProcessor::split_with(&config, 1.0);
+        std::thread::sleep(Duration::from_millis(5));
+        let window =
+            WindowStatsResponse::from_reader("OLD", &reader, "1m".parse().unwrap()).unwrap();
+        assert!(window.stale && window.age_ms >= 5);
+
+        let res = ReadyResponse::from_readers(&["MISSING".to_string()], &readers);
+        assert!(!res.is_ready());
+        assert!(!res.symbols["MISSING"].tracked);
+    }
+}
@@ -0,0 +1,279 @@
+use std::{fmt, str::FromStr};
+
+use crate::utils::extract_symbol;
+
+/// Exchange whose API a configured URL points at.
+///
+/// Selects the response type used to parse the endpoint and how the symbol is
+/// read from the URL. Symbols are normalised to the Binance style (`BTCUSDT`), so
+/// the same pair reported by different exchanges shares a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Exchange {
+    /// `https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT` and stream URLs
+    #[default]
+    Binance,
+    /// `https://api.exchange.coinbase.com/products/BTC-USD/ticker`
+    Coinbase,
+    /// `https://api.kraken.com/0/public/Ticker?pair=XBTUSD`
+    Kraken,
+    /// `https://www.okx.com/api/v5/market/ticker?instId=BTC-USDT`
+    Okx,
+    /// `https://api.bybit.com/v5/market/tickers?category=spot&symbol=BTCUSDT`
+    Bybit,
+}
+
+impl Exchange {
+    /// Every supported exchange.
+    pub const ALL: [Self; 5] = [
+        Self::Binance,
+        Self::Coinbase,
+        Self::Kraken,
+        Self::Okx,
+        Self::Bybit,
+    ];
+
+    /// Lowercase tag used in configuration, e.g. `"kraken"`.
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Self::Binance => "binance",
+            Self::Coinbase => "coinbase",
+            Self::Kraken => "kraken",
+            Self::Okx => "okx",
+            Self::Bybit => "bybit",
+        }
+    }
+
+    /// Reads the symbol from an endpoint URL of this exchange.
+    ///
+    /// # Returns
+    /// The normalised symbol, or `None` if the URL does not carry one.
+    pub fn extract_symbol(self, url: &str) -> Option<String> {
+        match self {
+            Self::Binance => extract_symbol(url),
+            Self::Coinbase => {
+                // `.../products/BTC-USD/ticker`
+                let mut segments = url.split('?').next()?.rsplit('/');
+                segments.next()?;
+                normalize_pair(segments.next()?)
+            }
+            Self::Kraken => query_param(url, "pair").and_then(normalize_pair),
+            Self::Okx => query_param(url, "instId").and_then(normalize_pair),
+            Self::Bybit => query_param(url, "symbol").and_then(normalize_pair),
+        }
+    }
+}
+
+impl fmt::Display for Exchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl FromStr for Exchange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.tag().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown exchange `{s}`"))
+    }
+}
+
+/// Who serves a configured endpoint and therefore how its response is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Provider {
+    /// A built-in exchange with its own response type
+    Exchange(Exchange),
+    /// A provider configured with a `JsonAdapter`, by lowercase name
+    Json(String),
+}
+
+impl Default for Provider {
+    fn default() -> Self {
+        Self::Exchange(Exchange::default())
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exchange(exchange) => exchange.fmt(f),
+            Self::Json(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A configured endpoint together with its provider.
+///
+/// Written in configuration as `<provider>:<url>`, e.g.
+/// `kraken:https://api.kraken.com/0/public/Ticker?pair=XBTUSD`. Tags that are not a
+/// built-in exchange name a JSON provider. URLs without a tag are Binance endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceUrl {
+    /// Provider serving `url`
+    pub provider: Provider,
+    /// Endpoint URL without the tag
+    pub url: String,
+}
+
+impl SourceUrl {
+    /// Parses `<provider>:<url>` or a plain (Binance) URL.
+    ///
+    /// # Example
+    /// ```
+    /// use aboss_task::exchange::{Exchange, Provider, SourceUrl};
+    /// let src = SourceUrl::parse("okx:https://www.okx.com/api/v5/market/ticker?instId=BTC-USDT");
+    /// assert_eq!(src.provider, Provider::Exchange(Exchange::Okx));
+    /// assert_eq!(src.symbol().as_deref(), Some("BTCUSDT"));
+    /// ```
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if let Some((tag, url)) = value.split_once(':')
+            && is_tag(tag)
+            && url.contains("://")
+        {
+            let provider = match tag.parse::<Exchange>() {
+                Ok(exchange) => Provider::Exchange(exchange),
+                Err(_) => Provider::Json(tag.to_ascii_lowercase()),
+            };
+            return Self {
+                provider,
+                url: url.to_string(),
+            };
+        }
+        Self {
+            provider: Provider::default(),
+            url: value.to_string(),
+        }
+    }
+
+    /// Normalised symbol of the endpoint.
+    ///
+    /// See [`Exchange::extract_symbol`]; for JSON providers the common query
+    /// parameters (`symbol`, `pair`, `instId`, `product_id`, `market`) are tried.
+    pub fn symbol(&self) -> Option<String> {
+        match &self.provider {
+            Provider::Exchange(exchange) => exchange.extract_symbol(&self.url),
+            Provider::Json(_) => ["symbol", "pair", "instId", "product_id", "market"]
+                .into_iter()
+                .find_map(|key| query_param(&self.url, key))
+                .and_then(normalize_pair),
+        }
+    }
+}
+
+/// Returns `true` if `tag` looks like a provider name rather than a URL scheme part.
+fn is_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Returns the value of query parameter `key` in `url`.
+fn query_param<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+/// Normalises an exchange pair name (`BTC-USD`, `XBTUSD`, `btc_usdt`) to `BTCUSD`.
+///
+/// Separators are dropped and Kraken's legacy asset codes (`XBT`, `XDG`) are
+/// mapped to their common names.
+pub fn normalize_pair(pair: &str) -> Option<String> {
+    let pair: String = pair
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | '/'))
+        .collect::<String>()
+        .to_uppercase();
+    if pair.is_empty() {
+        return None;
+    }
+    let pair = match pair.strip_prefix("XBT") {
+        Some(quote) => format!("BTC{quote}"),
+        None => match pair.strip_prefix("XDG") {
+            Some(quote) => format!("DOGE{quote}"),
+            None => pair,
+        },
+    };
+    Some(pair)
+}
+
+#[cfg(test)]
+mod exchange_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tagged_and_plain_urls() {
+        let plain = SourceUrl::parse("https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT");
+        assert_eq!(plain.provider, Provider::Exchange(Exchange::Binance));
+        assert_eq!(plain.symbol().as_deref(), Some("BTCUSDT"));
+
+        let tagged = SourceUrl::parse("Kraken:https://api.kraken.com/0/public/Ticker?pair=XBTUSD");
+        assert_eq!(tagged.provider, Provider::Exchange(Exchange::Kraken));
+        assert_eq!(
+            tagged.url,
+            "https://api.kraken.com/0/public/Ticker?pair=XBTUSD"
+        );
+
+        let stream = SourceUrl::parse("wss://stream.binance.com:9443/ws/btcusdt@trade");
+        assert_eq!(stream.provider, Provider::Exchange(Exchange::Binance));
+        assert_eq!(stream.url, "wss://stream.binance.com:9443/ws/btcusdt@trade");
+
+        let custom = SourceUrl::parse("Gemini:https://api.gemini.com/v1/pubticker/btcusd");
+        assert_eq!(custom.provider, Provider::Json("gemini".into()));
+        assert_eq!(custom.symbol(), None);
+        let custom = SourceUrl::parse("bitstamp:https://example.com/ticker?pair=btc_usd");
+        assert_eq!(custom.symbol().as_deref(), Some("BTCUSD"));
+    }
+
+    #[test]
+    fn test_symbol_mapping_per_exchange() {
+        let cases = [
+            (
+                "coinbase:https://api.exchange.coinbase.com/products/BTC-USD/ticker",
+                "BTCUSD",
+            ),
+            (
+                "kraken:https://api.kraken.com/0/public/Ticker?pair=XBTUSDT",
+                "BTCUSDT",
+            ),
+            (
+                "kraken:https://api.kraken.com/0/public/Ticker?pair=XDGUSD",
+                "DOGEUSD",
+            ),
+            (
+                "okx:https://www.okx.com/api/v5/market/ticker?instId=ETH-USDT",
+                "ETHUSDT",
+            ),
+            (
+                "bybit:https://api.bybit.com/v5/market/tickers?category=spot&symbol=SOLUSDT",
+                "SOLUSDT",
+            ),
+        ];
+        for (url, symbol) in cases {
+            assert_eq!(
+                SourceUrl::parse(url).symbol().as_deref(),
+                Some(symbol),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            SourceUrl::parse("okx:https://www.okx.com/api/v5/market/ticker").symbol(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exchange_tag_roundtrip() {
+        for exchange in Exchange::ALL {
+            assert_eq!(exchange.to_string().parse::<Exchange>(), Ok(exchange));
+        }
+        assert!("ftx".parse::<Exchange>().is_err());
+    }
+}
@@ -0,0 +1,612 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+/// Default indicators reported per symbol when `INDICATORS` is not set.
+pub const DEFAULT_INDICATORS: &str = "rsi(14),macd(12,26,9)";
+
+/// A technical indicator updated with every price of a symbol.
+///
+/// Each `DataProcessor` owns its own instances, built from an [`IndicatorSpec`] at
+/// startup, and publishes their values with the rest of the snapshot. The number
+/// and names of the outputs must not change after construction; an output without
+/// enough data yet (warm-up) should be `f64::NAN`, which is reported as `null`, and
+/// [`Indicator::samples_needed`] tells how long the warm-up still lasts.
+pub trait Indicator: Send {
+    /// Names of the output values, e.g. `["rsi_14"]`; unique within a symbol.
+    fn outputs(&self) -> Vec<String>;
+
+    /// Consumes a new price sampled at `at` (Unix milliseconds).
+    fn update(&mut self, price: f64, at: u64);
+
+    /// Writes the current value of every output, in `outputs` order, into `out`.
+    fn values(&self, out: &mut [f64]);
+
+    /// Prices still needed before every output has a value, `0` once warmed up.
+    ///
+    /// Defaults to `0` for indicators without a warm-up.
+    fn samples_needed(&self) -> u64 {
+        0
+    }
+}
+
+/// Builds an indicator from its numeric parameters, or explains why they are invalid.
+pub type IndicatorFactory = fn(&[f64]) -> Result<Box<dyn Indicator>, String>;
+
+/// An indicator resolved against an [`IndicatorRegistry`], e.g. `roc(10)`.
+#[derive(Clone)]
+pub struct IndicatorSpec {
+    /// Registered name
+    pub name: String,
+    /// Parameters passed to the factory
+    pub params: Vec<f64>,
+    /// Factory the name was registered with
+    factory: IndicatorFactory,
+}
+
+impl IndicatorSpec {
+    /// Builds a fresh instance; the parameters were validated when resolving.
+    pub fn build(&self) -> Box<dyn Indicator> {
+        (self.factory)(&self.params).expect("indicator parameters validated on resolve")
+    }
+}
+
+impl fmt::Debug for IndicatorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl fmt::Display for IndicatorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(f64::to_string).collect();
+        write!(f, "{}({})", self.name, params.join(","))
+    }
+}
+
+impl PartialEq for IndicatorSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.params == other.params
+    }
+}
+
+impl FromStr for IndicatorSpec {
+    type Err = String;
+
+    /// Resolves against the built-in indicators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IndicatorRegistry::default().resolve(s)
+    }
+}
+
+/// Name -> factory of every indicator that can be configured.
+///
+/// `Default` holds the built-in indicators; embedders register their own before
+/// loading the configuration.
+#[derive(Clone)]
+pub struct IndicatorRegistry {
+    factories: HashMap<String, IndicatorFactory>,
+}
+
+impl Default for IndicatorRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            factories: HashMap::new(),
+        };
+        registry.register("roc", RateOfChange::factory);
+        registry.register("rsi", RelativeStrength::factory);
+        registry.register("macd", Macd::factory);
+        registry
+    }
+}
+
+impl IndicatorRegistry {
+    /// Registers `factory` under `name` (case-insensitive), replacing any previous one.
+    pub fn register(&mut self, name: &str, factory: IndicatorFactory) {
+        self.factories.insert(name.to_ascii_lowercase(), factory);
+    }
+
+    /// Resolves `name(p1,p2,...)` (or just `name`) into a spec, checking that the
+    /// indicator exists and accepts the parameters.
+    pub fn resolve(&self, spec: &str) -> Result<IndicatorSpec, String> {
+        let spec = spec.trim();
+        let (name, params) = match spec.split_once('(') {
+            Some((name, rest)) => {
+                let params = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("missing `)` in indicator `{spec}`"))?;
+                let params = params
+                    .split(',')
+                    .filter(|p| !p.trim().is_empty())
+                    .map(|p| p.trim().parse::<f64>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("invalid parameter in indicator `{spec}`: {e}"))?;
+                (name.trim(), params)
+            }
+            None => (spec, Vec::new()),
+        };
+        let name = name.to_ascii_lowercase();
+        let factory = *self
+            .factories
+            .get(&name)
+            .ok_or_else(|| format!("unknown indicator `{name}`"))?;
+        factory(&params).map_err(|e| format!("indicator `{spec}`: {e}"))?;
+        Ok(IndicatorSpec {
+            name,
+            params,
+            factory,
+        })
+    }
+
+    /// Resolves a comma-separated list such as `roc(10),macd(12,26,9)`; commas inside
+    /// parentheses separate parameters.
+    pub fn resolve_list(&self, list: &str) -> Result<Vec<IndicatorSpec>, String> {
+        let mut specs = Vec::new();
+        let (mut depth, mut start) = (0usize, 0);
+        for (i, c) in list.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    specs.push(&list[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        specs.push(&list[start..]);
+        specs
+            .into_iter()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| self.resolve(s))
+            .collect()
+    }
+}
+
+/// Largest sample count [`count_param`] accepts; longer periods would only allocate
+/// buffers that never fill.
+pub const MAX_PERIOD: usize = 100_000;
+
+/// Reads parameter `idx` as a positive sample count of at most [`MAX_PERIOD`].
+pub fn count_param(params: &[f64], idx: usize, name: &str) -> Result<usize, String> {
+    match params.get(idx) {
+        Some(p) if *p > MAX_PERIOD as f64 => {
+            Err(format!("{name} must be at most {MAX_PERIOD}, got {p}"))
+        }
+        Some(p) if *p >= 1.0 && p.fract() == 0.0 => Ok(*p as usize),
+        Some(p) => Err(format!("{name} must be a positive integer, got {p}")),
+        None => Err(format!("missing {name}")),
+    }
+}
+
+/// Rate of change in percent over `n` samples: `(price / price[n ago] - 1) * 100`.
+pub struct RateOfChange {
+    n: usize,
+    /// Last `n + 1` prices, oldest first
+    prices: VecDeque<f64>,
+}
+
+impl RateOfChange {
+    /// `roc(n)`
+    pub fn factory(params: &[f64]) -> Result<Box<dyn Indicator>, String> {
+        let n = count_param(params, 0, "period")?;
+        Ok(Box::new(Self {
+            n,
+            prices: VecDeque::with_capacity(n + 1),
+        }))
+    }
+}
+
+impl Indicator for RateOfChange {
+    fn outputs(&self) -> Vec<String> {
+        vec![format!("roc_{}", self.n)]
+    }
+
+    fn update(&mut self, price: f64, _at: u64) {
+        if self.prices.len() == self.n + 1 {
+            self.prices.pop_front();
+        }
+        self.prices.push_back(price);
+    }
+
+    fn values(&self, out: &mut [f64]) {
+        out[0] = match (self.prices.front(), self.prices.back()) {
+            (Some(old), Some(new)) if self.prices.len() == self.n + 1 => (new / old - 1.0) * 100.0,
+            _ => f64::NAN,
+        };
+    }
+
+    fn samples_needed(&self) -> u64 {
+        (self.n + 1 - self.prices.len()) as u64
+    }
+}
+
+/// Exponential moving average over `period` samples seeded with the simple mean of
+/// the first `period` samples, as charting platforms compute it.
+struct SeededEma {
+    period: usize,
+    /// Samples seen, saturating at `period`
+    count: usize,
+    /// Sum of the samples during warm-up, the average afterwards
+    value: f64,
+}
+
+impl SeededEma {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            count: 0,
+            value: 0.0,
+        }
+    }
+
+    /// Adds `x` and returns the average once `period` samples were seen.
+    fn push(&mut self, x: f64) -> Option<f64> {
+        if self.count < self.period {
+            self.value += x;
+            self.count += 1;
+            if self.count < self.period {
+                return None;
+            }
+            self.value /= self.period as f64;
+        } else {
+            self.value += 2.0 / (self.period as f64 + 1.0) * (x - self.value);
+        }
+        Some(self.value)
+    }
+
+    fn get(&self) -> Option<f64> {
+        (self.count == self.period).then_some(self.value)
+    }
+
+    /// Samples still needed before the average is available.
+    fn remaining(&self) -> usize {
+        self.period - self.count
+    }
+}
+
+/// Relative strength index over `n` price changes with Wilder's smoothing.
+///
+/// The first averages are simple means of the first `n` gains and losses; afterwards
+/// `avg = (avg * (n - 1) + change) / n`. Reports `NaN` until `n + 1` prices were seen.
+pub struct RelativeStrength {
+    n: usize,
+    /// Previous price
+    last: Option<f64>,
+    /// Price changes seen, saturating at `n`
+    changes: usize,
+    /// Average gain (sum during warm-up)
+    gain: f64,
+    /// Average loss as a positive number (sum during warm-up)
+    loss: f64,
+}
+
+impl RelativeStrength {
+    /// `rsi(n)`
+    pub fn factory(params: &[f64]) -> Result<Box<dyn Indicator>, String> {
+        let n = count_param(params, 0, "period")?;
+        Ok(Box::new(Self {
+            n,
+            last: None,
+            changes: 0,
+            gain: 0.0,
+            loss: 0.0,
+        }))
+    }
+}
+
+impl Indicator for RelativeStrength {
+    fn outputs(&self) -> Vec<String> {
+        vec![format!("rsi_{}", self.n)]
+    }
+
+    fn update(&mut self, price: f64, _at: u64) {
+        let Some(last) = self.last.replace(price) else {
+            return;
+        };
+        let change = price - last;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let n = self.n as f64;
+        if self.changes < self.n {
+            self.gain += gain;
+            self.loss += loss;
+            self.changes += 1;
+            if self.changes == self.n {
+                self.gain /= n;
+                self.loss /= n;
+            }
+        } else {
+            self.gain = (self.gain * (n - 1.0) + gain) / n;
+            self.loss = (self.loss * (n - 1.0) + loss) / n;
+        }
+    }
+
+    fn values(&self, out: &mut [f64]) {
+        out[0] = if self.changes < self.n {
+            f64::NAN
+        } else if self.loss == 0.0 {
+            // Only gains is fully overbought, no movement at all is neutral
+            if self.gain == 0.0 { 50.0 } else { 100.0 }
+        } else {
+            100.0 - 100.0 / (1.0 + self.gain / self.loss)
+        };
+    }
+
+    fn samples_needed(&self) -> u64 {
+        // The first price only sets the reference for the first change
+        let first = self.last.is_none() as usize;
+        (self.n - self.changes + first) as u64
+    }
+}
+
+/// Moving average convergence divergence: the `fast` minus the `slow` EMA, its
+/// `signal` EMA and their difference (histogram).
+///
+/// The line is reported once `slow` prices were seen, the signal and histogram
+/// `signal - 1` prices later; every EMA is seeded with a simple mean.
+pub struct Macd {
+    params: (usize, usize, usize),
+    fast: SeededEma,
+    slow: SeededEma,
+    signal: SeededEma,
+}
+
+impl Macd {
+    /// `macd(fast,slow,signal)`
+    pub fn factory(params: &[f64]) -> Result<Box<dyn Indicator>, String> {
+        let fast = count_param(params, 0, "fast period")?;
+        let slow = count_param(params, 1, "slow period")?;
+        let signal = count_param(params, 2, "signal period")?;
+        if fast >= slow {
+            return Err(format!(
+                "fast period {fast} must be shorter than slow period {slow}"
+            ));
+        }
+        Ok(Box::new(Self {
+            params: (fast, slow, signal),
+            fast: SeededEma::new(fast),
+            slow: SeededEma::new(slow),
+            signal: SeededEma::new(signal),
+        }))
+    }
+}
+
+impl Indicator for Macd {
+    fn outputs(&self) -> Vec<String> {
+        let (fast, slow, signal) = self.params;
+        ["macd", "macd_signal", "macd_hist"]
+            .iter()
+            .map(|name| format!("{name}_{fast}_{slow}_{signal}"))
+            .collect()
+    }
+
+    fn update(&mut self, price: f64, _at: u64) {
+        let fast = self.fast.push(price);
+        if let (Some(fast), Some(slow)) = (fast, self.slow.push(price)) {
+            self.signal.push(fast - slow);
+        }
+    }
+
+    fn values(&self, out: &mut [f64]) {
+        let line = match (self.fast.get(), self.slow.get()) {
+            (Some(fast), Some(slow)) => fast - slow,
+            _ => f64::NAN,
+        };
+        let signal = self.signal.get().unwrap_or(f64::NAN);
+        out[0] = line;
+        out[1] = signal;
+        out[2] = line - signal;
+    }
+
+    fn samples_needed(&self) -> u64 {
+        // The signal gets its first value with the price that completes the slow EMA
+        match self.signal.count {
+            0 => (self.slow.remaining() + self.signal.remaining() - 1) as u64,
+            _ => self.signal.remaining() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod indicator_tests {
+    use super::*;
+
+    /// Custom indicator as a team would add it: counts prices above a threshold.
+    struct AboveCount {
+        threshold: f64,
+        count: u64,
+    }
+
+    impl Indicator for AboveCount {
+        fn outputs(&self) -> Vec<String> {
+            vec!["above".into(), "above_threshold".into()]
+        }
+
+        fn update(&mut self, price: f64, _at: u64) {
+            self.count += (price > self.threshold) as u64;
+        }
+
+        fn values(&self, out: &mut [f64]) {
+            out[0] = self.count as f64;
+            out[1] = self.threshold;
+        }
+    }
+
+    fn above(params: &[f64]) -> Result<Box<dyn Indicator>, String> {
+        let threshold = *params.first().ok_or("missing threshold")?;
+        Ok(Box::new(AboveCount {
+            threshold,
+            count: 0,
+        }))
+    }
+
+    #[test]
+    fn test_resolve_parses_names_and_params() {
+        let registry = IndicatorRegistry::default();
+        let spec = registry.resolve(" ROC( 10 ) ").unwrap();
+        assert_eq!(
+            (spec.name.as_str(), spec.params.as_slice()),
+            ("roc", &[10.0][..])
+        );
+        assert_eq!(spec.to_string(), "roc(10)");
+        assert_eq!(spec, "roc(10)".parse().unwrap());
+
+        assert!(registry.resolve("roc").is_err());
+        assert!(registry.resolve("roc(0)").is_err());
+        assert!(registry.resolve("roc(2.5)").is_err());
+        assert!(registry.resolve("roc(100000)").is_ok());
+        assert!(registry.resolve("roc(100001)").is_err());
+        assert!(registry.resolve("rsi(1e30)").is_err());
+        assert!(registry.resolve("macd(12,1e30,9)").is_err());
+        assert!(registry.resolve("roc(10").is_err());
+        assert!(registry.resolve("nope(1)").is_err());
+    }
+
+    #[test]
+    fn test_resolve_list_splits_outside_parentheses() {
+        let mut registry = IndicatorRegistry::default();
+        registry.register("above", above);
+        let specs = registry.resolve_list("roc(3), above(100),,roc(5)").unwrap();
+        let names: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+        assert_eq!(names, ["roc(3)", "above(100)", "roc(5)"]);
+        assert!(registry.resolve_list("roc(3),macd(1,2)").is_err());
+        assert!(registry.resolve_list("roc(3),macd(26,12,9)").is_err());
+        assert!(registry.resolve_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rate_of_change() {
+        let mut roc = "roc(2)".parse::<IndicatorSpec>().unwrap().build();
+        assert_eq!(roc.outputs(), ["roc_2"]);
+        let mut out = [0.0];
+        for (price, expected) in [(100.0, None), (105.0, None), (110.0, Some(10.0))] {
+            roc.update(price, 0);
+            roc.values(&mut out);
+            match expected {
+                Some(v) => assert!((out[0] - v).abs() < 1e-12),
+                None => assert!(out[0].is_nan()),
+            }
+        }
+        roc.update(99.0, 0);
+        roc.values(&mut out);
+        assert!((out[0] - (99.0 / 105.0 - 1.0) * 100.0).abs() < 1e-12);
+    }
+
+    /// Reference RSI: simple averages of the first `n` changes, then Wilder smoothing.
+    fn reference_rsi(prices: &[f64], n: usize) -> f64 {
+        let changes: Vec<f64> = prices.windows(2).map(|w| w[1] - w[0]).collect();
+        let n_f = n as f64;
+        let mut gain = changes[..n].iter().map(|c| c.max(0.0)).sum::<f64>() / n_f;
+        let mut loss = changes[..n].iter().map(|c| (-c).max(0.0)).sum::<f64>() / n_f;
+        for c in &changes[n..] {
+            gain = (gain * (n_f - 1.0) + c.max(0.0)) / n_f;
+            loss = (loss * (n_f - 1.0) + (-c).max(0.0)) / n_f;
+        }
+        100.0 - 100.0 / (1.0 + gain / loss)
+    }
+
+    /// Reference EMA seeded with the mean of the first `n` values, `None` if too short.
+    fn reference_ema(values: &[f64], n: usize) -> Option<Vec<f64>> {
+        let seed = values.get(..n)?.iter().sum::<f64>() / n as f64;
+        let alpha = 2.0 / (n as f64 + 1.0);
+        let mut out = vec![seed];
+        for x in &values[n..] {
+            let last = *out.last().unwrap();
+            out.push(last + alpha * (x - last));
+        }
+        Some(out)
+    }
+
+    fn prices(len: usize) -> Vec<f64> {
+        let mut state = 0xDEAD_BEEF_u64;
+        (0..len)
+            .map(|_| 100.0 + (crate::utils::xorshift64(&mut state) % 1_000) as f64 / 100.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_rsi_matches_reference_after_warm_up() {
+        let mut rsi = "rsi(14)".parse::<IndicatorSpec>().unwrap().build();
+        assert_eq!(rsi.outputs(), ["rsi_14"]);
+        let prices = prices(200);
+        let mut out = [0.0];
+        for (i, price) in prices.iter().enumerate() {
+            rsi.update(*price, 0);
+            rsi.values(&mut out);
+            if i < 14 {
+                assert!(out[0].is_nan(), "warm-up at {i}");
+            } else {
+                let expected = reference_rsi(&prices[..=i], 14);
+                assert!((out[0] - expected).abs() < 1e-9, "{} vs {expected}", out[0]);
+                assert!((0.0..=100.0).contains(&out[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rsi_one_sided_moves() {
+        let mut out = [0.0];
+        let mut rising = "rsi(3)".parse::<IndicatorSpec>().unwrap().build();
+        let mut flat = "rsi(3)".parse::<IndicatorSpec>().unwrap().build();
+        for i in 0..5 {
+            rising.update(i as f64, 0);
+            flat.update(7.0, 0);
+        }
+        rising.values(&mut out);
+        assert_eq!(out[0], 100.0);
+        flat.values(&mut out);
+        assert_eq!(out[0], 50.0);
+    }
+
+    #[test]
+    fn test_macd_matches_reference_after_warm_up() {
+        let mut macd = "macd(3,6,4)".parse::<IndicatorSpec>().unwrap().build();
+        assert_eq!(
+            macd.outputs(),
+            ["macd_3_6_4", "macd_signal_3_6_4", "macd_hist_3_6_4"]
+        );
+        let prices = prices(100);
+        let mut out = [0.0; 3];
+        for (i, price) in prices.iter().enumerate() {
+            macd.update(*price, 0);
+            macd.values(&mut out);
+            let seen = &prices[..=i];
+            let (Some(fast), Some(slow)) = (reference_ema(seen, 3), reference_ema(seen, 6)) else {
+                assert!(out.iter().all(|v| v.is_nan()), "warm-up at {i}");
+                continue;
+            };
+            // Align both EMAs on the prices where the slow one exists
+            let line: Vec<f64> = fast[3..].iter().zip(&slow).map(|(f, s)| f - s).collect();
+            assert!((out[0] - line.last().unwrap()).abs() < 1e-9);
+            match reference_ema(&line, 4) {
+                Some(signal) => {
+                    let signal = *signal.last().unwrap();
+                    assert!((out[1] - signal).abs() < 1e-9);
+                    assert!((out[2] - (out[0] - signal)).abs() < 1e-9);
+                }
+                None => assert!(out[1].is_nan() && out[2].is_nan()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_samples_needed_counts_down_to_first_values() {
+        let mut out = [0.0; 3];
+        for spec in ["roc(4)", "rsi(5)", "macd(3,6,4)"] {
+            let mut indicator = spec.parse::<IndicatorSpec>().unwrap().build();
+            let width = indicator.outputs().len();
+            let mut needed = indicator.samples_needed();
+            assert!(needed > 0, "{spec}");
+            for price in prices(20) {
+                indicator.update(price, 0);
+                indicator.values(&mut out[..width]);
+                let now = indicator.samples_needed();
+                assert_eq!(now, needed.saturating_sub(1), "{spec}");
+                assert_eq!(now == 0, out[..width].iter().all(|v| !v.is_nan()), "{spec}");
+                needed = now;
+            }
+        }
+    }
+}
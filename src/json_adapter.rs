@@ -0,0 +1,351 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use reqwest::Client;
+use serde_json::Value;
+use tokio::time::{Interval, MissedTickBehavior, interval};
+
+use crate::{
+    config::AppConfig,
+    dto::GetPrice,
+    exchange::normalize_pair,
+    source::{PriceSource, PriceUpdate, SourceError, SourceFuture},
+    utils::retune_ticker,
+};
+
+/// Timestamps below this are taken as Unix seconds rather than milliseconds
+/// (`1e11` ms is March 1973, `1e11` s is far in the future).
+const SECONDS_THRESHOLD: f64 = 1e11;
+
+/// Where to find the price, symbol and timestamp in an arbitrary JSON response.
+///
+/// Configured per provider as `price=<path>;symbol=<path>;time=<path>`, where only
+/// `price` is required. A path is either a JSON pointer (`/data/0/last`) or the
+/// dotted form (`data.0.last`). Values may be JSON numbers or numeric strings;
+/// timestamps are Unix milliseconds, or seconds if they are too small to be
+/// milliseconds.
+///
+/// # Example
+/// ```
+/// use aboss_task::json_adapter::JsonAdapter;
+/// let adapter: JsonAdapter = "price=data.0.last;symbol=/data/0/instId".parse().unwrap();
+/// let json = serde_json::json!({"data": [{"instId": "BTC-USDT", "last": "117454.3"}]});
+/// let price = adapter.extract(&json).unwrap();
+/// assert_eq!(price.price, 117454.3);
+/// assert_eq!(price.symbol.as_deref(), Some("BTCUSDT"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonAdapter {
+    /// JSON pointer of the price
+    pub price: String,
+    /// JSON pointer of the symbol, if the response carries one
+    pub symbol: Option<String>,
+    /// JSON pointer of the event time, if the response carries one
+    pub time: Option<String>,
+}
+
+/// Price extracted by a [`JsonAdapter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPrice {
+    /// Normalised symbol, if the adapter has a symbol path
+    pub symbol: Option<String>,
+    pub price: f64,
+    /// Event time in Unix milliseconds, if the adapter has a time path
+    pub event_time: Option<u64>,
+}
+
+impl GetPrice for JsonPrice {
+    fn get_price(&self) -> f64 {
+        self.price
+    }
+
+    fn get_event_time(&self) -> Option<u64> {
+        self.event_time
+    }
+}
+
+impl FromStr for JsonAdapter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut price = None;
+        let mut symbol = None;
+        let mut time = None;
+        for field in s.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+            let (key, path) = field
+                .split_once('=')
+                .ok_or_else(|| format!("expected `key=path`, got `{field}`"))?;
+            let path = Some(to_pointer(path.trim()));
+            match key.trim() {
+                "price" => price = path,
+                "symbol" => symbol = path,
+                "time" | "timestamp" => time = path,
+                other => return Err(format!("unknown adapter field `{other}`")),
+            }
+        }
+        Ok(Self {
+            price: price.ok_or("missing `price` path")?,
+            symbol,
+            time,
+        })
+    }
+}
+
+impl JsonAdapter {
+    /// Extracts the price, and the symbol and event time if configured, from `value`.
+    ///
+    /// # Returns
+    /// An error naming the path that is missing or not numeric.
+    pub fn extract(&self, value: &Value) -> Result<JsonPrice, String> {
+        let price = value
+            .pointer(&self.price)
+            .and_then(json_f64)
+            .ok_or_else(|| format!("no numeric price at `{}`", self.price))?;
+
+        let symbol = match &self.symbol {
+            Some(path) => Some(
+                value
+                    .pointer(path)
+                    .and_then(Value::as_str)
+                    .and_then(normalize_pair)
+                    .ok_or_else(|| format!("no symbol at `{path}`"))?,
+            ),
+            None => None,
+        };
+
+        let event_time = match &self.time {
+            Some(path) => Some(
+                value
+                    .pointer(path)
+                    .and_then(json_f64)
+                    .map(|t| if t < SECONDS_THRESHOLD { t * 1000.0 } else { t } as u64)
+                    .ok_or_else(|| format!("no timestamp at `{path}`"))?,
+            ),
+            None => None,
+        };
+
+        Ok(JsonPrice {
+            symbol,
+            price,
+            event_time,
+        })
+    }
+}
+
+/// Converts a dotted path (`data.0.last`) to a JSON pointer (`/data/0/last`).
+///
+/// Pointers and the empty (whole document) path are returned unchanged.
+fn to_pointer(path: &str) -> String {
+    if path.is_empty() || path.starts_with('/') {
+        return path.to_string();
+    }
+    path.split('.')
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Reads a JSON number or numeric string, as `de_str_to_f64` does for string prices.
+fn json_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+/// Polls a REST endpoint and reads the price with a [`JsonAdapter`].
+///
+/// Lets ops add providers through configuration alone. The symbol comes from the
+/// adapter's symbol path when it has one, otherwise from the URL; if the URL
+/// names no symbol it is learned from the first response.
+pub struct JsonPoller {
+    /// Full path to query, including query parameters.
+    url: String,
+
+    /// Symbol the endpoint reports, empty until learned from a response.
+    symbols: Vec<String>,
+
+    /// How to read the response.
+    adapter: Arc<JsonAdapter>,
+
+    /// Reqwest client used for HTTP requests.
+    client_manager: Client,
+
+    /// Paces the requests; the first tick completes immediately.
+    ticker: Interval,
+}
+
+impl JsonPoller {
+    /// Constructs a new `JsonPoller`.
+    ///
+    /// # Parameters
+    /// - `interval_duration`: Duration between HTTP requests.
+    /// - `url`: URL of the endpoint.
+    /// - `symbol`: Symbol named by the URL, if any.
+    /// - `adapter`: How to read the response.
+    /// - `client_manager`: Reqwest client to perform requests.
+    pub fn new(
+        interval_duration: Duration,
+        url: String,
+        symbol: Option<String>,
+        adapter: Arc<JsonAdapter>,
+        client_manager: Client,
+    ) -> Self {
+        let mut ticker = interval(interval_duration);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            url,
+            symbols: symbol.into_iter().collect(),
+            adapter,
+            client_manager,
+            ticker,
+        }
+    }
+}
+
+impl PriceSource for JsonPoller {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    fn expects_all_symbols(&self) -> bool {
+        true
+    }
+
+    fn next_prices(&mut self) -> SourceFuture<'_> {
+        Box::pin(async move {
+            self.ticker.tick().await;
+            let res = self.client_manager.get(&self.url).send().await?;
+            let value = res.json::<Value>().await?;
+            let price = self.adapter.extract(&value).map_err(SourceError::Decode)?;
+
+            let symbol = match (&price.symbol, self.symbols.first()) {
+                (Some(symbol), _) => symbol.clone(),
+                (None, Some(symbol)) => symbol.clone(),
+                (None, None) => {
+                    return Err(SourceError::Decode(
+                        "no symbol in URL or response".to_string(),
+                    ));
+                }
+            };
+            if self.symbols.is_empty() {
+                self.symbols.push(symbol.clone());
+            }
+            Ok(vec![PriceUpdate::new(
+                symbol,
+                price.get_price(),
+                price.get_event_time(),
+            )])
+        })
+    }
+
+    fn reconfigure(&mut self, config: &AppConfig) {
+        let period = self
+            .symbols
+            .first()
+            .map_or(config.interval, |s| config.interval_for(s));
+        retune_ticker(&mut self.ticker, period);
+        self.client_manager = config.client.clone();
+    }
+}
+
+#[cfg(test)]
+mod json_adapter_tests {
+    use super::*;
+    use crate::rpc_manager::RpcManager;
+    use serde_json::json;
+    use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+    #[test]
+    fn test_parse_config() {
+        let adapter: JsonAdapter = " price=result.list.0.lastPrice ; time=/time "
+            .parse()
+            .unwrap();
+        assert_eq!(adapter.price, "/result/list/0/lastPrice");
+        assert_eq!(adapter.time.as_deref(), Some("/time"));
+        assert_eq!(adapter.symbol, None);
+
+        assert!("symbol=/s".parse::<JsonAdapter>().is_err());
+        assert!("price=/p;volume=/v".parse::<JsonAdapter>().is_err());
+        assert!("price".parse::<JsonAdapter>().is_err());
+    }
+
+    #[test]
+    fn test_extract_string_and_numeric_values() {
+        let adapter: JsonAdapter = "price=/last;symbol=/pair;time=/ts".parse().unwrap();
+
+        let price = adapter
+            .extract(&json!({"last": "101.25", "pair": "eth_usdt", "ts": "1754900071000"}))
+            .unwrap();
+        assert_eq!(price.price, 101.25);
+        assert_eq!(price.symbol.as_deref(), Some("ETHUSDT"));
+        assert_eq!(price.event_time, Some(1754900071000));
+
+        // Numbers, and a timestamp in seconds
+        let price = adapter
+            .extract(&json!({"last": 99.5, "pair": "ETH-USDT", "ts": 1754900071.5}))
+            .unwrap();
+        assert_eq!(price.price, 99.5);
+        assert_eq!(price.event_time, Some(1754900071500));
+    }
+
+    #[test]
+    fn test_extract_reports_missing_paths() {
+        let adapter: JsonAdapter = "price=/data/0/last;symbol=/data/0/id".parse().unwrap();
+        let err = adapter.extract(&json!({"data": []})).unwrap_err();
+        assert!(err.contains("/data/0/last"));
+
+        let err = adapter
+            .extract(&json!({"data": [{"last": "abc"}]}))
+            .unwrap_err();
+        assert!(err.contains("price"));
+
+        let err = adapter
+            .extract(&json!({"data": [{"last": "1.0"}]}))
+            .unwrap_err();
+        assert!(err.contains("symbol"));
+    }
+
+    /// Serves `body` as a JSON HTTP response to every connection.
+    async fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut tcp, _)) = listener.accept().await {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                tcp.write_all(response.as_bytes()).await.ok();
+            }
+        });
+        format!("http://{addr}/v1/ticker")
+    }
+
+    #[tokio::test]
+    async fn test_poller_learns_symbol_from_response() {
+        let url = serve(r#"{"ticker":{"market":"SOL/USD","last":"188.42","at":1754900071}}"#).await;
+        let adapter = "price=ticker.last;symbol=ticker.market;time=ticker.at"
+            .parse()
+            .unwrap();
+        let mut poller = JsonPoller::new(
+            Duration::from_millis(10),
+            url,
+            None,
+            Arc::new(adapter),
+            Client::new(),
+        );
+        assert!(poller.symbols().is_empty());
+
+        let seeded = RpcManager::seed(&mut poller).await.unwrap();
+        assert_eq!(seeded["SOLUSD"], 188.42);
+        assert_eq!(poller.symbols(), ["SOLUSD"]);
+
+        let updates = poller.next_prices().await.unwrap();
+        assert_eq!(updates[0].event_time, Some(1754900071000));
+    }
+}
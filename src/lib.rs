@@ -1,7 +1,24 @@
+pub mod admin;
+pub mod batch_manager;
+pub mod candles;
+pub mod circuit_breaker;
 pub mod config;
+pub mod config_file;
+pub mod consensus;
 pub mod data_processor;
+pub mod decimal;
 pub mod dto;
+pub mod exchange;
+pub mod indicator;
+pub mod json_adapter;
 pub mod models;
+pub mod quantile;
+pub mod reload;
+pub mod retry;
 pub mod routes;
 pub mod rpc_manager;
+pub mod seqlock;
+pub mod source;
 pub mod utils;
+pub mod window;
+pub mod ws_manager;
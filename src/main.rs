@@ -1,29 +1,40 @@
 use aboss_task::{
-    data_processor::DataProcessor,
-    dto::{BinancePrice, GetPrice},
-    models::MapData,
+    admin::SymbolAdmin,
+    config::AppConfig,
+    indicator::IndicatorRegistry,
+    models::{ConsensusData, HealthData, MapData, ReadyData, SharedMap},
+    reload::spawn_reloader,
     routes,
     rpc_manager::RpcManager,
-    utils::extract_symbol,
+    source::build_sources,
 };
 use actix_web::{App, HttpServer};
-use std::{collections::HashMap, sync::Arc};
-use tokio::spawn;
-use tracing::info;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{info, warn};
 
-mod config;
-use config::AppConfig;
 /// Entry point for the `aboss_task` service.
 ///
 /// # Overview
 ///
 /// This main function does the following:
 /// 1. Initializes logging using `tracing_subscriber`.
-/// 2. Loads configuration from environment variables (`AppConfig`).
-/// 3. Extracts symbols from the list of URLs to monitor.
-/// 4. Initializes a `DataProcessor` per symbol for tracking streaming statistics.
-/// 5. Spawns a `RpcManager` task for each URL to fetch data periodically.
-/// 6. Starts an `actix_web` HTTP server exposing `/health` and `/stats` endpoints.
+/// 2. Loads and validates configuration from environment variables (`AppConfig`),
+///    exiting with every invalid setting listed.
+/// 3. Builds a `PriceSource` per configured URL (`build_sources`).
+/// 4. Seeds a `DataProcessor` per symbol for tracking streaming statistics; symbols
+///    fed by several sources get a `ConsensusBook` combining their quotes first.
+/// 5. Spawns a `RpcManager` task per source that keeps pulling prices from it.
+/// 6. Starts an `actix_web` HTTP server exposing `/health`, `/ready`, `/stats`,
+///    `/candles`, `/consensus` and the `/admin/symbols` endpoints.
+///
+/// The `SymbolAdmin` owns the current configuration: reloads (SIGHUP, config file
+/// changes or `/admin/reload`) add and remove sources and push new intervals,
+/// timeouts, retry, breaker and consensus settings into the running `RpcManager`s;
+/// `/ready` and `/health` read the current configuration on every request.
+///
+/// Each `RpcManager` reports its retry and circuit breaker state into a shared
+/// `SourceStatus` per symbol, which `/health` aggregates into a per-symbol report.
 ///
 /// # Async Execution
 ///
@@ -33,9 +44,10 @@ use config::AppConfig;
 ///
 /// # Shared State
 ///
-/// All `DataProcessorReader`s are stored in a `HashMap<String, DataProcessorReader>`
-/// wrapped in an `Arc` and exposed to `actix_web` using `web::Data<MapData>`. This
-/// makes the statistics **read-only** and shareable across all web handlers.
+/// All `DataProcessorReader`s are stored in a copy-on-write `SharedMap` wrapped in an
+/// `Arc` and exposed to `actix_web` using `web::Data<MapData>`. Handlers read a
+/// snapshot of the map without locking out the `SymbolAdmin`, which adds and removes
+/// symbols at runtime.
 ///
 /// # Server Bindings
 ///
@@ -47,56 +59,89 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    // Load configuration (URLs, interval, SMA size, IP, port)
-    let config = AppConfig::from_env()?;
+    // Load and validate configuration (URLs, interval, SMA size, IP, port), reporting
+    // every invalid setting before exiting; reloads resolve indicators against the
+    // same registry
+    let registry = IndicatorRegistry::default();
+    let config = match AppConfig::from_env_with(&registry) {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            tracing::error!("Invalid configuration: {e}");
+            std::process::exit(1);
+        }
+    };
 
     // Log parsed configuration
     tracing::info!("Parsed URLs: {:?}", config.urls);
-    tracing::info!("Interval: {:?}, SMA_N: {}", config.interval, config.sma_n);
+    tracing::info!(
+        "Interval: {:?}, SMA_N: {}, EMA: {:?}, QUANTILES: {:?}, INDICATORS: {:?}, WINDOWS: {:?}, CANDLES: {:?}",
+        config.interval,
+        config.stats.sma_n,
+        config.stats.emas,
+        config.stats.quantiles,
+        config.stats.indicators,
+        config.stats.windows,
+        config.stats.candles
+    );
     tracing::info!("IP: {}, PORT: {}", config.ip, config.port);
-
-    // Initialize map of symbol -> DataProcessorReader
-    let mut map = HashMap::new();
-    let symbols: Vec<String> = config
-        .urls
-        .iter()
-        .filter_map(|url| extract_symbol(url))
-        .collect();
-
-    // Spawn a `RpcManager` for each URL
-    for (idx, url) in config.urls.into_iter().enumerate() {
-        // Fetch initial data from remote endpoint
-        let initial_data = RpcManager::<BinancePrice>::send_reqwest(&config.client, &url).await?;
-        let client = config.client.clone();
-
-        // Split a DataProcessor into a reader and writer
-        let (reader, writer) = DataProcessor::split(config.sma_n, initial_data.get_price());
-
-        // Insert reader into shared map
-        map.insert(symbols[idx].clone(), reader);
-
-        let interval = config.interval;
-        // Spawn async task to continuously fetch and process prices
-        spawn(async move {
-            let rpc_manager = RpcManager::<BinancePrice>::new(interval, &url, client, writer);
-            rpc_manager.init_run().await; // runs infinitely
-        });
+    if config.admin_token.is_none() {
+        warn!("ADMIN_TOKEN is not set, the /admin endpoints are disabled");
     }
 
+    // Seed and spawn a `RpcManager` for each source; symbols fed by several
+    // sources are combined into a consensus price. Every manager follows the
+    // intervals and timeout of reloaded configurations
+    let (config_updates, _) = watch::channel(config.clone());
+    let tracked = RpcManager::spawn_all(
+        build_sources(&config),
+        &config.stats,
+        config.retry_policy,
+        config.breaker_policy,
+        config.consensus_policy,
+        Some(config_updates.subscribe()),
+    )
+    .await?;
+
     info!("STARTING SERVER");
 
-    // Wrap the map in Arc and Data for actix-web shareable state
+    // `/ready` requires every tracked symbol unless told otherwise; it and `/health`
+    // follow reloaded configurations
+    let ready_data = actix_web::web::Data::new(ReadyData {
+        config: config_updates.subscribe(),
+    });
+
+    // Wrap the maps in Arc and Data for actix-web shareable state
+    let readers = Arc::new(SharedMap::new(tracked.readers));
+    let statuses = Arc::new(SharedMap::new(tracked.statuses));
     let map_data = actix_web::web::Data::new(MapData {
-        data: Arc::new(map),
+        data: readers.clone(),
+    });
+    let health_data = actix_web::web::Data::new(HealthData {
+        sources: statuses.clone(),
+        config: config_updates.subscribe(),
+    });
+    let admin = Arc::new(
+        SymbolAdmin::new(config_updates, readers, statuses, tracked.tasks).with_registry(registry),
+    );
+
+    // Reload the configuration on SIGHUP and when the config file changes
+    spawn_reloader(admin.clone());
+    let admin_data = actix_web::web::Data::from(admin);
+    let consensus_data = actix_web::web::Data::new(ConsensusData {
+        books: Arc::new(tracked.books),
     });
 
     // Start HTTP server
     HttpServer::new(move || {
         App::new()
             .app_data(map_data.clone())
+            .app_data(health_data.clone())
+            .app_data(ready_data.clone())
+            .app_data(consensus_data.clone())
+            .app_data(admin_data.clone())
             .configure(routes::init)
     })
-    .bind((config.ip, config.port))?
+    .bind((config.ip.clone(), config.port))?
     .run()
     .await?;
 
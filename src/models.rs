@@ -1,16 +1,70 @@
-use crate::data_processor::DataProcessorReader;
+use crate::{
+    circuit_breaker::BreakerState, config::ConfigUpdates, consensus::ConsensusBook,
+    data_processor::DataProcessorReader, utils::now_millis,
+};
 use serde::Deserialize;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering::Relaxed},
+    },
+    time::Duration,
+};
 
-/// A shared, thread-safe, **read-only** mapping from string keys (symbols) to data processors.
+/// Copy-on-write map from symbols to `V` that can be updated while it is read.
 ///
-/// This map is intended to be **read-only**; modifications are not supported after creation.
-/// It can be safely shared between multiple threads and is suitable for use with
-/// `actix_web::web::Data` for storing symbolstats reader.
+/// Readers clone the `Arc` of the current map, holding the lock only for that
+/// pointer copy, and keep using their snapshot while it is being replaced. Updates
+/// build a new map and swap it in; they are rare (admin requests), so copying the
+/// map each time is cheaper than slowing down every read.
+pub struct SharedMap<V> {
+    /// Current map
+    current: RwLock<Arc<HashMap<String, V>>>,
+    /// Serializes updates so that concurrent ones are not lost
+    update: Mutex<()>,
+}
+
+impl<V: Clone> SharedMap<V> {
+    /// Creates the map with `entries`.
+    pub fn new(entries: HashMap<String, V>) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(entries)),
+            update: Mutex::new(()),
+        }
+    }
+
+    /// Snapshot of the current entries.
+    pub fn load(&self) -> Arc<HashMap<String, V>> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The current value of `key`, if any.
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.load().get(key).cloned()
+    }
+
+    /// Applies `f` to a copy of the entries and publishes the result.
+    pub fn update<R>(&self, f: impl FnOnce(&mut HashMap<String, V>) -> R) -> R {
+        let _guard = self.update.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = HashMap::clone(&self.load());
+        let result = f(&mut entries);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(entries);
+        result
+    }
+}
+
+/// Readers of every tracked symbol, shared with the HTTP handlers.
+///
+/// Symbols can be added and removed at runtime through the admin API; handlers
+/// always read a consistent snapshot of the map without waiting for updates.
 #[derive(Clone)]
 pub struct MapData {
-    /// The underlying map wrapped in an `Arc` for shared ownership across threads.
-    pub data: Arc<HashMap<String, DataProcessorReader>>,
+    /// Symbol -> reader of its statistics
+    pub data: Arc<SharedMap<DataProcessorReader>>,
 }
 
 /// Structure representing a query request for a specific symbol.
@@ -19,9 +73,157 @@ pub struct MapData {
 pub struct QuerryData {
     /// The symbol to query (e.g., "BTCUSDT").
     pub symbol: String,
+    /// Optional time window (e.g., "5m") restricting `/stats` to that window.
+    pub window: Option<String>,
+}
+
+/// Query of `/candles`, mirroring Binance's `klines` parameters.
+#[derive(Debug, Deserialize)]
+pub struct CandleQuery {
+    /// The symbol to query (e.g., "BTCUSDT").
+    pub symbol: String,
+    /// Candle resolution (e.g., "1m").
+    pub interval: String,
+    /// Maximum number of candles returned, latest last.
+    pub limit: Option<usize>,
+}
+
+/// Body of `POST /admin/symbols`.
+#[derive(Debug, Deserialize)]
+pub struct AddSymbolRequest {
+    /// Source to track, in the format of an entry of `URLS`
+    /// (e.g. `okx:https://www.okx.com/api/v5/market/ticker?instId=SOL-USDT`).
+    pub url: String,
 }
 
-// Safety: `MapData` can be safely sent and shared across threads because
-// `Arc<HashMap<...>>` is inherently thread-safe for read-only access.
-unsafe impl Send for MapData {}
-unsafe impl Sync for MapData {}
+/// Consensus books of the symbols fed by several sources, shared with the
+/// `/consensus` handlers.
+#[derive(Clone)]
+pub struct ConsensusData {
+    /// Symbol -> its consensus book
+    pub books: Arc<HashMap<String, Arc<ConsensusBook>>>,
+}
+
+/// Configuration of the `/ready` handler, shared with it.
+#[derive(Clone)]
+pub struct ReadyData {
+    /// Current configuration, whose `ready_symbols` must be fresh (every currently
+    /// tracked symbol when empty)
+    pub config: ConfigUpdates,
+}
+
+/// Health of every source, shared with the `/health` handler.
+#[derive(Clone)]
+pub struct HealthData {
+    /// Symbol (`<symbol>@<source>` for combined symbols) -> status of the source feeding it
+    pub sources: Arc<SharedMap<Arc<SourceStatus>>>,
+    /// Current configuration, whose `unhealthy_fraction` of unhealthy sources makes
+    /// `/health` stop returning 200
+    pub config: ConfigUpdates,
+}
+
+/// Retry and circuit breaker state of a single source, shared between its
+/// `RpcManager` and the HTTP layer.
+///
+/// Fields are updated independently with relaxed atomics; they are meant for
+/// reporting, not for synchronising other data.
+#[derive(Debug, Default)]
+pub struct SourceStatus {
+    /// Consecutive failed fetches since the last success
+    failure_streak: AtomicU64,
+    /// Unix time in milliseconds of the next retry, `0` when not backing off
+    next_retry_at: AtomicU64,
+    /// Set once the failure streak reaches the policy threshold
+    degraded: AtomicBool,
+    /// `BreakerState` as `u8`
+    breaker_state: AtomicU8,
+    /// Unix time in milliseconds of the last successful fetch, `0` if none yet
+    last_success_at: AtomicU64,
+    /// Unix time in milliseconds of the last failed fetch, `0` if none yet
+    last_error_at: AtomicU64,
+    /// Message of the last failed fetch; only touched on the error path
+    last_error: Mutex<Option<String>>,
+}
+
+impl SourceStatus {
+    /// Records a successful fetch, clearing the failure streak.
+    pub fn record_success(&self) {
+        self.failure_streak.store(0, Relaxed);
+        self.next_retry_at.store(0, Relaxed);
+        self.degraded.store(false, Relaxed);
+        self.last_success_at.store(now_millis(), Relaxed);
+    }
+
+    /// Records a failed fetch that will be retried after `retry_in`.
+    pub fn record_failure(
+        &self,
+        failure_streak: u64,
+        retry_in: Duration,
+        degraded: bool,
+        error: String,
+    ) {
+        let now = now_millis();
+        let retry_at = now.saturating_add(retry_in.as_millis() as u64);
+        self.failure_streak.store(failure_streak, Relaxed);
+        self.next_retry_at.store(retry_at, Relaxed);
+        self.degraded.store(degraded, Relaxed);
+        self.last_error_at.store(now, Relaxed);
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(error);
+        }
+    }
+
+    /// Mirrors the state of the source's circuit breaker.
+    pub fn set_breaker_state(&self, state: BreakerState) {
+        self.breaker_state.store(state as u8, Relaxed);
+    }
+
+    /// Last known circuit breaker state.
+    pub fn breaker_state(&self) -> BreakerState {
+        BreakerState::from_u8(self.breaker_state.load(Relaxed))
+    }
+
+    /// Unix time in milliseconds of the last successful fetch.
+    pub fn last_success_at(&self) -> Option<u64> {
+        match self.last_success_at.load(Relaxed) {
+            0 => None,
+            at => Some(at),
+        }
+    }
+
+    /// Unix time in milliseconds of the last failed fetch.
+    pub fn last_error_at(&self) -> Option<u64> {
+        match self.last_error_at.load(Relaxed) {
+            0 => None,
+            at => Some(at),
+        }
+    }
+
+    /// Message of the last failed fetch.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+
+    /// A source is healthy while its breaker is closed and it is not degraded.
+    pub fn is_healthy(&self) -> bool {
+        self.breaker_state() == BreakerState::Closed && !self.is_degraded()
+    }
+
+    /// Consecutive failed fetches since the last success.
+    pub fn failure_streak(&self) -> u64 {
+        self.failure_streak.load(Relaxed)
+    }
+
+    /// Unix time in milliseconds of the next retry, if the source is backing off.
+    pub fn next_retry_at(&self) -> Option<u64> {
+        match self.next_retry_at.load(Relaxed) {
+            0 => None,
+            at => Some(at),
+        }
+    }
+
+    /// Returns `true` once the source has failed too many times in a row.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Relaxed)
+    }
+}
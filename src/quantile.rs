@@ -0,0 +1,302 @@
+use std::{fmt, str::FromStr};
+
+/// Default quantiles reported per symbol when `QUANTILES` is not set.
+pub const DEFAULT_QUANTILES: &str = "0.05,0.5,0.95";
+
+/// A quantile to report, e.g. `0.95`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantileSpec(f64);
+
+impl QuantileSpec {
+    /// Quantile `q`, which must lie in `(0, 1)`.
+    pub fn new(q: f64) -> Self {
+        assert!(q > 0.0 && q < 1.0, "quantile must be in (0, 1)");
+        Self(q)
+    }
+
+    /// The quantile as a fraction in `(0, 1)`.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for QuantileSpec {
+    /// Name of the quantile in responses: `p5`, `p50`, `p99.9`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Round away representation noise such as `0.05 * 100 = 5.000000000000001`
+        write!(f, "p{}", (self.0 * 1e6).round() / 1e4)
+    }
+}
+
+impl FromStr for QuantileSpec {
+    type Err = String;
+
+    /// Parses a fraction (`0.95`) or a percentile (`p95`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let q = match s.strip_prefix('p') {
+            Some(pct) => pct.parse::<f64>().map(|p| p / 100.0),
+            None => s.parse::<f64>(),
+        };
+        match q {
+            Ok(q) if q > 0.0 && q < 1.0 => Ok(Self(q)),
+            _ => Err(format!(
+                "invalid quantile `{s}`, expected a fraction in (0, 1) or `p<percent>`"
+            )),
+        }
+    }
+}
+
+/// Quantile of `sorted` by linear interpolation between the closest ranks.
+///
+/// `sorted` must not be empty.
+pub fn exact_quantile(sorted: &[f64], q: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * q;
+    let lo = h.floor() as usize;
+    match sorted.get(lo + 1) {
+        Some(hi) => sorted[lo] + (h - lo as f64) * (hi - sorted[lo]),
+        None => sorted[lo],
+    }
+}
+
+/// Streaming estimate of one quantile with the P² algorithm (Jain & Chlamtac).
+///
+/// Keeps five markers whose heights approximate the minimum, the `q / 2`, `q` and
+/// `(1 + q) / 2` quantiles and the maximum, adjusting them with a piecewise
+/// parabolic fit per observation: O(1) time and memory regardless of stream length.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    /// Target quantile
+    q: f64,
+    /// Observations so far
+    count: u64,
+    /// Marker heights; the first `count` observations until there are five
+    heights: [f64; 5],
+    /// Actual marker positions (1-based ranks)
+    positions: [f64; 5],
+    /// Desired marker positions
+    desired: [f64; 5],
+    /// Increment of the desired positions per observation
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Creates an estimator of quantile `q` with no observations.
+    pub fn new(q: f64) -> Self {
+        Self {
+            q,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * q, 1.0 + 4.0 * q, 3.0 + 2.0 * q, 5.0],
+            increments: [0.0, q / 2.0, q, (1.0 + q) / 2.0, 1.0],
+        }
+    }
+
+    /// Adds one observation.
+    pub fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count as usize] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Cell of the new observation, stretching the extremes if needed
+        let h = &mut self.heights;
+        let k = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (1..4).find(|&i| x < h[i]).map_or(3, |i| i - 1)
+        };
+        for p in &mut self.positions[k + 1..] {
+            *p += 1.0;
+        }
+        for (d, inc) in self.desired.iter_mut().zip(self.increments) {
+            *d += inc;
+        }
+
+        // Move the middle markers towards their desired positions
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let n = &self.positions;
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                self.heights[i] =
+                    if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                        parabolic
+                    } else {
+                        self.linear(i, d)
+                    };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    /// Current estimate, exact while there are fewer than five observations.
+    pub fn estimate(&self) -> f64 {
+        match self.count {
+            0 => f64::NAN,
+            1..5 => {
+                let mut seen = self.heights[..self.count as usize].to_vec();
+                seen.sort_by(f64::total_cmp);
+                exact_quantile(&seen, self.q)
+            }
+            _ => self.heights[2],
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+    }
+}
+
+/// Values of a sliding window kept sorted, for exact window quantiles.
+///
+/// Replacing a value costs two binary searches and an O(n) shift, which is cheap
+/// for SMA-sized windows.
+#[derive(Debug, Clone)]
+pub struct SortedWindow {
+    sorted: Vec<f64>,
+}
+
+impl SortedWindow {
+    /// Window of `len` copies of `initial`.
+    pub fn new(len: usize, initial: f64) -> Self {
+        Self {
+            sorted: vec![initial; len],
+        }
+    }
+
+    /// Window of `values`, in any order.
+    pub fn from_values(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Self { sorted }
+    }
+
+    /// Replaces one occurrence of `old` by `new`.
+    pub fn replace(&mut self, old: f64, new: f64) {
+        let at = self.sorted.partition_point(|v| v.total_cmp(&old).is_lt());
+        debug_assert!(at < self.sorted.len() && self.sorted[at].total_cmp(&old).is_eq());
+        self.sorted.remove(at);
+        let at = self.sorted.partition_point(|v| v.total_cmp(&new).is_lt());
+        self.sorted.insert(at, new);
+    }
+
+    /// Quantile `q` of the window.
+    pub fn quantile(&self, q: f64) -> f64 {
+        exact_quantile(&self.sorted, q)
+    }
+}
+
+#[cfg(test)]
+mod quantile_tests {
+    use super::*;
+    use crate::utils::xorshift64;
+
+    fn sorted(values: &[f64]) -> Vec<f64> {
+        let mut values = values.to_vec();
+        values.sort_by(f64::total_cmp);
+        values
+    }
+
+    #[test]
+    fn test_spec_parse_and_label() {
+        assert_eq!("0.95".parse(), Ok(QuantileSpec::new(0.95)));
+        assert_eq!("p5".parse::<QuantileSpec>().unwrap().value(), 0.05);
+        assert_eq!(QuantileSpec::new(0.05).to_string(), "p5");
+        assert_eq!(QuantileSpec::new(0.5).to_string(), "p50");
+        assert_eq!(QuantileSpec::new(0.999).to_string(), "p99.9");
+        assert!("0".parse::<QuantileSpec>().is_err());
+        assert!("1".parse::<QuantileSpec>().is_err());
+        assert!("p100".parse::<QuantileSpec>().is_err());
+        assert!("median".parse::<QuantileSpec>().is_err());
+    }
+
+    #[test]
+    fn test_exact_quantile_interpolates() {
+        let values = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(exact_quantile(&values, 0.5), 2.5);
+        assert_eq!(exact_quantile(&values, 0.0), 1.0);
+        assert_eq!(exact_quantile(&values, 1.0), 4.0);
+        assert_eq!(exact_quantile(&[7.0], 0.3), 7.0);
+    }
+
+    #[test]
+    fn test_p2_tracks_exact_quantiles() {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut values = Vec::new();
+        let mut estimators: Vec<P2Quantile> =
+            [0.05, 0.5, 0.95].into_iter().map(P2Quantile::new).collect();
+        for i in 0..20_000 {
+            // Prices drifting around 100 with occasional spikes
+            let noise = (xorshift64(&mut state) % 10_000) as f64 / 1_000.0;
+            let spike = if i % 500 == 0 { 50.0 } else { 0.0 };
+            let x = 100.0 + noise + spike;
+            values.push(x);
+            for e in &mut estimators {
+                e.push(x);
+            }
+        }
+
+        let values = sorted(&values);
+        for e in &estimators {
+            let exact = exact_quantile(&values, e.q);
+            // Within 1% of the value range
+            assert!(
+                (e.estimate() - exact).abs() < 0.6,
+                "q {}: estimate {} exact {exact}",
+                e.q,
+                e.estimate()
+            );
+        }
+    }
+
+    #[test]
+    fn test_p2_is_exact_for_few_observations() {
+        let mut e = P2Quantile::new(0.5);
+        assert!(e.estimate().is_nan());
+        for x in [4.0, 1.0, 3.0] {
+            e.push(x);
+        }
+        assert_eq!(e.estimate(), 3.0);
+        e.push(2.0);
+        assert_eq!(e.estimate(), 2.5);
+    }
+
+    #[test]
+    fn test_sorted_window_matches_naive_sort() {
+        let mut state = 42u64;
+        let window = 7;
+        let mut ring = vec![10.0; window];
+        let mut sorted_window = SortedWindow::new(window, 10.0);
+        for i in 0..2_000 {
+            let x = (xorshift64(&mut state) % 100) as f64;
+            let old = std::mem::replace(&mut ring[i % window], x);
+            sorted_window.replace(old, x);
+
+            let expected = sorted(&ring);
+            for q in [0.05, 0.5, 0.95] {
+                assert_eq!(sorted_window.quantile(q), exact_quantile(&expected, q));
+            }
+        }
+    }
+}
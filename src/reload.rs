@@ -0,0 +1,155 @@
+use std::{fs, future::pending, path::Path, sync::Arc, time::SystemTime};
+
+use tokio::{
+    select, spawn,
+    task::JoinHandle,
+    time::{Interval, MissedTickBehavior, interval},
+};
+use tracing::info;
+
+use crate::admin::SymbolAdmin;
+
+/// Spawns the task reloading the configuration on SIGHUP and, if the
+/// configuration has a `config_watch` interval, whenever its config file changes.
+///
+/// Reloads go through [`SymbolAdmin::reload_from_env`], which logs and keeps the
+/// outcome for `GET /admin/reload`. The file and watch interval are the ones of
+/// the configuration at startup.
+pub fn spawn_reloader(admin: Arc<SymbolAdmin>) -> JoinHandle<()> {
+    let config = admin.config();
+    let file = config.config_file.clone();
+    let mut ticker = match (&file, config.config_watch) {
+        (Some(_), Some(period)) => {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            Some(ticker)
+        }
+        _ => None,
+    };
+
+    spawn(async move {
+        let mut hangup = Hangup::new();
+        let mut modified = file.as_deref().and_then(modified_at);
+        loop {
+            select! {
+                () = hangup.recv() => info!("SIGHUP received, reloading configuration"),
+                () = tick(&mut ticker) => {
+                    let now = file.as_deref().and_then(modified_at);
+                    if now == modified {
+                        continue;
+                    }
+                    modified = now;
+                    info!("Config file changed, reloading configuration");
+                }
+            }
+            admin.reload_from_env().await;
+        }
+    })
+}
+
+/// Last modification time of `path`, `None` if it cannot be read.
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Waits for the next tick; never resolves without a ticker.
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => pending().await,
+    }
+}
+
+/// SIGHUP listener; never fires where the signal does not exist or cannot be
+/// listened to.
+struct Hangup {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangup {
+    fn new() -> Self {
+        Self {
+            #[cfg(unix)]
+            signal: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok(),
+        }
+    }
+
+    /// Waits for the next SIGHUP.
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal {
+            if signal.recv().await.is_some() {
+                return;
+            }
+            self.signal = None;
+        }
+        pending().await
+    }
+}
+
+#[cfg(test)]
+mod reload_tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use tokio::{sync::watch, time::sleep};
+
+    use super::*;
+    use crate::{config::AppConfig, indicator::IndicatorRegistry, models::SharedMap};
+
+    const BTC: &str = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
+
+    #[tokio::test]
+    async fn test_reloads_when_config_file_changes() {
+        let dir = std::env::temp_dir().join(format!("aboss-reload-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let write = |interval: &str| {
+            let file = format!(
+                "urls = [\"{BTC}\"]\ninterval = {interval}\nsma_n = 5\nconfig_watch_interval = 20\n"
+            );
+            fs::write(&path, file).unwrap();
+        };
+        write("1000");
+
+        // Neither the process environment nor `.env` may override the file
+        let registry = IndicatorRegistry::default();
+        let config = AppConfig::from_parts(&HashMap::new(), Some(path.clone()), &registry);
+        let admin = SymbolAdmin::new(
+            watch::channel(Arc::new(config.unwrap())).0,
+            Arc::new(SharedMap::new(HashMap::new())),
+            Arc::new(SharedMap::new(HashMap::new())),
+            HashMap::new(),
+        )
+        .with_env(HashMap::new);
+        let admin = Arc::new(admin);
+        let reloader = spawn_reloader(admin.clone());
+        // Let the reloader note the file's modification time first
+        sleep(Duration::from_millis(50)).await;
+
+        write("250");
+        let mut waited = 0;
+        while admin.config().interval != Duration::from_millis(250) {
+            assert!(waited < 100, "config file change not picked up");
+            sleep(Duration::from_millis(20)).await;
+            waited += 1;
+        }
+        let report = admin.last_reload().unwrap();
+        assert_eq!(report.status, "ok", "{:?}", report.errors);
+        assert!(report.reconfigured);
+
+        write("[250");
+        let mut waited = 0;
+        while admin.last_reload().unwrap().status != "failed" {
+            assert!(waited < 100, "malformed config file not reported");
+            sleep(Duration::from_millis(20)).await;
+            waited += 1;
+        }
+        assert_eq!(admin.config().interval, Duration::from_millis(250));
+
+        reloader.abort();
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::xorshift64;
+
+/// Default delay before the first retry in milliseconds.
+pub const DEFAULT_RETRY_INITIAL_DELAY: u64 = 500;
+/// Default factor applied to the delay after every consecutive failure.
+pub const DEFAULT_RETRY_MULTIPLIER: f64 = 2.0;
+/// Default upper bound for the retry delay in milliseconds.
+pub const DEFAULT_RETRY_MAX_DELAY: u64 = 30_000;
+/// Default fraction of the delay that may be randomly shaved off.
+pub const DEFAULT_RETRY_JITTER: f64 = 0.2;
+/// Default number of consecutive failures before a source is marked degraded.
+pub const DEFAULT_RETRY_MAX_FAILURES: u64 = 5;
+
+/// Retry policy applied by a `RpcManager` after a failed fetch.
+///
+/// The `n`-th consecutive failure waits
+/// `min(initial_delay * multiplier^(n-1), max_delay)`, reduced by a random
+/// fraction of up to `jitter` so that many sources failing together do not
+/// retry in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Growth factor between consecutive retries (`>= 1.0`)
+    pub multiplier: f64,
+    /// Upper bound for a single delay
+    pub max_delay: Duration,
+    /// Fraction of the delay randomised away, in `[0.0, 1.0]`
+    pub jitter: f64,
+    /// Consecutive failures after which the source is reported as degraded
+    pub max_failures: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(DEFAULT_RETRY_INITIAL_DELAY),
+            multiplier: DEFAULT_RETRY_MULTIPLIER,
+            max_delay: Duration::from_millis(DEFAULT_RETRY_MAX_DELAY),
+            jitter: DEFAULT_RETRY_JITTER,
+            max_failures: DEFAULT_RETRY_MAX_FAILURES,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay for the given failure streak (`>= 1`) before jitter is applied.
+    pub fn base_delay(&self, failure_streak: u64) -> Duration {
+        let exp = failure_streak.saturating_sub(1).min(i32::MAX as u64) as i32;
+        let max = self.max_delay.as_secs_f64();
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exp);
+        // `min` also maps an overflowing `inf` back to `max_delay`
+        Duration::from_secs_f64(delay.min(max))
+    }
+
+    /// Returns `true` once `failure_streak` reaches the degraded threshold.
+    pub fn is_degraded(&self, failure_streak: u64) -> bool {
+        self.max_failures > 0 && failure_streak >= self.max_failures
+    }
+}
+
+/// Per-source backoff state driven by a [`RetryPolicy`].
+pub struct Backoff {
+    policy: RetryPolicy,
+    /// Consecutive failures since the last success
+    failure_streak: u64,
+    /// State of the jitter PRNG
+    rng: u64,
+}
+
+impl Backoff {
+    /// Creates a new backoff with an empty failure streak.
+    pub fn new(policy: RetryPolicy) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        // Mix in a stack address so sources created in the same instant still diverge
+        let local = 0u8;
+        let rng = (seed ^ (&local as *const u8 as u64)) | 1;
+        Self {
+            policy,
+            failure_streak: 0,
+            rng,
+        }
+    }
+
+    /// Policy this backoff follows.
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Follows `policy` from the next failure on, keeping the failure streak.
+    pub fn set_policy(&mut self, policy: RetryPolicy) {
+        self.policy = policy;
+    }
+
+    /// Consecutive failures since the last success.
+    pub fn failure_streak(&self) -> u64 {
+        self.failure_streak
+    }
+
+    /// Records a failure and returns how long to wait before retrying.
+    pub fn on_failure(&mut self) -> Duration {
+        self.failure_streak = self.failure_streak.saturating_add(1);
+        let base = self.policy.base_delay(self.failure_streak);
+
+        let jitter = self.policy.jitter.clamp(0.0, 1.0);
+        // Uniform in [0, 1) from the top 53 bits
+        let unit = (xorshift64(&mut self.rng) >> 11) as f64 / (1u64 << 53) as f64;
+        base.mul_f64(1.0 - jitter * unit)
+    }
+
+    /// Records a success, resetting the failure streak.
+    pub fn on_success(&mut self) {
+        self.failure_streak = 0;
+    }
+}
+
+#[cfg(test)]
+mod retry_tests {
+    use super::*;
+
+    fn policy(jitter: f64) -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_millis(1000),
+            jitter,
+            max_failures: 3,
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_exponentially_and_caps() {
+        let mut backoff = Backoff::new(policy(0.0));
+        let delays: Vec<u128> = (0..7).map(|_| backoff.on_failure().as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000, 1000]);
+        assert_eq!(backoff.failure_streak(), 7);
+    }
+
+    #[test]
+    fn test_success_resets_streak() {
+        let mut backoff = Backoff::new(policy(0.0));
+        backoff.on_failure();
+        backoff.on_failure();
+        backoff.on_success();
+        assert_eq!(backoff.failure_streak(), 0);
+        assert_eq!(backoff.on_failure().as_millis(), 100);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let mut backoff = Backoff::new(policy(0.5));
+        for _ in 0..1000 {
+            backoff.on_success();
+            let d = backoff.on_failure();
+            assert!(d <= Duration::from_millis(100));
+            assert!(d >= Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_huge_streak_does_not_overflow() {
+        let p = policy(0.0);
+        assert_eq!(p.base_delay(u64::MAX), p.max_delay);
+    }
+
+    #[test]
+    fn test_degraded_threshold() {
+        let p = policy(0.0);
+        assert!(!p.is_degraded(2));
+        assert!(p.is_degraded(3));
+        let never = RetryPolicy {
+            max_failures: 0,
+            ..p
+        };
+        assert!(!never.is_degraded(u64::MAX));
+    }
+}
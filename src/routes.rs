@@ -1,43 +1,137 @@
 use actix_web::{
-    HttpResponse, HttpResponseBuilder, get,
-    http::StatusCode,
-    web::{Data, Query, ServiceConfig},
+    HttpRequest, HttpResponse, HttpResponseBuilder, delete, get,
+    http::{StatusCode, header::AUTHORIZATION},
+    post,
+    web::{Data, Json, Path, Query, ServiceConfig},
 };
 
 use crate::{
-    dto::{AllStatesResponse, HealthResponse, StatsResponse},
-    models::{MapData, QuerryData},
+    admin::SymbolAdmin,
+    dto::{
+        AddedSymbolsResponse, AllStatesResponse, ConsensusResponse, HealthResponse, KlineResponse,
+        ReadyResponse, SymbolStatsResponse, WindowStatsResponse,
+    },
+    models::{
+        AddSymbolRequest, CandleQuery, ConsensusData, HealthData, MapData, QuerryData, ReadyData,
+    },
+    window::WindowSpec,
 };
 
 /// Health check endpoint.
 ///
-/// Returns a simple JSON string indicating the service status.
+/// Returns the circuit breaker and retry state of every source. Responds with
+/// HTTP 503 once the configured fraction of sources is unhealthy, HTTP 200 otherwise.
 /// Example response:
 /// ```json
-/// { "status": "ok" }
+/// {
+///   "status": "degraded",
+///   "sources": {
+///     "BTCUSDT": {
+///       "state": "open",
+///       "healthy": false,
+///       "consecutive_failures": 5,
+///       "last_success": 1754900000000,
+///       "last_error": "error sending request",
+///       "last_error_at": 1754900042000,
+///       "next_retry_at": 1754900072000
+///     }
+///   }
+/// }
 /// ```
 #[get("/health")]
-async fn health() -> HttpResponse {
-    HttpResponseBuilder::new(StatusCode::OK)
-        .body(HealthResponse::health_status_json_string_default())
+async fn health(health: Data<HealthData>) -> HttpResponse {
+    let unhealthy_fraction = health.config.borrow().unhealthy_fraction;
+    let res = HealthResponse::from_sources(health.sources.load().iter(), unhealthy_fraction);
+    let code = if res.is_unhealthy() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    HttpResponseBuilder::new(code).json(&res)
+}
+
+/// Readiness endpoint.
+///
+/// Reports the freshness of every required symbol. Responds with HTTP 503 when
+/// one of them is not tracked or was last updated longer ago than its staleness
+/// limit, HTTP 200 otherwise; unlike `/health` it does not look at fetch errors.
+/// Example response:
+/// ```json
+/// {
+///   "status": "not_ready",
+///   "symbols": {
+///     "BTCUSDT": { "tracked": true, "age_ms": 420, "stale": false },
+///     "ETHUSDT": { "tracked": true, "age_ms": 95000, "stale": true }
+///   }
+/// }
+/// ```
+#[get("/ready")]
+async fn ready(ready: Data<ReadyData>, map: Data<MapData>) -> HttpResponse {
+    let readers = map.data.load();
+    let config = ready.config.borrow().clone();
+    let res = if config.ready_symbols.is_empty() {
+        let mut all: Vec<String> = readers.keys().cloned().collect();
+        all.sort();
+        ReadyResponse::from_readers(&all, &readers)
+    } else {
+        ReadyResponse::from_readers(&config.ready_symbols, &readers)
+    };
+    let code = if res.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    HttpResponseBuilder::new(code).json(&res)
 }
 
 /// Get statistics for a specific symbol.
 ///
-/// - `querry`: Query parameter containing the `symbol` to look up.
-/// - `map`: Shared read-only reference to `MapData` containing all symbol readers.
+/// - `querry`: Query parameter containing the `symbol` to look up and an optional
+///   time `window` (e.g. `5m`).
+/// - `map`: Shared reference to `MapData` containing all symbol readers.
 ///
 /// Returns HTTP 200 with JSON body containing the stats for the symbol if it exists,
-/// or HTTP 204 if the symbol is not found.
+/// or HTTP 204 if the symbol is not found. With `window`, only the statistics of that
+/// time window are returned, or HTTP 400 if the window is invalid or not tracked.
 ///
-/// Example JSON response:
+/// Example JSON response (one `ema_*` field per configured EMA, `p*` and `window_p*`
+/// fields per configured quantile):
 /// ```json
 /// {
 ///   "min": 123.45,
 ///   "max": 234.56,
+///   "window_min": 205.17,
+///   "window_max": 214.02,
 ///   "curr_avg": 200.12,
 ///   "sma": 210.34,
-///   "data_point": 50
+///   "variance": 4.41,
+///   "std_dev": 2.1,
+///   "bollinger_upper": 214.54,
+///   "bollinger_lower": 206.14,
+///   "data_point": 50,
+///   "first_seen": 1754900000000,
+///   "last_update": 1754900049000,
+///   "event_time": null,
+///   "age_ms": 420,
+///   "stale": false,
+///   "ema_20": 208.91,
+///   "p50": 201.3,
+///   "window_p50": 210.6
+/// }
+/// ```
+///
+/// Example JSON response for `window=5m`:
+/// ```json
+/// {
+///   "symbol": "BTCUSDT",
+///   "window": "5m",
+///   "mean": 210.12,
+///   "min": 205.17,
+///   "max": 214.02,
+///   "first": 206.5,
+///   "last": 212.3,
+///   "change_pct": 2.81,
+///   "count": 300
 /// }
 /// ```
 #[get("/stats")]
@@ -45,9 +139,19 @@ async fn stat(querry: Query<QuerryData>, map: Data<MapData>) -> HttpResponse {
     let res = map.data.get(&querry.symbol);
 
     if let Some(pair_data) = res {
-        let data: StatsResponse = pair_data.read().into();
-        HttpResponseBuilder::new(StatusCode::OK)
-        .json(&data)
+        if let Some(window) = &querry.window {
+            let data = window
+                .parse::<WindowSpec>()
+                .ok()
+                .and_then(|w| WindowStatsResponse::from_reader(&querry.symbol, &pair_data, w));
+            return match data {
+                Some(data) => HttpResponseBuilder::new(StatusCode::OK).json(&data),
+                None => HttpResponseBuilder::new(StatusCode::BAD_REQUEST)
+                    .body(format!("Unknown window `{window}`")),
+            };
+        }
+        let data = SymbolStatsResponse::from(&pair_data);
+        HttpResponseBuilder::new(StatusCode::OK).json(&data)
     } else {
         HttpResponseBuilder::new(StatusCode::NO_CONTENT)
             .body("The content you search does not exist")
@@ -56,26 +160,259 @@ async fn stat(querry: Query<QuerryData>, map: Data<MapData>) -> HttpResponse {
 
 /// Get statistics for all symbols.
 ///
-/// - `map`: Shared read-only reference to `MapData`.
+/// - `map`: Shared reference to `MapData`.
 ///
 /// Returns HTTP 200 with JSON array containing statistics for all available symbols.
 #[get("/stats/")]
 async fn stats(map: Data<MapData>) -> HttpResponse {
-    let mut result = Vec::with_capacity(map.data.len() + 1);
+    let readers = map.data.load();
+    let mut result = Vec::with_capacity(readers.len() + 1);
 
-    for (symbol,reader) in map.data.iter() {
-        let data: StatsResponse = reader.read().into();
-        let val = AllStatesResponse{symbol:symbol.clone(),stats:data};
+    for (symbol, reader) in readers.iter() {
+        let val = AllStatesResponse {
+            symbol: symbol.clone(),
+            stats: reader.into(),
+        };
         result.push(val);
     }
 
-    HttpResponseBuilder::new(StatusCode::OK)
-    .json(&result)
+    HttpResponseBuilder::new(StatusCode::OK).json(&result)
+}
+
+/// Default number of candles returned by `/candles`, as on Binance.
+const DEFAULT_CANDLES_LIMIT: usize = 500;
+
+/// Get the OHLCV candles of a symbol.
+///
+/// - `querry`: `symbol`, candle `interval` (e.g. `1m`) and optional `limit`
+///   (default 500, at most the retained history).
+/// - `map`: Shared reference to `MapData` containing all symbol readers.
+///
+/// Returns HTTP 200 with the candles, oldest first, in the Binance kline layout,
+/// HTTP 204 if the symbol is not found, or HTTP 400 if the interval is invalid or
+/// not tracked. The last candle is still open.
+///
+/// Example JSON response:
+/// ```json
+/// [
+///   [1754900040000, "117450.1", "117463.86", "117448.2", "117454.33", "0.8123",
+///    1754900099999, "95408.11", 60, "0", "0", "0"]
+/// ]
+/// ```
+#[get("/candles")]
+async fn candles(querry: Query<CandleQuery>, map: Data<MapData>) -> HttpResponse {
+    let Some(reader) = map.data.get(&querry.symbol).map(|r| r.latest()) else {
+        return HttpResponseBuilder::new(StatusCode::NO_CONTENT)
+            .body("The content you search does not exist");
+    };
+
+    let limit = querry.limit.unwrap_or(DEFAULT_CANDLES_LIMIT);
+    let interval = querry.interval.parse::<WindowSpec>().ok();
+    match interval.and_then(|i| Some((i, reader.candles(i, limit)?))) {
+        Some((interval, candles)) => {
+            let result: Vec<KlineResponse> = candles
+                .into_iter()
+                .map(|candle| KlineResponse {
+                    candle,
+                    interval_ms: interval.millis(),
+                    tick_size: reader.tick_size(),
+                })
+                .collect();
+            HttpResponseBuilder::new(StatusCode::OK).json(&result)
+        }
+        None => HttpResponseBuilder::new(StatusCode::BAD_REQUEST)
+            .body(format!("Unknown interval `{}`", querry.interval)),
+    }
+}
+
+/// Get the consensus price of a symbol fed by several sources.
+///
+/// - `querry`: Query parameter containing the `symbol` to look up.
+/// - `books`: Shared reference to the consensus books.
+///
+/// Returns HTTP 200 with the consensus and every source's contribution and
+/// deviation, or HTTP 204 if the symbol has no consensus (unknown or single source).
+///
+/// Example JSON response:
+/// ```json
+/// {
+///   "symbol": "BTCUSDT",
+///   "method": "median",
+///   "price": 117454.33,
+///   "updated_at": 1754900071000,
+///   "sources": [
+///     {
+///       "source": "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT",
+///       "price": 117454.33,
+///       "volume": null,
+///       "weight": 1.0,
+///       "stale": false,
+///       "age_ms": 120,
+///       "deviation": 0.0,
+///       "deviation_bps": 0.0
+///     }
+///   ]
+/// }
+/// ```
+#[get("/consensus")]
+async fn consensus(querry: Query<QuerryData>, books: Data<ConsensusData>) -> HttpResponse {
+    match books.books.get(&querry.symbol) {
+        Some(book) => HttpResponseBuilder::new(StatusCode::OK).json(book.report()),
+        None => HttpResponseBuilder::new(StatusCode::NO_CONTENT)
+            .body("The content you search does not exist"),
+    }
+}
+
+/// Get the consensus of every symbol fed by several sources.
+///
+/// Returns HTTP 200 with a JSON array of consensus reports.
+#[get("/consensus/")]
+async fn consensus_all(books: Data<ConsensusData>) -> HttpResponse {
+    let result: Vec<ConsensusResponse> = books.books.values().map(|b| b.report()).collect();
+    HttpResponseBuilder::new(StatusCode::OK).json(&result)
+}
+
+/// Returns the response rejecting `req`: HTTP 403 while no admin token is
+/// configured, since `/admin/symbols` fetches any URL it is given, and HTTP 401 if
+/// `req` does not carry the token.
+fn rejected(req: &HttpRequest, admin: &SymbolAdmin) -> Option<HttpResponse> {
+    let Some(token) = admin.token() else {
+        return Some(
+            HttpResponseBuilder::new(StatusCode::FORBIDDEN)
+                .body("admin endpoints are disabled, set ADMIN_TOKEN to enable them"),
+        );
+    };
+    let authorized = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|value| value == token);
+    (!authorized).then(|| HttpResponseBuilder::new(StatusCode::UNAUTHORIZED).finish())
+}
+
+/// Start tracking a new source at runtime.
+///
+/// - `body`: `{"url": "..."}`, in the format of an entry of `URLS`.
+/// - `admin`: Shared reference to the `SymbolAdmin`.
+///
+/// Seeds the source and spawns its poller, then returns HTTP 201 with the symbols
+/// it tracks. Responds with HTTP 400 for an unsupported URL, HTTP 409 if one of
+/// the symbols is already tracked, HTTP 502 if seeding failed, HTTP 401 without
+/// the `ADMIN_TOKEN` bearer token and HTTP 403 if no token is configured.
+///
+/// Example JSON response:
+/// ```json
+/// { "symbols": ["SOLUSDT"] }
+/// ```
+#[post("/admin/symbols")]
+async fn add_symbol(
+    req: HttpRequest,
+    body: Json<AddSymbolRequest>,
+    admin: Data<SymbolAdmin>,
+) -> HttpResponse {
+    if let Some(rejection) = rejected(&req, &admin) {
+        return rejection;
+    }
+    match admin.add(&body.url).await {
+        Ok(symbols) => {
+            HttpResponseBuilder::new(StatusCode::CREATED).json(AddedSymbolsResponse { symbols })
+        }
+        Err(e) => HttpResponseBuilder::new(e.status()).body(e.to_string()),
+    }
+}
+
+/// Stop tracking a symbol at runtime.
+///
+/// - `symbol`: Path segment naming the symbol (e.g. `SOLUSDT`).
+/// - `admin`: Shared reference to the `SymbolAdmin`.
+///
+/// Cancels the symbol's poller and drops its statistics, then returns HTTP 204.
+/// Responds with HTTP 404 if the symbol is not tracked, HTTP 409 if its source also
+/// feeds other symbols (batched or combined), HTTP 401 without the `ADMIN_TOKEN`
+/// bearer token and HTTP 403 if no token is configured.
+#[delete("/admin/symbols/{symbol}")]
+async fn remove_symbol(
+    req: HttpRequest,
+    symbol: Path<String>,
+    admin: Data<SymbolAdmin>,
+) -> HttpResponse {
+    if let Some(rejection) = rejected(&req, &admin) {
+        return rejection;
+    }
+    match admin.remove(&symbol) {
+        Ok(()) => HttpResponseBuilder::new(StatusCode::NO_CONTENT).finish(),
+        Err(e) => HttpResponseBuilder::new(e.status()).body(e.to_string()),
+    }
+}
+
+/// Reload the configuration from the config file and the environment.
+///
+/// - `admin`: Shared reference to the `SymbolAdmin`.
+///
+/// Applies the differences to the running service (see `SymbolAdmin::reload`) and
+/// returns HTTP 200 with the outcome, HTTP 400 if the configuration could not be
+/// loaded, HTTP 401 without the `ADMIN_TOKEN` bearer token or HTTP 403 if no token
+/// is configured.
+///
+/// Example JSON response:
+/// ```json
+/// {
+///   "at": 1754900071000,
+///   "status": "partial",
+///   "added": ["SOLUSDT"],
+///   "removed": [],
+///   "statistics": {
+///     "ETHUSDT": ["rsi(14) added: warmed up from the SMA window"]
+///   },
+///   "reconfigured": true,
+///   "errors": ["IP and PORT changes need a restart"]
+/// }
+/// ```
+#[post("/admin/reload")]
+async fn reload(req: HttpRequest, admin: Data<SymbolAdmin>) -> HttpResponse {
+    if let Some(rejection) = rejected(&req, &admin) {
+        return rejection;
+    }
+    let res = admin.reload_from_env().await;
+    let code = if res.is_failed() {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::OK
+    };
+    HttpResponseBuilder::new(code).json(&res)
+}
+
+/// Get the outcome of the latest configuration reload, whatever triggered it.
+///
+/// Returns HTTP 200 with the same body as `POST /admin/reload`, HTTP 204 if the
+/// configuration was never reloaded, HTTP 401 without the `ADMIN_TOKEN` bearer
+/// token or HTTP 403 if no token is configured.
+#[get("/admin/reload")]
+async fn last_reload(req: HttpRequest, admin: Data<SymbolAdmin>) -> HttpResponse {
+    if let Some(rejection) = rejected(&req, &admin) {
+        return rejection;
+    }
+    match admin.last_reload() {
+        Some(res) => HttpResponseBuilder::new(StatusCode::OK).json(&res),
+        None => HttpResponseBuilder::new(StatusCode::NO_CONTENT).finish(),
+    }
 }
 
 /// Initialize all routes for the application.
 ///
-/// Registers the health and stats endpoints with the Actix-web service configuration.
+/// Registers the health, readiness, stats, candles, consensus and admin endpoints
+/// with the Actix-web service configuration.
 pub fn init(cfg: &mut ServiceConfig) {
-    cfg.service(health).service(stat).service(stats);
+    cfg.service(health)
+        .service(ready)
+        .service(stat)
+        .service(stats)
+        .service(candles)
+        .service(consensus)
+        .service(consensus_all)
+        .service(add_symbol)
+        .service(remove_symbol)
+        .service(reload)
+        .service(last_reload);
 }
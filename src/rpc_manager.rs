@@ -1,60 +1,82 @@
-use std::{marker::PhantomData, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    future::pending,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use reqwest::{Client, Error};
 use serde::de::DeserializeOwned;
-use tokio::time::interval;
-use tracing::error;
+use tokio::{
+    select, spawn,
+    task::AbortHandle,
+    time::{Interval, MissedTickBehavior, interval, sleep},
+};
+use tracing::{error, info, warn};
 
-use crate::{data_processor::DataProcessorWriter, dto::GetPrice};
+use crate::{
+    circuit_breaker::{BreakerPolicy, BreakerState, CircuitBreaker},
+    config::{AppConfig, ConfigUpdates},
+    consensus::{ConsensusBook, ConsensusInput, ConsensusPolicy},
+    data_processor::{DataProcessor, DataProcessorReader, StatsConfig},
+    dto::GetPrice,
+    models::SourceStatus,
+    retry::{Backoff, RetryPolicy},
+    source::{PriceSink, PriceSource, PriceUpdate, SourceError, SourceFuture},
+    utils::retune_ticker,
+};
 
-/// A generic RPC manager that periodically fetches data from a given HTTP endpoint
-/// and updates a `DataProcessorWriter` with the latest value.
+/// Polls a single REST endpoint on a fixed interval and deserializes `T`.
 ///
 /// # Type Parameters
 /// - `T`: The response type from the endpoint, which must implement `DeserializeOwned`
 ///   (to allow JSON deserialization) and `GetPrice` (to extract the price from the response).
-pub struct RpcManager<'a, T>
+pub struct HttpPoller<T>
 where
     T: DeserializeOwned + GetPrice,
 {
-    /// Interval between successive requests.
-    interval: Duration,
-
     /// Full path to query, including query parameters.
-    path: &'a str,
+    path: String,
 
-    /// Writer for updating shared streaming statistics.
-    data_processor_writer: DataProcessorWriter,
+    /// Symbol the endpoint reports, as a one-element list for `PriceSource::symbols`.
+    symbols: [String; 1],
 
     /// Reqwest client used for HTTP requests.
     client_manager: Client,
 
+    /// Paces the requests; the first tick completes immediately.
+    ticker: Interval,
+
     /// Phantom data to tie the generic response type to this struct.
-    _response_phantom_data: PhantomData<T>,
+    _response_phantom_data: PhantomData<fn() -> T>,
 }
 
-impl<'a, ResponseType> RpcManager<'a, ResponseType>
+impl<ResponseType> HttpPoller<ResponseType>
 where
     ResponseType: DeserializeOwned + GetPrice,
 {
-    /// Constructs a new `RpcManager`.
+    /// Constructs a new `HttpPoller`.
     ///
     /// # Parameters
-    /// - `interval`: Duration between HTTP requests.
+    /// - `interval_duration`: Duration between HTTP requests.
     /// - `path`: URL path for the RPC endpoint.
+    /// - `symbol`: Symbol the endpoint reports.
     /// - `client_manager`: Reqwest client to perform requests.
-    /// - `data_processor_writer`: Writer to update statistics with fetched prices.
     pub fn new(
-        interval: Duration,
-        path: &'a str,
+        interval_duration: Duration,
+        path: String,
+        symbol: String,
         client_manager: Client,
-        data_processor_writer: DataProcessorWriter,
     ) -> Self {
+        let mut ticker = interval(interval_duration);
+        // After a backoff sleep, resume the normal cadence instead of bursting missed ticks
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
         Self {
-            interval,
             path,
-            data_processor_writer,
+            symbols: [symbol],
             client_manager,
+            ticker,
             _response_phantom_data: PhantomData,
         }
     }
@@ -72,38 +94,695 @@ where
         let res = client_manager.get(path).send().await?;
         res.json::<ResponseType>().await
     }
+}
+
+impl<ResponseType> PriceSource for HttpPoller<ResponseType>
+where
+    ResponseType: DeserializeOwned + GetPrice,
+{
+    fn name(&self) -> &str {
+        &self.path
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    fn expects_all_symbols(&self) -> bool {
+        true
+    }
+
+    fn next_prices(&mut self) -> SourceFuture<'_> {
+        Box::pin(async move {
+            // Wait for the configured interval before the next request
+            self.ticker.tick().await;
+            let res = Self::send_reqwest(&self.client_manager, &self.path).await?;
+            let update = PriceUpdate::new(
+                self.symbols[0].clone(),
+                res.get_price(),
+                res.get_event_time(),
+            )
+            .with_decimal(res.get_decimal())
+            .with_volume(res.get_volume())
+            .with_quantity(res.get_quantity());
+            Ok(vec![update])
+        })
+    }
+
+    fn reconfigure(&mut self, config: &AppConfig) {
+        retune_ticker(&mut self.ticker, config.interval_for(&self.symbols[0]));
+        self.client_manager = config.client.clone();
+    }
+}
+
+/// Readers, statuses, consensus books and tasks of every symbol started by
+/// [`RpcManager::spawn_all`].
+#[derive(Default)]
+pub struct Tracked {
+    /// Symbol -> reader of its statistics
+    pub readers: HashMap<String, DataProcessorReader>,
+    /// Symbol (or `<symbol>@<source>` for combined symbols) -> source status
+    pub statuses: HashMap<String, Arc<SourceStatus>>,
+    /// Symbol -> consensus of the symbols fed by several sources
+    pub books: HashMap<String, Arc<ConsensusBook>>,
+    /// Symbol -> task of the source feeding only that symbol, which can be stopped
+    /// without affecting other symbols
+    pub tasks: HashMap<String, AbortHandle>,
+}
+
+/// Drives a [`PriceSource`] and hands every price to the `PriceSink` of its symbol.
+///
+/// The manager owns everything that is independent of how prices are fetched:
+/// fanning updates out by symbol, retry backoff, the circuit breaker and the
+/// per-symbol `SourceStatus` read by `/health`.
+pub struct RpcManager {
+    /// Source of prices.
+    source: Box<dyn PriceSource>,
+
+    /// Symbol -> destination of its prices.
+    writers: HashMap<String, Box<dyn PriceSink>>,
+
+    /// Symbol -> status reported to the HTTP layer.
+    statuses: HashMap<String, Arc<SourceStatus>>,
+
+    /// Backoff state applied after failed fetches.
+    backoff: Backoff,
+
+    /// Circuit breaker suspending a persistently failing source.
+    breaker: CircuitBreaker,
+
+    /// Reloaded configurations to apply to the source, if reloads are enabled.
+    updates: Option<ConfigUpdates>,
+}
+
+impl RpcManager {
+    /// Constructs a new `RpcManager`.
+    ///
+    /// # Parameters
+    /// - `source`: Where prices come from.
+    /// - `outputs`: Symbol -> (sink, status) for every symbol of `source`.
+    /// - `retry_policy`: Backoff applied after failed fetches.
+    /// - `breaker_policy`: Thresholds for the circuit breaker.
+    pub fn new(
+        source: Box<dyn PriceSource>,
+        outputs: HashMap<String, (Box<dyn PriceSink>, Arc<SourceStatus>)>,
+        retry_policy: RetryPolicy,
+        breaker_policy: BreakerPolicy,
+    ) -> Self {
+        let mut writers = HashMap::with_capacity(outputs.len());
+        let mut statuses = HashMap::with_capacity(outputs.len());
+        for (symbol, (writer, status)) in outputs {
+            writers.insert(symbol.clone(), writer);
+            statuses.insert(symbol, status);
+        }
+        Self {
+            source,
+            writers,
+            statuses,
+            backoff: Backoff::new(retry_policy),
+            breaker: CircuitBreaker::new(breaker_policy),
+            updates: None,
+        }
+    }
+
+    /// Applies every configuration published on `updates` to the source, e.g. a
+    /// new poll interval, without waiting for the current fetch to finish.
+    pub fn with_updates(mut self, updates: Option<ConfigUpdates>) -> Self {
+        self.updates = updates;
+        self
+    }
+
+    /// Fetches from `source` until every one of its symbols has a price.
+    ///
+    /// Used to seed the statistics before the manager starts; any error is returned.
+    /// A snapshot source (`expects_all_symbols`) must deliver every symbol in its
+    /// first response, otherwise `SourceError::MissingSymbols` is returned. A source
+    /// that does not know its symbols up front is seeded with whatever its first
+    /// response reports.
+    pub async fn seed(source: &mut dyn PriceSource) -> Result<HashMap<String, f64>, SourceError> {
+        let discover = source.symbols().is_empty();
+        let mut seeded = HashMap::with_capacity(source.symbols().len());
+        while seeded.is_empty() || seeded.len() < source.symbols().len() {
+            let updates = source.next_prices().await?;
+            for update in updates {
+                if discover || source.symbols().contains(&update.symbol) {
+                    seeded.insert(update.symbol, update.price);
+                }
+            }
+            if discover {
+                break;
+            }
+
+            if source.expects_all_symbols() && seeded.len() < source.symbols().len() {
+                let missing = source
+                    .symbols()
+                    .iter()
+                    .filter(|s| !seeded.contains_key(*s))
+                    .cloned()
+                    .collect();
+                return Err(SourceError::MissingSymbols(missing));
+            }
+        }
+        Ok(seeded)
+    }
 
-    /// Continuously fetches data from the RPC endpoint at the configured interval.
+    /// Seeds a `DataProcessor` per symbol of `source` and spawns a manager for it.
     ///
-    /// On successful fetch, it extracts the price using `GetPrice` and writes it to
-    /// the `DataProcessorWriter`. Errors during fetching or deserialization are logged
-    /// but do not stop the loop.
+    /// Returns the reader and status of every symbol.
+    pub async fn spawn(
+        source: Box<dyn PriceSource>,
+        stats: &StatsConfig,
+        retry_policy: RetryPolicy,
+        breaker_policy: BreakerPolicy,
+    ) -> Result<Vec<(String, DataProcessorReader, Arc<SourceStatus>)>, SourceError> {
+        let (handles, _task) =
+            Self::spawn_cancellable(source, stats, retry_policy, breaker_policy, None).await?;
+        Ok(handles)
+    }
+
+    /// Same as [`RpcManager::spawn`], also returning a handle that stops the manager;
+    /// the source follows the configurations published on `updates`.
+    ///
+    /// Aborting the task drops the source and the writers of its symbols; their
+    /// readers keep serving the last published statistics.
+    pub async fn spawn_cancellable(
+        mut source: Box<dyn PriceSource>,
+        stats: &StatsConfig,
+        retry_policy: RetryPolicy,
+        breaker_policy: BreakerPolicy,
+        updates: Option<ConfigUpdates>,
+    ) -> Result<
+        (
+            Vec<(String, DataProcessorReader, Arc<SourceStatus>)>,
+            AbortHandle,
+        ),
+        SourceError,
+    > {
+        let seeded = Self::seed(source.as_mut()).await?;
+
+        let mut handles = Vec::with_capacity(seeded.len());
+        let mut outputs = HashMap::with_capacity(seeded.len());
+        for (symbol, initial_data) in seeded {
+            let (reader, writer) =
+                DataProcessor::split_with(&stats.for_symbol(&symbol), initial_data);
+            // The seed fetch already succeeded
+            let status = Arc::new(SourceStatus::default());
+            status.record_success();
+            outputs.insert(
+                symbol.clone(),
+                (Box::new(writer) as Box<dyn PriceSink>, status.clone()),
+            );
+            handles.push((symbol, reader, status));
+        }
+
+        info!(
+            "Tracking {} symbol(s) from {}",
+            handles.len(),
+            source.name()
+        );
+        let manager =
+            Self::new(source, outputs, retry_policy, breaker_policy).with_updates(updates);
+        let task = spawn(manager.init_run()).abort_handle();
+        Ok((handles, task))
+    }
+
+    /// Seeds every source and spawns a manager for each of them.
+    ///
+    /// A symbol produced by a single source gets its own `DataProcessor`, as with
+    /// [`RpcManager::spawn`]. A symbol produced by several sources gets a
+    /// [`ConsensusBook`] instead: each source submits its quotes to the book, which
+    /// writes the combined price to the symbol's `DataProcessor`.
+    ///
+    /// Every source follows the configurations published on `updates`.
+    ///
+    /// # Returns
+    /// The readers, statuses and consensus books of every symbol, and the tasks of
+    /// sources dedicated to a single symbol. Statuses of combined symbols are keyed
+    /// `<symbol>@<source name>`.
+    pub async fn spawn_all(
+        sources: Vec<Box<dyn PriceSource>>,
+        stats: &StatsConfig,
+        retry_policy: RetryPolicy,
+        breaker_policy: BreakerPolicy,
+        consensus_policy: ConsensusPolicy,
+        updates: Option<ConfigUpdates>,
+    ) -> Result<Tracked, SourceError> {
+        let mut seeded = Vec::with_capacity(sources.len());
+        for mut source in sources {
+            let prices = Self::seed(source.as_mut()).await?;
+            seeded.push((source, prices));
+        }
+
+        // Symbol -> (source name, seed price) of every source producing it
+        let mut quotes: HashMap<&str, Vec<(String, f64)>> = HashMap::new();
+        for (source, prices) in &seeded {
+            for (symbol, price) in prices {
+                quotes
+                    .entry(symbol)
+                    .or_default()
+                    .push((source.name().to_string(), *price));
+            }
+        }
+
+        let mut tracked = Tracked::default();
+        for (symbol, seeds) in quotes {
+            if seeds.len() > 1 {
+                info!(
+                    "Combining {} sources for {} by {}",
+                    seeds.len(),
+                    symbol,
+                    consensus_policy.method
+                );
+                let (book, reader) =
+                    ConsensusBook::new(symbol.to_string(), consensus_policy, stats, seeds);
+                tracked.readers.insert(symbol.to_string(), reader);
+                tracked.books.insert(symbol.to_string(), Arc::new(book));
+            }
+        }
+
+        for (source, prices) in seeded {
+            let mut outputs = HashMap::with_capacity(prices.len());
+            for (symbol, initial_data) in prices {
+                // The seed fetch already succeeded
+                let status = Arc::new(SourceStatus::default());
+                status.record_success();
+
+                let sink: Box<dyn PriceSink> = match tracked.books.get(&symbol) {
+                    Some(book) => {
+                        let key = format!("{}@{}", symbol, source.name());
+                        tracked.statuses.insert(key, status.clone());
+                        Box::new(ConsensusInput::new(book.clone(), source.name().into()))
+                    }
+                    None => {
+                        let (reader, writer) =
+                            DataProcessor::split_with(&stats.for_symbol(&symbol), initial_data);
+                        tracked.readers.insert(symbol.clone(), reader);
+                        tracked.statuses.insert(symbol.clone(), status.clone());
+                        Box::new(writer)
+                    }
+                };
+                outputs.insert(symbol, (sink, status));
+            }
+
+            info!(
+                "Tracking {} symbol(s) from {}",
+                outputs.len(),
+                source.name()
+            );
+            // Only a source feeding a single symbol of its own can be stopped alone
+            let dedicated = match outputs.keys().next() {
+                Some(symbol) if outputs.len() == 1 && !tracked.books.contains_key(symbol) => {
+                    Some(symbol.clone())
+                }
+                _ => None,
+            };
+            let manager = Self::new(source, outputs, retry_policy, breaker_policy)
+                .with_updates(updates.clone());
+            let task = spawn(manager.init_run()).abort_handle();
+            if let Some(symbol) = dedicated {
+                tracked.tasks.insert(symbol, task);
+            }
+        }
+        Ok(tracked)
+    }
+
+    /// Writes every update to the writer of its symbol.
+    ///
+    /// Updates for unknown symbols are logged and dropped. If the source delivers
+    /// full snapshots, symbols absent from `updates` are logged and recorded as failed.
+    pub fn fan_out(&self, updates: Vec<PriceUpdate>) {
+        let mut seen = HashSet::with_capacity(updates.len());
+        for update in updates {
+            match self.writers.get(&update.symbol) {
+                Some(writer) => {
+                    writer.write(&update);
+                    if let Some(status) = self.statuses.get(&update.symbol) {
+                        status.record_success();
+                    }
+                    seen.insert(update.symbol);
+                }
+                None => warn!(
+                    "Source {} produced unrequested symbol {}",
+                    self.source.name(),
+                    update.symbol
+                ),
+            }
+        }
+
+        if !self.source.expects_all_symbols() {
+            return;
+        }
+        for (symbol, status) in &self.statuses {
+            if !seen.contains(symbol) {
+                warn!("Source {} is missing symbol {}", self.source.name(), symbol);
+                let streak = status.failure_streak().saturating_add(1);
+                let degraded = self.backoff.policy().is_degraded(streak);
+                status.record_failure(
+                    streak,
+                    Duration::ZERO,
+                    degraded,
+                    "missing from source response".into(),
+                );
+            }
+        }
+    }
+
+    /// Mirrors the breaker state into every symbol's status.
+    fn publish_breaker_state(&self) {
+        let state = self.breaker.state();
+        self.statuses
+            .values()
+            .for_each(|s| s.set_breaker_state(state));
+    }
+
+    /// Continuously pulls prices from the source.
+    ///
+    /// Every batch of updates is written to the matching `DataProcessorWriter`.
+    /// Errors are logged and retried after the delay given by the `RetryPolicy`, so
+    /// a failing source is never hammered in a tight loop. Once the circuit breaker
+    /// trips, fetching is suspended until its open timeout elapses and a single probe
+    /// is sent.
     ///
     /// # Note
     /// This function never returns (`-> !`) as it loops indefinitely.
-    pub async fn init_run(self) -> ! {
-        let client_manager = &self.client_manager;
-        let path = self.path;
-        let mut ticker = interval(self.interval);
+    pub async fn init_run(mut self) -> ! {
         loop {
-            let res = Self::send_reqwest(client_manager, path).await;
-            match res {
-                Ok(price_data) => {
-                    // Extract price and update the shared data processor
-                    let price = price_data.get_price();
-                    self.data_processor_writer.write(price);
+            // Wait out an open breaker before fetching anything
+            if let Err(wait) = self.breaker.try_acquire(Instant::now()) {
+                sleep(wait).await;
+                continue;
+            }
+            self.publish_breaker_state();
+
+            let fetched = select! {
+                fetched = self.source.next_prices() => fetched,
+                config = config_changed(&mut self.updates) => {
+                    self.source.reconfigure(&config);
+                    self.backoff.set_policy(config.retry_policy);
+                    self.breaker.set_policy(config.breaker_policy);
+                    for (symbol, writer) in &mut self.writers {
+                        writer.reconfigure(symbol, &config);
+                    }
+                    info!("Reconfigured {}", self.source.name());
+                    continue;
+                }
+            };
+            match fetched {
+                Ok(updates) => {
+                    self.fan_out(updates);
+                    self.backoff.on_success();
+                    if self.breaker.state() != BreakerState::Closed {
+                        info!("Circuit breaker for {} closed", self.source.name());
+                    }
+                    self.breaker.on_success();
+                    self.publish_breaker_state();
                 }
                 Err(e) => {
-                    // Log errors and continue
-                    error!("Error while fetching RPC data: [{:?}]", e);
-                    continue;
+                    // Log errors and retry after the backoff delay
+                    let now = Instant::now();
+                    let mut delay = self.backoff.on_failure();
+                    let streak = self.backoff.failure_streak();
+                    let degraded = self.backoff.policy().is_degraded(streak);
+
+                    let was_open = self.breaker.state() == BreakerState::Open;
+                    self.breaker.on_failure(now);
+                    if let Some(open_for) = self.breaker.remaining_open(now) {
+                        if !was_open {
+                            warn!(
+                                "Circuit breaker for {} opened for {:?}",
+                                self.source.name(),
+                                open_for
+                            );
+                        }
+                        delay = delay.max(open_for);
+                    }
+                    self.publish_breaker_state();
+                    for status in self.statuses.values() {
+                        status.record_failure(streak, delay, degraded, e.to_string());
+                    }
+
+                    error!(
+                        "Error while fetching from {} (failure #{}, retrying in {:?}): [{}]",
+                        self.source.name(),
+                        streak,
+                        delay,
+                        e
+                    );
+                    if degraded && streak == self.backoff.policy().max_failures {
+                        warn!(
+                            "Source {} marked degraded after {} failures",
+                            self.source.name(),
+                            streak
+                        );
+                    }
+                    sleep(delay).await;
                 }
             }
-            // Wait for the configured interval before the next request
-            ticker.tick().await;
         }
     }
 }
 
-/// Safety: `RpcManager` can be sent between threads since all its members are `Send`.
-unsafe impl<'a, T: DeserializeOwned + GetPrice> Send for RpcManager<'a, T> {}
+/// Waits for the next configuration on `updates`; never resolves without a
+/// channel or once its sender is gone.
+async fn config_changed(updates: &mut Option<ConfigUpdates>) -> Arc<AppConfig> {
+    if let Some(receiver) = updates {
+        if receiver.changed().await.is_ok() {
+            return receiver.borrow_and_update().clone();
+        }
+        *updates = None;
+    }
+    pending().await
+}
+
+#[cfg(test)]
+mod rpc_tests {
+    use super::*;
+    use crate::{
+        consensus::ConsensusMethod, dto::HealthResponse, source::ChannelSource, utils::now_millis,
+    };
+    use tokio::{sync::watch, time::timeout};
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: Duration::from_millis(5),
+            multiplier: 1.0,
+            max_delay: Duration::from_millis(5),
+            jitter: 0.0,
+            max_failures: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_seeds_and_streams_pushed_prices() {
+        let symbols = vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+        let (source, sender) = ChannelSource::new("push".into(), symbols, 16);
+        for (symbol, price) in [("BTCUSDT", 100.0), ("ETHUSDT", 10.0), ("BTCUSDT", 102.0)] {
+            sender
+                .send(PriceUpdate::new(symbol, price, None))
+                .await
+                .unwrap();
+        }
+
+        let handles = RpcManager::spawn(
+            Box::new(source),
+            &StatsConfig::new(2),
+            fast_retry(),
+            BreakerPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let readers: HashMap<_, _> = handles
+            .into_iter()
+            .map(|(symbol, reader, _)| (symbol, reader))
+            .collect();
+
+        // The third update is written by the spawned manager
+        timeout(Duration::from_secs(2), async {
+            while readers["BTCUSDT"].read().version < 1 {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let btc = readers["BTCUSDT"].read().data;
+        assert_eq!(btc.min, 100.0);
+        assert_eq!(btc.max, 102.0);
+        assert_eq!(readers["ETHUSDT"].read().data.max, 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_all_combines_shared_symbols() {
+        let (binance, binance_tx) = ChannelSource::new(
+            "binance".into(),
+            vec!["BTCUSDT".into(), "ETHUSDT".into()],
+            8,
+        );
+        let (okx, okx_tx) = ChannelSource::new("okx".into(), vec!["BTCUSDT".into()], 8);
+        let (bybit, bybit_tx) = ChannelSource::new("bybit".into(), vec!["BTCUSDT".into()], 8);
+        for (tx, symbol, price) in [
+            (&binance_tx, "BTCUSDT", 100.0),
+            (&binance_tx, "ETHUSDT", 10.0),
+            (&okx_tx, "BTCUSDT", 102.0),
+            (&bybit_tx, "BTCUSDT", 900.0),
+        ] {
+            tx.send(PriceUpdate::new(symbol, price, None))
+                .await
+                .unwrap();
+        }
+
+        let tracked = RpcManager::spawn_all(
+            vec![Box::new(binance), Box::new(okx), Box::new(bybit)],
+            &StatsConfig::new(2),
+            fast_retry(),
+            BreakerPolicy::default(),
+            ConsensusPolicy::default(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Median of the seeds
+        assert_eq!(tracked.readers["BTCUSDT"].read().data.max, 102.0);
+        assert_eq!(tracked.readers["ETHUSDT"].read().data.max, 10.0);
+        assert!(tracked.books.contains_key("BTCUSDT"));
+        assert!(!tracked.books.contains_key("ETHUSDT"));
+        assert!(tracked.statuses.contains_key("BTCUSDT@okx"));
+        assert!(tracked.statuses.contains_key("ETHUSDT"));
+
+        okx_tx
+            .send(PriceUpdate::new("BTCUSDT", 98.0, None))
+            .await
+            .unwrap();
+        timeout(Duration::from_secs(2), async {
+            while tracked.readers["BTCUSDT"].read().version < 1 {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let report = tracked.books["BTCUSDT"].report();
+        assert_eq!(report.price, 100.0);
+        let bybit = report.sources.iter().find(|s| s.source == "bybit").unwrap();
+        assert_eq!(bybit.weight, 0.0);
+        assert_eq!(bybit.deviation, 800.0);
+        assert_eq!(tracked.readers["BTCUSDT"].read().data.min, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_consensus_policy_reaches_books() {
+        let (binance, binance_tx) = ChannelSource::new("binance".into(), vec!["BTCUSDT".into()], 8);
+        let (okx, okx_tx) = ChannelSource::new("okx".into(), vec!["BTCUSDT".into()], 8);
+        for (tx, price) in [(&binance_tx, 100.0), (&okx_tx, 102.0)] {
+            tx.send(PriceUpdate::new("BTCUSDT", price, None))
+                .await
+                .unwrap();
+        }
+        let config = Arc::new(AppConfig::for_tests());
+        let (updates, _) = watch::channel(config.clone());
+
+        let tracked = RpcManager::spawn_all(
+            vec![Box::new(binance), Box::new(okx)],
+            &config.stats,
+            config.retry_policy,
+            config.breaker_policy,
+            config.consensus_policy,
+            Some(updates.subscribe()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tracked.books["BTCUSDT"].report().method,
+            ConsensusMethod::Median
+        );
+
+        updates.send_replace(Arc::new(AppConfig {
+            consensus_policy: ConsensusPolicy {
+                method: ConsensusMethod::Weighted,
+                ..ConsensusPolicy::default()
+            },
+            ..AppConfig::for_tests()
+        }));
+        timeout(Duration::from_secs(2), async {
+            while tracked.books["BTCUSDT"].report().method != ConsensusMethod::Weighted {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_closed_source_is_retried_and_reported() {
+        let (source, sender) = ChannelSource::new("push".into(), vec!["BTCUSDT".into()], 4);
+        sender
+            .send(PriceUpdate::new("BTCUSDT", 1.0, None))
+            .await
+            .unwrap();
+        drop(sender);
+
+        let handles = RpcManager::spawn(
+            Box::new(source),
+            &StatsConfig::new(2),
+            fast_retry(),
+            BreakerPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let status = handles[0].2.clone();
+
+        timeout(Duration::from_secs(2), async {
+            while !status.is_degraded() {
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(status.failure_streak() >= 2);
+        assert!(status.last_error().unwrap().contains("closed"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_state_is_reported_by_health() {
+        let (source, sender) = ChannelSource::new("push".into(), vec!["BTCUSDT".into()], 4);
+        sender
+            .send(PriceUpdate::new("BTCUSDT", 1.0, None))
+            .await
+            .unwrap();
+        drop(sender);
+
+        // A long backoff keeps the manager waiting for its first retry
+        let retry = RetryPolicy {
+            initial_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(60),
+            ..fast_retry()
+        };
+        let handles = RpcManager::spawn(
+            Box::new(source),
+            &StatsConfig::new(2),
+            retry,
+            BreakerPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let statuses = HashMap::from([("BTCUSDT".to_string(), handles[0].2.clone())]);
+
+        let report = timeout(Duration::from_secs(2), async {
+            loop {
+                let report = HealthResponse::from_sources(&statuses, 1.0);
+                if report.sources["BTCUSDT"].consecutive_failures > 0 {
+                    return report;
+                }
+                sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let btc = &report.sources["BTCUSDT"];
+        assert_eq!(btc.consecutive_failures, 1);
+        let next_retry_at = btc.next_retry_at.unwrap();
+        assert!(next_retry_at > now_millis() + 50_000);
+        assert!(btc.last_error.as_deref().unwrap().contains("closed"));
+    }
+}
@@ -0,0 +1,372 @@
+use std::{collections::HashMap, fmt, future::Future, pin::Pin};
+
+use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tracing::warn;
+
+use crate::{
+    batch_manager::BatchPoller,
+    config::AppConfig,
+    data_processor::DataProcessorWriter,
+    decimal::Decimal,
+    dto::{
+        BinancePrice, BinanceTicker, BinanceTrade, BybitTicker, CoinbaseTicker, KrakenTicker,
+        OkxTicker,
+    },
+    exchange::{Exchange, Provider, SourceUrl},
+    json_adapter::JsonPoller,
+    rpc_manager::HttpPoller,
+    utils::{is_stream_url, now_millis},
+    ws_manager::WsSource,
+};
+
+/// A single price observed by a [`PriceSource`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceUpdate {
+    /// Symbol the price belongs to, e.g. `"BTCUSDT"`
+    pub symbol: String,
+    /// Observed price
+    pub price: f64,
+    /// `price` exactly as the exchange sent it, if the source parses decimals
+    pub decimal: Option<Decimal>,
+    /// Exchange-provided event time in Unix milliseconds, if the source has one
+    pub event_time: Option<u64>,
+    /// Traded volume reported alongside the price, used to weight consensus quotes
+    pub volume: Option<f64>,
+    /// Quantity traded at `price`, if the update is a single trade; feeds candle volume
+    pub quantity: Option<f64>,
+    /// Local receive time in Unix milliseconds
+    pub received_at: u64,
+}
+
+impl PriceUpdate {
+    /// Creates an update received now.
+    pub fn new(symbol: impl Into<String>, price: f64, event_time: Option<u64>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            price,
+            decimal: None,
+            event_time,
+            volume: None,
+            quantity: None,
+            received_at: now_millis(),
+        }
+    }
+
+    /// Attaches the exact price; `price` should be its `f64` value.
+    pub fn with_decimal(mut self, decimal: Option<Decimal>) -> Self {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Attaches the traded volume reported with the price.
+    pub fn with_volume(mut self, volume: Option<f64>) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Attaches the quantity traded at the price.
+    pub fn with_quantity(mut self, quantity: Option<f64>) -> Self {
+        self.quantity = quantity;
+        self
+    }
+}
+
+/// Destination of the prices of one symbol.
+///
+/// `RpcManager` hands every update to the sink of its symbol: a
+/// `DataProcessorWriter` when the symbol has a single source, or a consensus
+/// input when several sources are combined.
+pub trait PriceSink: Send {
+    /// Consumes one update.
+    fn write(&self, update: &PriceUpdate);
+
+    /// Applies the settings of a reloaded configuration to the sink of `symbol`.
+    fn reconfigure(&mut self, _symbol: &str, _config: &AppConfig) {}
+}
+
+impl PriceSink for DataProcessorWriter {
+    fn write(&self, update: &PriceUpdate) {
+        // Time windows follow the exchange clock when the source reports one
+        let quantity = update.quantity.unwrap_or(0.0);
+        match update.decimal {
+            Some(price) => {
+                self.write_exact_update(price, update.received_at, update.event_time, quantity)
+            }
+            None => self.write_update(
+                update.price,
+                update.received_at,
+                update.event_time,
+                quantity,
+            ),
+        }
+    }
+
+    fn reconfigure(&mut self, symbol: &str, config: &AppConfig) {
+        DataProcessorWriter::reconfigure(self, &config.stats.for_symbol(symbol));
+    }
+}
+
+/// Error returned by a [`PriceSource`].
+#[derive(Debug)]
+pub enum SourceError {
+    /// HTTP request or response decoding failed
+    Http(reqwest::Error),
+    /// WebSocket connect or read failed
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+    /// The source ended (connection closed, channel dropped)
+    Closed(String),
+    /// A full snapshot did not contain these symbols
+    MissingSymbols(Vec<String>),
+    /// The response was received but the price could not be read from it
+    Decode(String),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "http error: {e}"),
+            Self::WebSocket(e) => write!(f, "websocket error: {e}"),
+            Self::Closed(reason) => write!(f, "source closed: {reason}"),
+            Self::MissingSymbols(symbols) => write!(f, "missing symbols: {symbols:?}"),
+            Self::Decode(reason) => write!(f, "decode error: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+impl From<reqwest::Error> for SourceError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for SourceError {
+    fn from(value: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(value)
+    }
+}
+
+/// Future returned by [`PriceSource::next_prices`].
+pub type SourceFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<PriceUpdate>, SourceError>> + Send + 'a>>;
+
+/// Anything that produces prices for one or more symbols.
+///
+/// A source paces itself: a poller waits for its interval inside `next_prices`,
+/// a stream waits for the next message. Retries, the circuit breaker and writing
+/// into `DataProcessorWriter`s are handled by `RpcManager`, so new sources
+/// (WebSocket, file replay, push-based) only implement fetching.
+pub trait PriceSource: Send {
+    /// Identifier used in logs, usually the URL.
+    fn name(&self) -> &str;
+
+    /// Symbols this source produces prices for.
+    fn symbols(&self) -> &[String];
+
+    /// Returns `true` if every successful `next_prices` call is a full snapshot of
+    /// all `symbols`, so an absent symbol should be reported as missing.
+    fn expects_all_symbols(&self) -> bool {
+        false
+    }
+
+    /// Waits for and returns the next prices. An error is retried by the caller
+    /// after a backoff; the source must be able to recover on the next call
+    /// (e.g. by reconnecting).
+    fn next_prices(&mut self) -> SourceFuture<'_>;
+
+    /// Applies a reloaded configuration, e.g. a new poll interval or HTTP timeout.
+    /// Sources without such settings ignore it.
+    fn reconfigure(&mut self, _config: &AppConfig) {}
+}
+
+/// Push-based source fed through a channel.
+///
+/// Useful for embedding the service or for tests; the source ends once every
+/// [`Sender`] has been dropped.
+pub struct ChannelSource {
+    name: String,
+    symbols: Vec<String>,
+    receiver: Receiver<PriceUpdate>,
+}
+
+impl ChannelSource {
+    /// Creates a source for `symbols` and the sender that feeds it.
+    pub fn new(name: String, symbols: Vec<String>, capacity: usize) -> (Self, Sender<PriceUpdate>) {
+        let (sender, receiver) = channel(capacity);
+        let source = Self {
+            name,
+            symbols,
+            receiver,
+        };
+        (source, sender)
+    }
+}
+
+impl PriceSource for ChannelSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    fn next_prices(&mut self) -> SourceFuture<'_> {
+        Box::pin(async move {
+            match self.receiver.recv().await {
+                Some(update) => Ok(vec![update]),
+                None => Err(SourceError::Closed("all senders dropped".into())),
+            }
+        })
+    }
+}
+
+/// Builds one source per configured URL.
+///
+/// - `ws://` / `wss://` URLs become a [`WsSource`] (`@ticker` streams parse
+///   `BinanceTicker`, anything else `BinanceTrade`)
+/// - with `batch_poll`, Binance REST URLs sharing an endpoint become one [`BatchPoller`],
+///   except for symbols polled at their own interval
+/// - URLs of a JSON provider become a [`JsonPoller`] with the provider's adapter
+/// - any other URL becomes an [`HttpPoller`] of its exchange's ticker response
+///
+/// Pollers wait for the interval of their symbol (`AppConfig::interval_for`).
+///
+/// URLs without an extractable symbol, and stream URLs of exchanges other than
+/// Binance, are skipped. JSON provider URLs may omit the symbol if the adapter
+/// reads it from the response.
+pub fn build_sources(config: &AppConfig) -> Vec<Box<dyn PriceSource>> {
+    let mut sources: Vec<Box<dyn PriceSource>> = Vec::with_capacity(config.urls.len());
+    let mut batched: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for source_url in &config.urls {
+        let url = &source_url.url;
+        if config.batch_poll
+            && matches!(source_url.provider, Provider::Exchange(Exchange::Binance))
+            && !is_stream_url(url)
+            && let Some(symbol) = Exchange::Binance.extract_symbol(url)
+            && !config.symbol_intervals.contains_key(&symbol)
+            && let Some((base, _)) = url.split_once('?')
+        {
+            batched.entry(base).or_default().push(symbol);
+            continue;
+        }
+        sources.extend(build_source(config, source_url));
+    }
+
+    for (base, symbols) in batched {
+        sources.push(Box::new(BatchPoller::new(
+            config.interval,
+            base,
+            symbols,
+            config.client.clone(),
+        )));
+    }
+    sources
+}
+
+/// Builds the source of a single URL, as [`build_sources`] does without batching.
+///
+/// Returns `None`, after logging why, for URLs without an extractable symbol and
+/// stream URLs of exchanges other than Binance.
+pub fn build_source(config: &AppConfig, source_url: &SourceUrl) -> Option<Box<dyn PriceSource>> {
+    let url = &source_url.url;
+    let exchange = match &source_url.provider {
+        Provider::Exchange(exchange) => *exchange,
+        Provider::Json(name) => {
+            let Some(adapter) = config.providers.get(name) else {
+                warn!("Skipping URL of unknown provider {}: {}", name, url);
+                return None;
+            };
+            let symbol = source_url.symbol();
+            if symbol.is_none() && adapter.symbol.is_none() {
+                warn!("Skipping {} URL without a symbol: {}", name, url);
+                return None;
+            }
+            let interval = symbol
+                .as_deref()
+                .map_or(config.interval, |s| config.interval_for(s));
+            return Some(Box::new(JsonPoller::new(
+                interval,
+                url.clone(),
+                symbol,
+                adapter.clone(),
+                config.client.clone(),
+            )));
+        }
+    };
+
+    let Some(symbol) = exchange.extract_symbol(url) else {
+        warn!("Skipping {} URL without a symbol: {}", exchange, url);
+        return None;
+    };
+
+    if is_stream_url(url) {
+        if exchange != Exchange::Binance {
+            warn!("Skipping unsupported {} stream: {}", exchange, url);
+            return None;
+        }
+        let idle_timeout = config.ws_idle_timeout;
+        if url.ends_with("@ticker") {
+            return Some(Box::new(
+                WsSource::<BinanceTicker>::new(url.clone(), symbol).with_idle_timeout(idle_timeout),
+            ));
+        }
+        return Some(Box::new(
+            WsSource::<BinanceTrade>::new(url.clone(), symbol).with_idle_timeout(idle_timeout),
+        ));
+    }
+
+    Some(http_poller(exchange, config, url.clone(), symbol))
+}
+
+/// REST poller parsing the ticker response of `exchange`.
+fn http_poller(
+    exchange: Exchange,
+    config: &AppConfig,
+    url: String,
+    symbol: String,
+) -> Box<dyn PriceSource> {
+    let client = config.client.clone();
+    let interval = config.interval_for(&symbol);
+    match exchange {
+        Exchange::Binance => Box::new(HttpPoller::<BinancePrice>::new(
+            interval, url, symbol, client,
+        )),
+        Exchange::Coinbase => Box::new(HttpPoller::<CoinbaseTicker>::new(
+            interval, url, symbol, client,
+        )),
+        Exchange::Kraken => Box::new(HttpPoller::<KrakenTicker>::new(
+            interval, url, symbol, client,
+        )),
+        Exchange::Okx => Box::new(HttpPoller::<OkxTicker>::new(interval, url, symbol, client)),
+        Exchange::Bybit => Box::new(HttpPoller::<BybitTicker>::new(
+            interval, url, symbol, client,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod source_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_channel_source_yields_pushed_updates_then_closes() {
+        let (mut source, sender) = ChannelSource::new("push".into(), vec!["BTCUSDT".into()], 4);
+        sender
+            .send(PriceUpdate::new("BTCUSDT", 1.5, Some(7)))
+            .await
+            .unwrap();
+        drop(sender);
+
+        let updates = source.next_prices().await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].price, 1.5);
+        assert_eq!(updates[0].event_time, Some(7));
+        assert!(matches!(
+            source.next_prices().await,
+            Err(SourceError::Closed(_))
+        ));
+    }
+}
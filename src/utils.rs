@@ -1,4 +1,6 @@
-use std::mem::transmute;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::time::{Instant, Interval, MissedTickBehavior, interval_at};
 
 /// Incrementally calculates the mean of a data stream.
 ///
@@ -74,7 +76,7 @@ pub const fn bound_index(idx: usize, bound_by: usize) -> usize {
 /// all-ones (`usize::MAX`) or all-zeros masks in a branchless way.
 ///
 /// # Safety
-/// This uses [`std::mem::transmute`] under the hood, but it is safe here
+/// This uses [`isize::cast_unsigned`] under the hood, which is a pure bit cast
 /// because `isize` and `usize` are guaranteed to have the same size
 /// on all Rust-supported targets.
 ///
@@ -91,11 +93,126 @@ pub const fn bound_index(idx: usize, bound_by: usize) -> usize {
 /// assert_eq!(isize2usize(-1), usize::MAX); // all bits set
 /// ```
 pub const fn isize2usize(val: isize) -> usize {
-    unsafe { transmute(val) }
+    val.cast_unsigned()
+}
+
+/// Advances a xorshift64 state and returns the next pseudo-random value.
+///
+/// Not cryptographically secure; only meant for cheap jitter where pulling
+/// in a full RNG crate is not worth it. The state must be non-zero,
+/// otherwise the generator keeps returning `0`.
+///
+/// # Example
+/// ```
+/// use aboss_task::utils::xorshift64;
+/// let mut state = 0x2545_F491_4F6C_DD1D;
+/// let a = xorshift64(&mut state);
+/// let b = xorshift64(&mut state);
+/// assert_ne!(a, b);
+/// ```
+pub const fn xorshift64(state: &mut u64) -> u64 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    x
+}
+
+/// Running sum with Neumaier (improved Kahan) compensation.
+///
+/// Keeps the low-order bits lost by each addition in a separate compensation
+/// term, so the error of the sum stays bounded by a few ulps however many values
+/// are added and removed (removing is adding the negation). A naive running sum
+/// instead accumulates one rounding error per update, which adds up over millions
+/// of updates and is made worse by large values entering and leaving.
+///
+/// # Example
+/// ```
+/// use aboss_task::utils::NeumaierSum;
+/// let mut sum = NeumaierSum::default();
+/// for x in [1e16, 1.0, -1e16] {
+///     sum.add(x);
+/// }
+/// assert_eq!(sum.value(), 1.0); // a naive sum returns 0.0
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NeumaierSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl NeumaierSum {
+    /// Adds `x` to the sum.
+    pub fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        // Recover the bits of the smaller operand lost in `t`
+        self.compensation += if self.sum.abs() >= x.abs() {
+            (self.sum - t) + x
+        } else {
+            (x - t) + self.sum
+        };
+        self.sum = t;
+    }
+
+    /// Adds every term of `other`, keeping the bits its compensation holds.
+    pub fn add_sum(&mut self, other: &Self) {
+        self.add(other.sum);
+        self.add(other.compensation);
+    }
+
+    /// Removes every term of `other` added before, e.g. when it leaves a window.
+    pub fn sub_sum(&mut self, other: &Self) {
+        self.add(-other.sum);
+        self.add(-other.compensation);
+    }
+
+    /// The compensated sum.
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Milliseconds since the Unix epoch for `time`, or `0` if it lies before it.
+pub fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    unix_millis(SystemTime::now())
+}
+
+/// Makes `ticker` tick every `period`, the next tick one period from now.
+///
+/// Used when a reloaded configuration changes a poll interval; a ticker already
+/// at `period` keeps its cadence.
+pub fn retune_ticker(ticker: &mut Interval, period: Duration) {
+    if ticker.period() == period {
+        return;
+    }
+    let mut retuned = interval_at(Instant::now() + period, period);
+    retuned.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    *ticker = retuned;
+}
+
+/// Returns `true` for WebSocket stream URLs (`ws://` / `wss://`).
+pub fn is_stream_url(url: &str) -> bool {
+    url.starts_with("ws://") || url.starts_with("wss://")
 }
 
 /// Used to extract Symbol
+///
+/// REST URLs carry it as `symbol=BTCUSDT`; Binance stream URLs as the lowercase
+/// `btcusdt@trade` path segment, which is returned uppercased.
 pub fn extract_symbol(url: &str) -> Option<String> {
+    if is_stream_url(url) {
+        let stream = url.rsplit('/').next()?;
+        let (symbol, _) = stream.split_once('@')?;
+        return (!symbol.is_empty()).then(|| symbol.to_uppercase());
+    }
     url.split("symbol=").nth(1).map(|s| s.to_string())
 }
 
@@ -148,6 +265,46 @@ mod tests {
         assert_eq!(isize2usize(isize::MAX), isize::MAX as usize);
     }
 
+    #[test]
+    fn test_neumaier_sum_keeps_small_values_next_to_large_ones() {
+        let mut sum = NeumaierSum::default();
+        for _ in 0..1_000 {
+            sum.add(1e9);
+            sum.add(0.1);
+            sum.add(-1e9);
+        }
+        assert!((sum.value() - 100.0).abs() < 1e-9, "{}", sum.value());
+    }
+
+    #[test]
+    fn test_xorshift64_is_deterministic_and_non_zero() {
+        let mut a = 42;
+        let mut b = 42;
+        for _ in 0..1000 {
+            let x = xorshift64(&mut a);
+            assert_eq!(x, xorshift64(&mut b));
+            assert_ne!(x, 0);
+        }
+    }
+
+    #[test]
+    fn test_extract_symbol() {
+        assert_eq!(
+            extract_symbol("https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT").as_deref(),
+            Some("BTCUSDT")
+        );
+        assert_eq!(
+            extract_symbol("wss://stream.binance.com:9443/ws/ethusdt@trade").as_deref(),
+            Some("ETHUSDT")
+        );
+        assert_eq!(
+            extract_symbol("ws://127.0.0.1:9000/ws/solusdt@ticker").as_deref(),
+            Some("SOLUSDT")
+        );
+        assert_eq!(extract_symbol("wss://stream.binance.com:9443/ws/"), None);
+        assert_eq!(extract_symbol("https://example.com/price"), None);
+    }
+
     #[test]
     fn test_isize2usize_negative() {
         assert_eq!(isize2usize(-1), usize::MAX);
@@ -0,0 +1,463 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    mem::{size_of, transmute},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::utils::NeumaierSum;
+
+/// Default time windows tracked per symbol when `WINDOWS` is not set.
+pub const DEFAULT_WINDOWS: &str = "1m,5m,1h,24h";
+
+/// Minimum or maximum of a sliding sequence of keyed values.
+///
+/// Keys must be pushed in non-decreasing order (data point counts or sample times).
+/// Values that can no longer become the extreme are dropped on push, so each value
+/// is pushed and popped at most once (amortized O(1)) and the front is always the
+/// extreme of the values still in the window.
+pub(crate) struct MonotonicDeque {
+    /// `(key, value)` candidates, front is the extreme
+    entries: VecDeque<(u64, f64)>,
+    /// Track the maximum instead of the minimum
+    is_max: bool,
+}
+
+impl MonotonicDeque {
+    /// Creates an empty deque tracking the maximum if `is_max`, else the minimum.
+    pub(crate) fn new(is_max: bool, capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            is_max,
+        }
+    }
+
+    /// Adds `value` under `key`, dropping the candidates it beats.
+    pub(crate) fn push(&mut self, key: u64, value: f64) {
+        while let Some(&(_, back)) = self.entries.back()
+            && (if self.is_max {
+                back <= value
+            } else {
+                back >= value
+            })
+        {
+            self.entries.pop_back();
+        }
+        self.entries.push_back((key, value));
+    }
+
+    /// Removes every value pushed under a key smaller than `key`.
+    pub(crate) fn evict_before(&mut self, key: u64) {
+        while let Some(&(front, _)) = self.entries.front()
+            && front < key
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Extreme of the remaining values.
+    pub(crate) fn front(&self) -> Option<f64> {
+        self.entries.front().map(|(_, value)| *value)
+    }
+}
+
+/// Length of a time-based rolling window, e.g. `5m`.
+///
+/// Parsed from a number followed by `ms`, `s`, `m`, `h` or `d`; displayed with the
+/// largest of `ms`, `s`, `m` and `h` that divides it, so `24h` stays `24h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WindowSpec(Duration);
+
+impl WindowSpec {
+    /// Window covering `duration`, which must not be zero.
+    pub fn new(duration: Duration) -> Self {
+        assert!(!duration.is_zero(), "window must be > 0");
+        Self(duration)
+    }
+
+    /// Length of the window.
+    pub fn duration(self) -> Duration {
+        self.0
+    }
+
+    /// Length of the window in milliseconds.
+    pub fn millis(self) -> u64 {
+        self.0.as_millis() as u64
+    }
+}
+
+impl fmt::Display for WindowSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = self.millis();
+        match ms {
+            ms if ms.is_multiple_of(3_600_000) => write!(f, "{}h", ms / 3_600_000),
+            ms if ms.is_multiple_of(60_000) => write!(f, "{}m", ms / 60_000),
+            ms if ms.is_multiple_of(1_000) => write!(f, "{}s", ms / 1_000),
+            ms => write!(f, "{ms}ms"),
+        }
+    }
+}
+
+impl FromStr for WindowSpec {
+    type Err = String;
+
+    /// Parses `500ms`, `30s`, `5m`, `1h` or `1d`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("window `{s}` has no unit (ms, s, m, h, d)"))?;
+        let (value, unit) = s.split_at(split);
+        let value: u64 = value.parse().map_err(|_| format!("invalid window `{s}`"))?;
+        let unit_ms = match unit {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            _ => return Err(format!("unknown unit in window `{s}`")),
+        };
+        match value.checked_mul(unit_ms) {
+            Some(0) => Err(format!("window `{s}` must be > 0")),
+            Some(ms) => Ok(Self(Duration::from_millis(ms))),
+            None => Err(format!("window `{s}` is too large")),
+        }
+    }
+}
+
+/// Statistics of the samples of one time window, as of the latest sample.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowStats {
+    /// Mean price
+    pub mean: f64,
+    /// Lowest price
+    pub min: f64,
+    /// Highest price
+    pub max: f64,
+    /// Oldest price still in the window
+    pub first: f64,
+    /// Latest price
+    pub last: f64,
+    /// Change from `first` to `last` in percent
+    pub change_pct: f64,
+    /// Number of samples
+    pub count: u64,
+}
+
+/// Number of 64-bit words `WindowStats` occupies inside the seqlock payload.
+pub(crate) const WINDOW_STATS_WORDS: usize = size_of::<WindowStats>() / size_of::<u64>();
+
+// Every field of `WindowStats` is 8 bytes wide, so it maps exactly onto `u64` words
+const _: () = assert!(size_of::<WindowStats>().is_multiple_of(size_of::<u64>()));
+
+impl WindowStats {
+    #[inline(always)]
+    pub(crate) fn to_words(self) -> [u64; WINDOW_STATS_WORDS] {
+        // SAFETY: WindowStats is `repr(C)` and made only of 8-byte plain-old-data fields
+        unsafe { transmute::<WindowStats, [u64; WINDOW_STATS_WORDS]>(self) }
+    }
+
+    #[inline(always)]
+    pub(crate) fn from_words(words: [u64; WINDOW_STATS_WORDS]) -> Self {
+        // SAFETY: every bit pattern is a valid `f64` / `u64`
+        unsafe { transmute::<[u64; WINDOW_STATS_WORDS], WindowStats>(words) }
+    }
+}
+
+/// Number of buckets the samples of a time window are aggregated into.
+pub(crate) const WINDOW_BUCKETS: u64 = 256;
+
+/// Aggregate of the samples taken during one bucket of a time window.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Start of the bucket divided by the bucket width
+    key: u64,
+    /// Oldest price of the bucket
+    first: f64,
+    /// Latest price of the bucket
+    last: f64,
+    /// Lowest price of the bucket
+    min: f64,
+    /// Highest price of the bucket
+    max: f64,
+    /// Sum of the prices of the bucket
+    sum: NeumaierSum,
+    /// Number of samples in the bucket
+    count: u64,
+}
+
+impl Bucket {
+    /// A bucket holding the single sample `price`.
+    fn sample(key: u64, price: f64) -> Self {
+        let mut sum = NeumaierSum::default();
+        sum.add(price);
+        Self {
+            key,
+            first: price,
+            last: price,
+            min: price,
+            max: price,
+            sum,
+            count: 1,
+        }
+    }
+}
+
+/// Samples of the last `spec` milliseconds and their running statistics.
+///
+/// Samples are aggregated into [`WINDOW_BUCKETS`] buckets of equal width, so the
+/// memory of a window does not depend on the sample rate. A bucket leaves the
+/// window once it starts before `latest - spec`: the window covers at most `spec`
+/// and at least `spec` minus one bucket, and always includes the latest sample.
+/// Buckets are only evicted on push: the window follows sample timestamps (often
+/// exchange event times), not the wall clock, and readers only see the published
+/// `WindowStats`. Each push is amortized O(1).
+pub(crate) struct TimeWindow {
+    /// Window length in milliseconds
+    span: u64,
+    /// Bucket width in milliseconds
+    width: u64,
+    /// Buckets holding samples, oldest first
+    buckets: VecDeque<Bucket>,
+    /// Compensated sum of the prices in `buckets`, so the mean does not drift
+    sum: NeumaierSum,
+    /// Number of samples in `buckets`
+    count: u64,
+    /// Minimum of the prices in `buckets`, keyed by bucket
+    min: MonotonicDeque,
+    /// Maximum of the prices in `buckets`, keyed by bucket
+    max: MonotonicDeque,
+}
+
+impl TimeWindow {
+    /// Creates the window holding the seed sample `price` at `at`.
+    pub(crate) fn new(spec: WindowSpec, at: u64, price: f64) -> Self {
+        let mut window = Self::empty(spec);
+        window.push(at, price);
+        window
+    }
+
+    fn empty(spec: WindowSpec) -> Self {
+        let span = spec.millis();
+        let capacity = WINDOW_BUCKETS as usize + 1;
+        Self {
+            span,
+            width: span.div_ceil(WINDOW_BUCKETS),
+            buckets: VecDeque::with_capacity(capacity),
+            sum: NeumaierSum::default(),
+            count: 0,
+            min: MonotonicDeque::new(false, capacity),
+            max: MonotonicDeque::new(true, capacity),
+        }
+    }
+
+    /// A window of `spec` holding the samples of this one, as of the latest sample
+    /// taken at `at`.
+    ///
+    /// Samples are moved to the bucket their bucket of this window starts in, so
+    /// a narrower window only gets as precise as the buckets of this one.
+    pub(crate) fn resized(&self, spec: WindowSpec, at: u64) -> Self {
+        let mut window = Self::empty(spec);
+        for bucket in &self.buckets {
+            window.merge(Bucket {
+                key: bucket.key * self.width / window.width,
+                ..*bucket
+            });
+        }
+        window.evict(at);
+        window
+    }
+
+    /// Adds the sample `price` at `at` and drops the buckets that left the window.
+    ///
+    /// `at` must not be older than the previous sample.
+    pub(crate) fn push(&mut self, at: u64, price: f64) -> WindowStats {
+        self.merge(Bucket::sample(at / self.width, price));
+        self.evict(at);
+        self.stats()
+    }
+
+    /// Adds the samples of `bucket`, which must not be older than the latest one.
+    fn merge(&mut self, bucket: Bucket) {
+        self.sum.add_sum(&bucket.sum);
+        self.count += bucket.count;
+        let key = bucket.key;
+        match self.buckets.back_mut() {
+            Some(last) if last.key == key => {
+                last.last = bucket.last;
+                last.sum.add_sum(&bucket.sum);
+                last.count += bucket.count;
+                // Only a new extreme of the bucket can become the window's
+                if bucket.min < last.min {
+                    last.min = bucket.min;
+                    self.min.push(key, bucket.min);
+                }
+                if bucket.max > last.max {
+                    last.max = bucket.max;
+                    self.max.push(key, bucket.max);
+                }
+            }
+            _ => {
+                self.buckets.push_back(bucket);
+                self.min.push(key, bucket.min);
+                self.max.push(key, bucket.max);
+            }
+        }
+    }
+
+    /// Drops the buckets starting before the window ending at `at` begins.
+    fn evict(&mut self, at: u64) {
+        let start = (at + 1).saturating_sub(self.span).div_ceil(self.width);
+        while let Some(bucket) = self.buckets.front()
+            && bucket.key < start
+        {
+            self.sum.sub_sum(&bucket.sum);
+            self.count -= bucket.count;
+            self.buckets.pop_front();
+        }
+        self.min.evict_before(start);
+        self.max.evict_before(start);
+    }
+
+    /// Statistics of the samples currently in the window.
+    pub(crate) fn stats(&self) -> WindowStats {
+        let first = self.buckets.front().map_or(0.0, |b| b.first);
+        let last = self.buckets.back().map_or(0.0, |b| b.last);
+        WindowStats {
+            mean: self.sum.value() / self.count as f64,
+            min: self.min.front().unwrap_or(last),
+            max: self.max.front().unwrap_or(last),
+            first,
+            last,
+            change_pct: (last - first) / first * 100.0,
+            count: self.count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod window_tests {
+    use super::*;
+
+    #[test]
+    fn test_window_spec_parse_and_display() {
+        assert_eq!("5m".parse(), Ok(WindowSpec::new(Duration::from_secs(300))));
+        assert_eq!("1d".parse::<WindowSpec>().unwrap().to_string(), "24h");
+        assert_eq!("90s".parse::<WindowSpec>().unwrap().to_string(), "90s");
+        assert_eq!("120s".parse::<WindowSpec>().unwrap().to_string(), "2m");
+        assert_eq!("250ms".parse::<WindowSpec>().unwrap().to_string(), "250ms");
+        assert!("5".parse::<WindowSpec>().is_err());
+        assert!("0m".parse::<WindowSpec>().is_err());
+        assert!("5w".parse::<WindowSpec>().is_err());
+        assert!("m".parse::<WindowSpec>().is_err());
+    }
+
+    #[test]
+    fn test_time_window_matches_naive_filter() {
+        let spec: WindowSpec = "10s".parse().unwrap();
+        let width = spec.millis().div_ceil(WINDOW_BUCKETS);
+        let mut window = TimeWindow::new(spec, 0, 100.0);
+        let mut all = vec![(0u64, 100.0)];
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut at = 0;
+        for _ in 0..3000 {
+            // Irregular gaps, including repeated timestamps and long pauses
+            let r = crate::utils::xorshift64(&mut state);
+            at += match r % 10 {
+                0 => 0,
+                1 => 12_000,
+                _ => r % 900,
+            };
+            let price = 100.0 + (r % 2000) as f64 / 100.0;
+            all.push((at, price));
+            let stats = window.push(at, price);
+
+            // Whole buckets starting at most `spec` before the latest sample
+            let start = (at + 1).saturating_sub(spec.millis()).div_ceil(width);
+            let inside: Vec<f64> = all
+                .iter()
+                .filter(|(t, _)| t / width >= start)
+                .map(|(_, p)| *p)
+                .collect();
+            let mean = inside.iter().sum::<f64>() / inside.len() as f64;
+            assert_eq!(stats.count, inside.len() as u64);
+            assert!((stats.mean - mean).abs() < 1e-9, "{stats:?} vs {mean}");
+            assert_eq!(
+                stats.min,
+                inside.iter().copied().fold(f64::INFINITY, f64::min)
+            );
+            assert_eq!(
+                stats.max,
+                inside.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+            );
+            assert_eq!(stats.first, inside[0]);
+            assert_eq!(stats.last, price);
+        }
+    }
+
+    #[test]
+    fn test_change_pct_and_expiry() {
+        let mut window = TimeWindow::new("1m".parse().unwrap(), 1_000, 200.0);
+        let stats = window.push(31_000, 210.0);
+        assert_eq!(stats.count, 2);
+        assert!((stats.change_pct - 5.0).abs() < 1e-12);
+
+        // The seed is exactly one minute old and drops out
+        let stats = window.push(61_000, 190.0);
+        assert_eq!((stats.count, stats.first, stats.max), (2, 210.0, 210.0));
+
+        // A long pause leaves only the latest sample
+        let stats = window.push(1_000_000, 180.0);
+        assert_eq!(stats.count, 1);
+        assert_eq!(
+            (stats.mean, stats.min, stats.change_pct),
+            (180.0, 180.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_buckets_bound_memory() {
+        // A steadily rising price at 1kHz would keep every sample as a minimum candidate
+        let mut window = TimeWindow::new("1m".parse().unwrap(), 0, 0.0);
+        for at in 1..=600_000u64 {
+            window.push(at, at as f64);
+        }
+        assert!(window.buckets.len() <= WINDOW_BUCKETS as usize);
+        assert!(window.min.entries.len() <= WINDOW_BUCKETS as usize);
+        let stats = window.stats();
+        assert_eq!(stats.max, 600_000.0);
+        assert!(stats.count <= 60_000 && stats.count > 59_000);
+        assert_eq!(stats.min, (600_001 - stats.count) as f64);
+    }
+
+    #[test]
+    fn test_mean_does_not_drift() {
+        let mut window = TimeWindow::new("1s".parse().unwrap(), 0, 1e16);
+        for at in 1..100 {
+            window.push(at, 1.0);
+        }
+        // Evicting the spike leaves the exact sum of the ones, which a plain sum loses
+        let stats = window.push(1_000, 1.0);
+        assert_eq!((stats.count, stats.mean, stats.max), (97, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_resized_window_keeps_buckets() {
+        let mut window = TimeWindow::new("5m".parse().unwrap(), 0, 100.0);
+        for i in 1..=600u64 {
+            window.push(i * 1_000, 100.0 + i as f64);
+        }
+        let at = 600_000;
+        let same = window.resized("5m".parse().unwrap(), at);
+        assert_eq!(same.stats(), window.stats());
+
+        // One minute of 5m buckets, the oldest one dropped if it started too early
+        let one = window.resized("1m".parse().unwrap(), at).stats();
+        assert_eq!((one.last, one.max), (700.0, 700.0));
+        assert!((59..=60).contains(&one.count), "{one:?}");
+        assert_eq!(one.first, one.min);
+        assert_eq!(one.first, 701.0 - one.count as f64);
+    }
+}
@@ -0,0 +1,320 @@
+use std::{marker::PhantomData, time::Duration};
+
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Error, Message},
+};
+use tracing::{info, warn};
+
+use crate::{
+    config::AppConfig,
+    dto::GetPrice,
+    source::{PriceSource, PriceUpdate, SourceError, SourceFuture},
+};
+
+/// Default time in milliseconds a stream may stay silent before it is reconnected.
+pub const DEFAULT_WS_IDLE_TIMEOUT: u64 = 30_000;
+
+/// WebSocket connection used by [`WsSource`].
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Consumes a Binance-style WebSocket stream (`<symbol>@trade`, `<symbol>@ticker`).
+///
+/// Unlike `HttpPoller`, which polls on a fixed interval, this sees every message the
+/// exchange pushes. When the connection drops, `next_prices` returns the error and
+/// the following call reconnects, so `RpcManager`'s backoff paces the reconnects.
+/// A connection that stalls without closing is treated the same way once no frame
+/// (data, ping or pong) arrived for the idle timeout.
+///
+/// # Type Parameters
+/// - `T`: The message type of the stream, which must implement `DeserializeOwned`
+///   and `GetPrice`.
+pub struct WsSource<T>
+where
+    T: DeserializeOwned + GetPrice,
+{
+    /// Stream URL, e.g. `wss://stream.binance.com:9443/ws/btcusdt@trade`.
+    url: String,
+
+    /// Symbol of the stream, as a one-element list for `PriceSource::symbols`.
+    symbols: [String; 1],
+
+    /// Open connection, `None` until connected or after it dropped.
+    stream: Option<WsStream>,
+
+    /// Longest silence before the connection is considered dead.
+    idle_timeout: Duration,
+
+    /// Phantom data to tie the generic message type to this struct.
+    _message_phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<MessageType> WsSource<MessageType>
+where
+    MessageType: DeserializeOwned + GetPrice,
+{
+    /// Constructs a new `WsSource`; the connection is opened on first use.
+    ///
+    /// # Parameters
+    /// - `url`: WebSocket stream URL (`ws://` or `wss://`).
+    /// - `symbol`: Symbol the stream reports.
+    pub fn new(url: String, symbol: String) -> Self {
+        Self {
+            url,
+            symbols: [symbol],
+            stream: None,
+            idle_timeout: Duration::from_millis(DEFAULT_WS_IDLE_TIMEOUT),
+            _message_phantom_data: PhantomData,
+        }
+    }
+
+    /// Reconnects once the stream stayed silent for `idle_timeout`.
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Opens a WebSocket connection to `url`.
+    pub async fn connect(url: &str) -> Result<WsStream, Error> {
+        let (stream, _response) = connect_async(url).await?;
+        Ok(stream)
+    }
+
+    /// Reads messages until one deserializes into `MessageType`.
+    ///
+    /// Control frames and messages of another shape (e.g. subscription acks) are
+    /// skipped. Returns `Ok(None)` once the server closes the connection, and
+    /// `SourceError::Closed` if no frame arrives for `idle_timeout`.
+    pub async fn next_message(
+        stream: &mut WsStream,
+        idle_timeout: Duration,
+    ) -> Result<Option<MessageType>, SourceError> {
+        loop {
+            let Ok(msg) = timeout(idle_timeout, stream.next()).await else {
+                return Err(SourceError::Closed(format!(
+                    "no message for {idle_timeout:?}"
+                )));
+            };
+            let Some(msg) = msg else {
+                return Ok(None);
+            };
+            let text = match msg? {
+                Message::Text(text) => text,
+                Message::Close(_) => return Ok(None),
+                // Pings are answered by tungstenite while reading
+                _ => continue,
+            };
+            match serde_json::from_str::<MessageType>(&text) {
+                Ok(parsed) => return Ok(Some(parsed)),
+                Err(e) => warn!("Skipping unparsable stream message [{}]: {:?}", text, e),
+            }
+        }
+    }
+}
+
+impl<MessageType> PriceSource for WsSource<MessageType>
+where
+    MessageType: DeserializeOwned + GetPrice,
+{
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    fn next_prices(&mut self) -> SourceFuture<'_> {
+        Box::pin(async move {
+            let stream = match self.stream.as_mut() {
+                Some(stream) => stream,
+                None => {
+                    let stream = Self::connect(&self.url).await?;
+                    info!("Streaming prices from {}", self.url);
+                    self.stream.insert(stream)
+                }
+            };
+
+            match Self::next_message(stream, self.idle_timeout).await {
+                Ok(Some(message)) => Ok(vec![
+                    PriceUpdate::new(
+                        self.symbols[0].clone(),
+                        message.get_price(),
+                        message.get_event_time(),
+                    )
+                    .with_decimal(message.get_decimal())
+                    .with_volume(message.get_volume())
+                    .with_quantity(message.get_quantity()),
+                ]),
+                // Drop the connection so the next call reconnects
+                Ok(None) => {
+                    self.stream = None;
+                    Err(SourceError::Closed("connection closed by server".into()))
+                }
+                Err(e) => {
+                    self.stream = None;
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn reconfigure(&mut self, config: &AppConfig) {
+        self.idle_timeout = config.ws_idle_timeout;
+    }
+}
+
+#[cfg(test)]
+mod ws_tests {
+    use super::*;
+    use crate::{
+        circuit_breaker::BreakerPolicy, data_processor::StatsConfig, dto::BinanceTrade,
+        retry::RetryPolicy, rpc_manager::RpcManager,
+    };
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio::{
+        net::TcpListener,
+        time::{sleep, timeout},
+    };
+    use tokio_tungstenite::accept_async;
+
+    fn trade(price: &str) -> Message {
+        Message::text(format!(
+            r#"{{"e":"trade","E":1,"s":"BTCUSDT","t":1,"p":"{price}","q":"1","T":1,"m":true,"M":true}}"#
+        ))
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: Duration::from_millis(10),
+            multiplier: 1.0,
+            max_delay: Duration::from_millis(10),
+            jitter: 0.0,
+            max_failures: 3,
+        }
+    }
+
+    /// Serves `sessions` connections in turn, sending each session's messages then closing.
+    async fn serve(sessions: Vec<Vec<Message>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for messages in sessions {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(tcp).await.unwrap();
+                for msg in messages {
+                    ws.send(msg).await.unwrap();
+                }
+                ws.close(None).await.ok();
+            }
+        });
+        format!("ws://{addr}/ws/btcusdt@trade")
+    }
+
+    #[tokio::test]
+    async fn test_next_message_skips_unrelated_messages() {
+        let url = serve(vec![vec![
+            Message::text(r#"{"result":null,"id":1}"#),
+            Message::Ping(vec![1].into()),
+            trade("101.5"),
+        ]])
+        .await;
+
+        let mut stream = WsSource::<BinanceTrade>::connect(&url).await.unwrap();
+        let idle = Duration::from_secs(5);
+        let first = WsSource::<BinanceTrade>::next_message(&mut stream, idle)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.price.to_string(), "101.5");
+        assert!(
+            WsSource::<BinanceTrade>::next_message(&mut stream, idle)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streams_prices_and_reconnects() {
+        let url = serve(vec![
+            vec![trade("1.0"), trade("2.0")],
+            vec![trade("not-a-price"), trade("3.0"), trade("4.0")],
+        ])
+        .await;
+
+        let source = WsSource::<BinanceTrade>::new(url, "BTCUSDT".into());
+        let handles = RpcManager::spawn(
+            Box::new(source),
+            &StatsConfig::new(2),
+            fast_retry(),
+            BreakerPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let (_, reader, status) = &handles[0];
+
+        // Seeded with the first price, then 3 more across two connections
+        timeout(Duration::from_secs(5), async {
+            while reader.read().version < 3 {
+                sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("stream did not deliver all prices");
+
+        let snap = reader.read().data;
+        assert_eq!(snap.max, 4.0);
+        assert_eq!(snap.min, 1.0);
+        assert_eq!(snap.data_point, 4);
+        assert_eq!(snap.sma, 3.5);
+        assert!(status.last_success_at().is_some());
+        assert!(status.last_error().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_silent_stream_is_reconnected() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut silent = accept_async(tcp).await.unwrap();
+            silent.send(trade("1.0")).await.unwrap();
+            // Keeps the first connection open without sending anything
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(tcp).await.unwrap();
+            ws.send(trade("2.0")).await.unwrap();
+            std::future::pending::<()>().await;
+            drop(silent);
+        });
+
+        let source = WsSource::<BinanceTrade>::new(
+            format!("ws://{addr}/ws/btcusdt@trade"),
+            "BTCUSDT".into(),
+        )
+        .with_idle_timeout(Duration::from_millis(100));
+        let handles = RpcManager::spawn(
+            Box::new(source),
+            &StatsConfig::new(2),
+            fast_retry(),
+            BreakerPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let (_, reader, status) = &handles[0];
+
+        timeout(Duration::from_secs(5), async {
+            while reader.read().version < 1 {
+                sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("silent stream was not reconnected");
+        assert_eq!(reader.read().data.max, 2.0);
+        assert!(status.last_error().unwrap().contains("no message"));
+    }
+}